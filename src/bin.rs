@@ -1,61 +1,503 @@
 #![deny(unsafe_code, nonstandard_style)]
 
-use anyhow::Result;
-use async_std::path::PathBuf;
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use async_std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use clap::{
+    parser::ValueSource, ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand,
+};
+#[cfg(feature = "remote")]
+use craftql::remote::{fetch_schema, remote_path, Header};
 use craftql::{
-    state::{GraphQL, State},
+    budget::{check_budget, Budget, BudgetViolation},
+    config::{Config, DEFAULT_WRAP_WIDTH, INLINE_SCHEMA_PATH},
+    diff::{diff, Change, Criticality},
+    error::Error,
+    formatter::{format_files, DefinitionOrder, FormatOptions},
+    lint::{lint, LintFinding},
+    operations::{link_operations, unused_fields},
+    schema::Schema,
+    snapshot::{drift, Drift, Snapshot},
+    state::{
+        wrap_list, AnalysisOptions, CompositionIssue, DeadReference, DocsCoverage, Entity,
+        FileSummary, Format, Granularity, GraphQL, GraphQLType, GroupBy, ImageFormat,
+        Implementation, ImportViolation, InputUsage, Layout, MergeConflict, MissingReport,
+        NodeIndices, Ownership, PathStyle, PossibleType, ScalarUsage, ScatteredType, SchemaGraph,
+        SortBy, State, Subgraph, UnknownDirectives, UnusedFields, WalkSummary,
+    },
     utils::{
-        find_and_print_neighbors, find_and_print_orphans, find_node, get_files,
-        populate_graph_from_ast, print_missing_definitions,
+        bridges, build_graph, build_subgraph, collapse_extensions, collect_files,
+        composition_issues, dead_references, dependency_tree, depth_metrics, docs_coverage,
+        empty_types, extensions_of, external_dependencies, file_graph, file_graph_snapshot,
+        get_node, glob_to_regex, implementors, import_violations, index_nodes, input_usage,
+        interfaces_of, is_blank_source, member_of, merge_extensions, missing_definitions_by_name,
+        missing_definitions_json, missing_definitions_report, neighborhood, neighbors,
+        nodes_matching, orphans, ownership, possible_types, render_cytoscape, render_d2,
+        render_dot_layered, render_dot_wrapped, render_entity_json, render_file_dot, render_gexf,
+        render_gvjson, render_html, render_json, render_jsonl, render_yaml, resolve_node,
+        scalar_usage, scattered_types, schema_definitions, select_nodes, sort_listed_by,
+        split_external_definitions, split_unknown_directives, style_path, subgraph_between,
+        summarize_by_file, unused_directives, validate_sources, Sortable,
     },
 };
-use petgraph::{
-    dot::{Config, Dot},
-    Direction,
+use petgraph::{dot, Direction};
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{ErrorKind, Write},
+    process::{exit, Stdio},
 };
 
+/// Exit codes of the binary, see the README.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ExitReason {
+    /// Everything went fine.
+    Success = 0,
+    /// Generic error, e.g. an unreadable path or an invalid schema.
+    Error = 1,
+    /// The requested analysis reported findings, e.g. orphans.
+    ValidationFindings = 2,
+    /// No GraphQL file found.
+    EmptyInput = 3,
+    /// The requested node can't be found or is ambiguous.
+    LookupNotFound = 4,
+}
+
+impl ExitReason {
+    /// Map the number of findings of an analysis to an exit reason.
+    fn from_findings(count: usize) -> Self {
+        if count == 0 {
+            ExitReason::Success
+        } else {
+            ExitReason::ValidationFindings
+        }
+    }
+}
+
+impl From<&anyhow::Error> for ExitReason {
+    fn from(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<Error>() {
+            Some(Error::EmptyInput(_)) | Some(Error::EmptySchema(_)) => ExitReason::EmptyInput,
+            Some(Error::AmbiguousNode(..)) | Some(Error::NodeNotFound(_)) => {
+                ExitReason::LookupNotFound
+            }
+            Some(Error::MissingDefinitions(_))
+            | Some(Error::MultipleSchemaDefinitions(_))
+            | Some(Error::UnknownDirectives(_))
+            | None => ExitReason::Error,
+        }
+    }
+}
+
+/// Subcommands, replacing the analysis of a single schema.
+#[derive(Subcommand)]
+enum Command {
+    /// Compares two schemas and classifies the changes as breaking, dangerous
+    /// or safe, exiting with the code 2 on breaking changes
+    Diff {
+        /// Path of the old schema
+        old: PathBuf,
+
+        /// Path of the new schema
+        new: PathBuf,
+
+        /// Exits successfully despite breaking changes
+        #[clap(long)]
+        allow_breaking: bool,
+    },
+    /// Writes or checks a snapshot of the analysis, to detect schema drift
+    Snapshot {
+        #[clap(subcommand)]
+        action: SnapshotAction,
+    },
+    /// Rewrites the schema files in canonical form, only the leading comments
+    /// being kept, the other ones failing the rewrite unless dropped
+    Fmt {
+        /// Path of the schema, defaults to the path of the configuration file
+        path: Option<PathBuf>,
+
+        /// Lists the files which would change without writing them, exiting
+        /// with the code 2 if any
+        #[clap(long)]
+        check: bool,
+
+        /// Order of the definitions, defaults to name
+        ///
+        /// - source
+        /// - name
+        /// - kind
+        #[clap(long, verbatim_doc_comment, default_value = "name")]
+        order: DefinitionOrder,
+
+        /// Line width above which the arguments are wrapped, one per line
+        #[clap(long, default_value_t = 80)]
+        width: usize,
+
+        /// Drops the comments following the leading ones instead of failing
+        #[clap(long)]
+        drop_comments: bool,
+    },
+}
+
+/// Actions on a snapshot file.
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Writes the snapshot of a schema to a file
+    Write {
+        /// Path of the snapshot file, e.g. schema.lock
+        file: PathBuf,
+
+        /// Path of the schema, defaults to the path of the configuration file
+        path: Option<PathBuf>,
+    },
+    /// Compares the snapshot of a schema to a file, exiting with the code 2 on
+    /// drift
+    Check {
+        /// Path of the snapshot file, e.g. schema.lock
+        file: PathBuf,
+
+        /// Path of the schema, defaults to the path of the configuration file
+        path: Option<PathBuf>,
+    },
+}
+
 #[derive(Parser)]
-#[clap(about, author, version)]
+#[clap(
+    about,
+    author,
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    group(ArgGroup::new("depth_target").args(["tree", "ownership"]).multiple(true)),
+    group(ArgGroup::new("node_target").args(["node", "nodes"]).multiple(true))
+)]
 struct Opts {
-    /// Path to get files from
-    path: PathBuf,
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// Path to get files from, defaults to the path of the configuration file
+    path: Option<PathBuf>,
+
+    /// Gets files from the paths listed in a manifest, one per line, relative
+    /// to the manifest (blank lines and # comments are ignored)
+    #[clap(long)]
+    paths_from: Option<PathBuf>,
+
+    /// Analyzes an inline schema instead of files
+    #[clap(long, conflicts_with_all = ["path", "paths_from"])]
+    schema: Option<String>,
+
+    /// Fetches the schema of a GraphQL endpoint via introspection instead of
+    /// files
+    #[cfg(feature = "remote")]
+    #[clap(long, conflicts_with_all = ["path", "paths_from", "schema"])]
+    url: Option<String>,
+
+    /// Header sent to --url, as "Name: value", can be repeated
+    #[cfg(feature = "remote")]
+    #[clap(long, requires = "url")]
+    header: Vec<Header>,
+
+    /// Checks the composition of federated subgraphs, provided as name=path
+    /// and comma separated, instead of analyzing a single schema
+    #[clap(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["path", "paths_from", "schema"]
+    )]
+    subgraphs: Vec<Subgraph>,
 
     /// Finds and displays incoming dependencies of a node
     #[clap(short, long)]
     incoming_dependencies: Option<String>,
 
+    /// Finds and displays both the incoming and outgoing dependencies of a
+    /// node, in two sections
+    #[clap(long)]
+    dependencies: Option<String>,
+
     /// Finds and displays missing definition(s)
     #[clap(short, long)]
     missing_definitions: bool,
 
+    /// Directive(s) not reported as missing on top of the built-in ones, e.g.
+    /// link,key
+    #[clap(long, value_delimiter = ',')]
+    known_directives: Vec<String>,
+
+    /// Name(s) not reported as missing definitions, e.g. the types stitched
+    /// at runtime
+    #[clap(long, value_delimiter = ',')]
+    ignore_missing: Vec<String>,
+
+    /// Severity of the applied directives without definition, reported as
+    /// missing definitions if not set
+    ///
+    /// - error: fails the run
+    /// - warn: prints them
+    /// - ignore: skips them
+    #[clap(long, verbatim_doc_comment)]
+    unknown_directives: Option<UnknownDirectives>,
+
+    /// Groups the missing definition(s) by node or by missing name
+    ///
+    /// - missing
+    /// - node
+    #[clap(
+        long,
+        default_value = "node",
+        requires = "missing_definitions",
+        verbatim_doc_comment
+    )]
+    group_by: GroupBy,
+
+    /// Displays the full definitions of the nodes referencing missing
+    /// definitions instead of one line per node
+    #[clap(long, requires = "missing_definitions")]
+    verbose: bool,
+
+    /// Prints the number of files read and the files skipped because of
+    /// their extension on stderr
+    #[clap(short = 'v', long)]
+    walk_summary: bool,
+
+    /// Finds and displays the bridges, i.e. the edges whose removal would
+    /// disconnect the graph treated as undirected
+    #[clap(long)]
+    bridges: bool,
+
+    /// Checks that the graphql-import style comments, e.g.
+    /// # import Foo from "./foo.graphql", reference analyzed files defining
+    /// the imported names
+    #[clap(long)]
+    check_imports: bool,
+
+    /// Finds and displays the fields referencing types unreachable from the
+    /// root operation types
+    #[clap(long)]
+    dead_references: bool,
+
+    /// Finds and displays the objects, interfaces and input objects defined
+    /// without fields, unless an extension adds some
+    #[clap(long)]
+    empty_types: bool,
+
+    /// Finds and displays the types whose definition and extensions span
+    /// more distinct files than --scattered-threshold
+    #[clap(long)]
+    scattered: bool,
+
+    /// Number of distinct files a type spans over to be scattered
+    #[clap(
+        long,
+        value_name = "THRESHOLD",
+        requires = "scattered",
+        default_value_t = 2
+    )]
+    scattered_threshold: usize,
+
+    /// Displays the type definitions lacking a description, and the fields
+    /// with --granularity field, with the coverage percentage
+    #[clap(long)]
+    docs_coverage: bool,
+
+    /// Fails when the documentation coverage percentage is under this one
+    #[clap(long, requires = "docs_coverage")]
+    fail_under: Option<f64>,
+
+    /// Resolves the Apollo Federation field sets (@key, @provides and
+    /// @requires) as dependencies, reports the definitions owned by other
+    /// subgraphs separately and never reports the entities (@key) as orphans
+    #[clap(long)]
+    federation: bool,
+
+    /// Reads the .json files found in the walked directories as introspection
+    /// results, a .json file given as a path always being read as such
+    #[clap(long)]
+    introspection: bool,
+
+    /// Displays the definitions of each file and their number of
+    /// dependencies defined in another file
+    #[clap(long)]
+    files: bool,
+
+    /// Renders the graph of the files instead, their edges weighted by the
+    /// number of dependencies between them, as dot, json or yaml
+    #[clap(long)]
+    file_graph: bool,
+
+    /// Displays the definitions of each directory under the path and their
+    /// dependencies on the other directories, see --depth
+    #[clap(long)]
+    ownership: bool,
+
+    /// Lints the schema, rules can be toggled in the configuration
+    #[clap(long)]
+    lint: bool,
+
+    /// Checks the schema against the size limits of the [budget] table of the
+    /// configuration, overridden by the --max-* flags, exiting with the code 2
+    /// when exceeded
+    #[clap(long)]
+    budget: bool,
+
+    /// Maximum number of types checked by --budget
+    #[clap(long, requires = "budget")]
+    max_types: Option<usize>,
+
+    /// Maximum number of fields per object, interface or input object checked
+    /// by --budget
+    #[clap(long, requires = "budget")]
+    max_fields_per_type: Option<usize>,
+
+    /// Maximum number of arguments per field checked by --budget
+    #[clap(long, requires = "budget")]
+    max_arguments_per_field: Option<usize>,
+
+    /// Maximum number of values per enum checked by --budget
+    #[clap(long, requires = "budget")]
+    max_enum_values: Option<usize>,
+
+    /// Maximum number of members per union checked by --budget
+    #[clap(long, requires = "budget")]
+    max_union_members: Option<usize>,
+
+    /// Links the named operations and fragments of the executable documents
+    /// found in a path to the types, and fields with --granularity field,
+    /// they select
+    #[clap(long)]
+    operations: Option<PathBuf>,
+
+    /// Finds and displays the fields of the objects and interfaces selected by
+    /// no operation nor fragment of --operations, grouped by type
+    #[clap(long, requires = "operations")]
+    unused_fields: bool,
+
+    /// Fails when an unused field is found
+    #[clap(long, requires = "unused_fields")]
+    fail_if_any: bool,
+
     /// Finds and displays orphan(s) node(s)
     #[clap(short = 'O', long)]
     orphans: bool,
 
+    /// Only reports orphan(s) node(s) of the provided GraphQL type(s), the
+    /// directives being only reported with --only directive
+    #[clap(long, requires = "orphans")]
+    only: Vec<GraphQL>,
+
+    /// Finds and displays the declared directives which are never used
+    #[clap(long)]
+    unused_directives: bool,
+
     /// Finds and displays outgoing dependencies of a node
     #[clap(short, long)]
     outgoing_dependencies: Option<String>,
 
+    /// Displays the transitive dependencies of a node defined in another
+    /// file than the node
+    #[clap(long)]
+    external_deps: Option<String>,
+
+    /// Displays the objects and interfaces implementing an interface,
+    /// extensions included
+    #[clap(long)]
+    implementors: Option<String>,
+
+    /// Displays the interfaces implemented by an object or interface,
+    /// extensions included
+    #[clap(long)]
+    interfaces_of: Option<String>,
+
+    /// Displays the possible types of an interface or a union, i.e. the
+    /// implementing objects or the members, extensions included
+    #[clap(long)]
+    possible_types: Option<String>,
+
+    /// Displays the interfaces and unions an object is a possible type of,
+    /// extensions included
+    #[clap(long)]
+    member_of: Option<String>,
+
+    /// Displays the fields, arguments and input fields referencing the custom
+    /// scalars, or only the given one, with their counts
+    #[clap(long, value_name = "NAME", num_args = 0..=1)]
+    scalar_usage: Option<Option<String>>,
+
+    /// Displays the field arguments accepting an input object, directly or
+    /// nested inside other input objects
+    #[clap(long)]
+    input_usage: Option<String>,
+
+    /// Displays the dependencies of a node as a tree
+    #[clap(long)]
+    tree: Option<String>,
+
+    /// Displays the dependents of the node of the tree instead
+    #[clap(long, requires = "tree")]
+    invert: bool,
+
+    /// Limits the depth of the dependency tree, or sets the number of path
+    /// components of the owners of --ownership, defaults to 1
+    #[clap(long, requires = "depth_target")]
+    depth: Option<usize>,
+
+    /// Displays the maximum nesting depth of the types reachable from each
+    /// root operation type, the types of a cycle counting as one level
+    #[clap(long)]
+    depth_metrics: bool,
+
+    /// Root type(s) of the depth metrics, defaults to the root operation types
+    #[clap(long, requires = "depth_metrics")]
+    root: Vec<String>,
+
     /// Finds and displays one node
     #[clap(short, long)]
     node: Option<String>,
 
+    /// Matches node(s) case-insensitively
+    #[clap(long)]
+    ignore_case: bool,
+
+    /// Displays the extensions of the nodes after their definition, with
+    /// --node and --nodes
+    #[clap(long)]
+    with_extensions: bool,
+
+    /// Displays only the SDL of the nodes, without the path header, with
+    /// --node and --nodes
+    #[clap(long, requires = "node_target")]
+    raw: bool,
+
     /// Finds and displays multiple nodes
     #[clap(short = 'N', long)]
     nodes: Vec<String>,
 
+    /// Finds and displays one node as JSON
+    #[clap(long)]
+    entity_json: Option<String>,
+
+    /// Finds and displays the nodes whose name matches a regex
+    #[clap(long)]
+    nodes_matching: Option<Regex>,
+
     /// Filter nodes by GraphQL type(s)
     ///
     /// - directive
     /// - enum
     /// - enum_extension
+    /// - field
+    /// - fragment
     /// - input_object
     /// - input_object_extension
     /// - interface
     /// - interface_extension
     /// - object
     /// - object_extension
+    /// - operation
     /// - scalar
     /// - scalar_extension
     /// - schema
@@ -63,73 +505,1964 @@ struct Opts {
     /// - union_extension
     #[clap(short, long, verbatim_doc_comment)]
     filter: Vec<GraphQL>,
+
+    /// Excludes nodes by GraphQL type(s), takes precedence over the filter
+    #[clap(long)]
+    exclude_type: Vec<GraphQL>,
+
+    /// Output format of the graph, defaults to dot
+    ///
+    /// - cytoscape
+    /// - d2
+    /// - dot
+    /// - gexf
+    /// - gvjson
+    /// - html
+    /// - json
+    /// - jsonl
+    /// - yaml
+    #[clap(long, verbatim_doc_comment)]
+    format: Option<Format>,
+
+    /// Renders the graph to an image with the dot binary of Graphviz, found
+    /// on the PATH
+    ///
+    /// - png
+    /// - svg
+    #[clap(
+        long,
+        requires = "output",
+        conflicts_with = "format",
+        verbatim_doc_comment
+    )]
+    render: Option<ImageFormat>,
+
+    /// Path of the image written by --render
+    #[clap(long, requires = "render")]
+    output: Option<PathBuf>,
+
+    /// Graphviz layout engine of --render, defaults to dot
+    ///
+    /// - dot
+    /// - fdp
+    /// - neato
+    #[clap(long, requires = "render", verbatim_doc_comment)]
+    layout: Option<Layout>,
+
+    /// Ordering of the listed nodes, e.g. the orphans, the dependencies of a
+    /// node or the implementors, defaults to name
+    ///
+    /// - degree
+    /// - name
+    /// - path
+    /// - type
+    #[clap(long, default_value = "name", verbatim_doc_comment)]
+    sort_by: SortBy,
+
+    /// Wraps the dependency lists of the dot labels and of the missing
+    /// definitions reports at a width, 0 disabling the wrapping
+    #[clap(long, default_value_t = DEFAULT_WRAP_WIDTH)]
+    wrap: usize,
+
+    /// Groups the nodes of the dot output in ranks by dependency depth, the
+    /// roots at the top, a cyclic graph being rendered unranked
+    #[clap(long)]
+    layered: bool,
+
+    /// Granularity of the nodes, fields being nodes between their type and the
+    /// types they reference with field
+    ///
+    /// - field
+    /// - type
+    #[clap(long, default_value = "type", verbatim_doc_comment)]
+    granularity: Granularity,
+
+    /// Renders only the nodes whose name matches one of the globs, e.g.
+    /// 'User*', and their transitive dependencies
+    #[clap(long, value_delimiter = ',', value_parser = parse_glob)]
+    select: Vec<Regex>,
+
+    /// Renders only the nodes lying on a path from a node to another one
+    /// following the dependencies, e.g. Query,User
+    #[clap(long, value_name = "FROM,TO", value_delimiter = ',')]
+    between: Vec<String>,
+
+    /// Folds the extension nodes into their base node in the rendered graph
+    #[clap(long)]
+    collapse_extensions: bool,
+
+    /// Merges the type extensions into their base definition, reporting the
+    /// members already defined
+    #[clap(long)]
+    merge_extensions: bool,
+
+    /// Adds the referenced built-in scalars as nodes, drawn dashed in the dot
+    /// format
+    #[clap(long)]
+    include_builtins: bool,
+
+    /// Prints only the number of nodes, orphans, missing definitions or lint
+    /// findings
+    #[clap(long)]
+    count_only: bool,
+
+    /// Prints nothing when no view is requested instead of rendering the
+    /// graph, e.g. to only validate the schema
+    #[clap(long)]
+    no_default_render: bool,
+
+    /// Only parses the files without building the graph, reporting all the
+    /// parse errors
+    #[clap(long)]
+    validate_sdl: bool,
+
+    /// Fails the run on an invalid schema, i.e. with several schema
+    /// definitions or missing definitions not ignored by --ignore-missing
+    #[clap(long)]
+    strict: bool,
+
+    /// Maximum number of files read concurrently, defaults to 16
+    #[clap(long)]
+    jobs: Option<usize>,
+
+    /// Prints the canonicalized absolute paths of the files
+    #[clap(long, conflicts_with = "relative_to")]
+    absolute_paths: bool,
+
+    /// Prints the paths of the files relative to a directory
+    #[clap(long)]
+    relative_to: Option<PathBuf>,
+
+    /// Caches the parsed files in a directory, e.g. .craftql-cache
+    #[clap(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Loads the configuration from a file instead of looking for a
+    /// craftql.toml file
+    #[clap(long, conflicts_with = "no_config")]
+    config: Option<PathBuf>,
+
+    /// Ignores any craftql.toml configuration file
+    #[clap(long)]
+    no_config: bool,
 }
 
-#[async_std::main]
-async fn main() -> Result<()> {
-    let opts: Opts = Opts::parse();
-    let state = State::default();
+/// Arguments of the modes never reading the raw SDL of the entities, i.e.
+/// rendering the graph, counting its nodes or listing the usages of a type,
+/// and of the inputs. Any other argument keeps the raw SDL.
+const RAW_FREE_ARGS: &[&str] = &[
+    "absolute_paths",
+    "between",
+    "cache_dir",
+    "collapse_extensions",
+    "config",
+    "count_only",
+    "exclude_type",
+    "file_graph",
+    "filter",
+    "format",
+    "granularity",
+    "header",
+    "include_builtins",
+    "input_usage",
+    "jobs",
+    "layered",
+    "layout",
+    "no_config",
+    "no_default_render",
+    "output",
+    "path",
+    "paths_from",
+    "relative_to",
+    "render",
+    "scalar_usage",
+    "schema",
+    "select",
+    "url",
+    "validate_sdl",
+    "wrap",
+];
+
+/// Check if the raw SDL of the entities is needed, i.e. if an argument is
+/// passed outside of the raw-free ones.
+fn needs_raw(matches: &ArgMatches) -> bool {
+    matches.ids().any(|id| {
+        matches.value_source(id.as_str()) != Some(ValueSource::DefaultValue)
+            && !RAW_FREE_ARGS.contains(&id.as_str())
+    })
+}
+
+impl Opts {
+    /// Check if a schema input is provided, the subcommands bring their own.
+    fn has_input(&self) -> bool {
+        #[cfg(feature = "remote")]
+        if self.url.is_some() {
+            return true;
+        }
+
+        self.path.is_some()
+            || self.paths_from.is_some()
+            || self.schema.is_some()
+            || !self.subgraphs.is_empty()
+    }
+}
+
+async fn run(mut opts: Opts, keep_raw: bool) -> Result<ExitReason> {
+    // Look for a configuration file from the path, the manifest or the
+    // current directory.
+    let config = if opts.no_config {
+        Config::default()
+    } else if let Some(ref config_path) = opts.config {
+        Config::load(config_path).await?
+    } else {
+        let start = opts
+            .path
+            .clone()
+            .or_else(|| opts.paths_from.clone())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        match Config::find(&start).await {
+            Some(config_path) => Config::load(&config_path).await?,
+            None => Config::default(),
+        }
+    };
+
+    // CLI flags take precedence over the configuration.
+    if opts.command.is_none() && !opts.has_input() {
+        match config.path {
+            Some(ref path) => opts.path = Some(PathBuf::from(path)),
+            None => bail!("No path provided, pass one or set it in the configuration file"),
+        }
+    }
+
+    // The unused fields are found on the field nodes.
+    let granularity = match opts.unused_fields {
+        true => Granularity::Field,
+        false => opts.granularity,
+    };
+    let mut options = AnalysisOptions::from(&config)
+        .granularity(granularity)
+        .exclude_types(opts.exclude_type.clone())
+        .known_directives(opts.known_directives.clone())
+        .ignore_missing(opts.ignore_missing.clone());
+
+    if !opts.filter.is_empty() {
+        options = options.filter(opts.filter.clone());
+    }
+
+    if let Some(ref cache_dir) = opts.cache_dir {
+        options = options.cache_dir(cache_dir.clone());
+    }
+
+    if let Some(jobs) = opts.jobs {
+        options = options.jobs(jobs);
+    }
+
+    if opts.absolute_paths {
+        options = options.path_style(PathStyle::Absolute);
+    }
+
+    if let Some(ref relative_to) = opts.relative_to {
+        options = options.path_style(PathStyle::RelativeTo(relative_to.clone()));
+    }
+
+    if opts.include_builtins {
+        options = options.treat_builtins_as_nodes(true);
+    }
+
+    if opts.federation {
+        options = options.federation(true);
+    }
+
+    if opts.introspection {
+        options = options.introspection(true);
+    }
+
+    if opts.operations.is_some() {
+        options = options.operations(true);
+    }
+
+    options = options.keep_raw(keep_raw);
+
+    if let Some(Command::Diff {
+        ref old,
+        ref new,
+        allow_breaking,
+    }) = opts.command
+    {
+        // The changes are read from the raw SDL.
+        let options = options.keep_raw(true);
+        let changes = diff(
+            Schema::from_path(old.clone(), &options).await?.graph(),
+            Schema::from_path(new.clone(), &options).await?.graph(),
+        );
+
+        return Ok(match print_changes(changes) > 0 && !allow_breaking {
+            true => ExitReason::ValidationFindings,
+            false => ExitReason::Success,
+        });
+    }
+
+    if let Some(Command::Fmt {
+        ref path,
+        check,
+        order,
+        width,
+        drop_comments,
+    }) = opts.command
+    {
+        let path = match path
+            .clone()
+            .or_else(|| config.path.clone().map(PathBuf::from))
+        {
+            Some(path) => path,
+            None => bail!("No path provided, pass one or set it in the configuration file"),
+        };
+        let format_options = FormatOptions {
+            order,
+            width,
+            drop_comments,
+        };
+        let formatted_files = format_files(path, &options, &format_options).await?;
+
+        if !check {
+            for (path, contents) in &formatted_files {
+                fs::write(path, contents)
+                    .await
+                    .with_context(|| format!("Failed to write {}", path.to_string_lossy()))?;
+            }
+        }
+
+        let count = print_formatted_files(formatted_files, check);
+
+        return Ok(match check {
+            true => ExitReason::from_findings(count),
+            false => ExitReason::Success,
+        });
+    }
+
+    if let Some(Command::Snapshot { ref action }) = opts.command {
+        let (file, path) = match action {
+            SnapshotAction::Write { file, path } | SnapshotAction::Check { file, path } => {
+                (file, path)
+            }
+        };
+        let path = match path
+            .clone()
+            .or_else(|| config.path.clone().map(PathBuf::from))
+        {
+            Some(path) => path,
+            None => bail!("No path provided, pass one or set it in the configuration file"),
+        };
+        // The content hashes are computed from the raw SDL.
+        let schema = Schema::from_path(path, &options.keep_raw(true)).await?;
+        let snapshot = Snapshot::from_graph(schema.graph());
+
+        if let SnapshotAction::Write { .. } = action {
+            fs::write(file, snapshot.render()?)
+                .await
+                .with_context(|| format!("Failed to write {}", file.to_string_lossy()))?;
+
+            return Ok(ExitReason::Success);
+        }
+
+        let contents = fs::read_to_string(file)
+            .await
+            .with_context(|| format!("Failed to read {}", file.to_string_lossy()))?;
+        let drifts = drift(
+            &Snapshot::parse(&contents)
+                .with_context(|| format!("Invalid {}", file.to_string_lossy()))?,
+            &snapshot,
+        );
+
+        return Ok(ExitReason::from_findings(print_drifts(file, drifts)));
+    }
+
+    let format = opts.format.or(config.format).unwrap_or(Format::Dot);
+
+    // The ranks only exist in the dot output, also piped into Graphviz.
+    if opts.layered && format != Format::Dot && opts.render.is_none() {
+        bail!("--layered only applies to the dot format");
+    }
+
+    if !opts.subgraphs.is_empty() {
+        let mut subgraphs = vec![];
+
+        for subgraph in &opts.subgraphs {
+            subgraphs.push(build_subgraph(subgraph, &options).await?);
+        }
+
+        let issues = composition_issues(&subgraphs);
+        let count = if opts.count_only {
+            println!("{}", issues.len());
+
+            issues.len()
+        } else {
+            print_composition_issues(issues)
+        };
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    // Kept to style the path of --ownership as the stored ones.
+    let path_style = options.stored_path_style().clone();
+    let state = State::with_options(options);
     let shared_data = state.shared;
-    let shared_data_for_populate = shared_data.clone();
 
-    // Walk the GraphQL files and populate the data.
-    get_files(opts.path, shared_data.files).await?;
+    let mut paths = opts.path.iter().cloned().collect::<Vec<PathBuf>>();
+
+    if let Some(ref manifest) = opts.paths_from {
+        paths.extend(read_manifest(manifest).await?);
+    }
+
+    // Either the path or the manifest, used to report an empty input.
+    let input = opts.path.as_ref().or(opts.paths_from.as_ref());
+
+    if let Some(input) = input {
+        let mut summary = WalkSummary::default();
+
+        // Walk the GraphQL files and populate the data.
+        for path in paths {
+            summary.extend(collect_files(path, &shared_data).await?);
+        }
+
+        if opts.walk_summary {
+            print_walk_summary(&summary);
+        }
+
+        // The walk fails on a path without files, an empty manifest lists
+        // no path to walk.
+        if shared_data.files.lock().await.is_empty() {
+            return Err(Error::EmptyInput(input.clone()).into());
+        }
+    }
+
+    if let Some(ref schema) = opts.schema {
+        // Bypass the files and use a synthetic path.
+        shared_data
+            .files
+            .lock()
+            .await
+            .insert(PathBuf::from(INLINE_SCHEMA_PATH), schema.clone());
+    }
+
+    #[cfg(feature = "remote")]
+    if let Some(ref url) = opts.url {
+        // Bypass the files and use a synthetic path, the URL may end with
+        // .json while the SDL is already converted.
+        let sdl = fetch_schema(url, &opts.header).await?;
+
+        shared_data
+            .files
+            .lock()
+            .await
+            .insert(PathBuf::from(remote_path(url)), sdl);
+    }
+
+    // Distinguish an empty schema from an analysis without any finding.
+    if shared_data
+        .files
+        .lock()
+        .await
+        .values()
+        .all(|contents| is_blank_source(contents))
+    {
+        let path = input
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(INLINE_SCHEMA_PATH));
+
+        return Err(Error::EmptySchema(path).into());
+    }
+
+    if opts.validate_sdl {
+        let errors = validate_sources(&*shared_data.files.lock().await);
+
+        if opts.count_only {
+            println!("{}", errors.len());
+        } else {
+            print_parse_errors(&errors);
+        }
+
+        return Ok(ExitReason::from_findings(errors.len()));
+    }
 
     // Populate the graph.
-    populate_graph_from_ast(
-        shared_data_for_populate.dependencies,
-        shared_data_for_populate.files,
-        &opts.filter,
-        shared_data_for_populate.graph,
-        shared_data_for_populate.missing_definitions,
-    )
-    .await?;
+    build_graph(&shared_data).await?;
+
+    if let Some(ref operations) = opts.operations {
+        link_operations(operations.clone(), &shared_data).await?;
+    }
+
+    let graph = &*shared_data.graph.lock().await;
+    let node_indices = &*shared_data.node_indices.lock().await;
+
+    if opts.strict {
+        let schema_definitions = schema_definitions(graph);
+
+        if schema_definitions.len() > 1 {
+            let locations = schema_definitions
+                .iter()
+                .map(|entity| entity.location())
+                .collect();
+
+            return Err(Error::MultipleSchemaDefinitions(locations).into());
+        }
+    }
+
+    if let Some(severity) = opts.unknown_directives {
+        let mut missing_definitions = shared_data.missing_definitions.lock().await;
+        let (types, directives) = split_unknown_directives(&missing_definitions);
+
+        // The directives are no longer reported as missing definitions.
+        *missing_definitions = types;
+
+        let count = directives.values().map(Vec::len).sum::<usize>();
+
+        match severity {
+            UnknownDirectives::Ignore => {}
+            UnknownDirectives::Warn => {
+                print_missing_reports(
+                    missing_definitions_report(graph, &directives),
+                    "applies unknown",
+                );
+            }
+            UnknownDirectives::Error if count > 0 => {
+                print_missing_reports(
+                    missing_definitions_report(graph, &directives),
+                    "applies unknown",
+                );
+
+                return Err(Error::UnknownDirectives(count).into());
+            }
+            UnknownDirectives::Error => {}
+        }
+    }
+
+    // The unknown directives are no longer missing definitions when split
+    // above with --unknown-directives.
+    if opts.strict {
+        let missing_definitions = &*shared_data.missing_definitions.lock().await;
+        let count = missing_definitions.values().map(Vec::len).sum::<usize>();
+
+        if count > 0 {
+            print_missing_reports(
+                missing_definitions_report(graph, missing_definitions),
+                "references undefined",
+            );
+
+            return Err(Error::MissingDefinitions(count).into());
+        }
+    }
+
+    // The stored graph is left untouched.
+    let merged;
+    let (graph, node_indices) = if opts.merge_extensions {
+        let (graph, conflicts) = merge_extensions(graph);
+
+        print_merge_conflicts(conflicts);
+        merged = (index_nodes(&graph), graph);
+
+        (&merged.1, &merged.0)
+    } else {
+        (graph, node_indices)
+    };
+    let resolve = |node: &str| resolve_node(node, node_indices, opts.ignore_case);
+    let listing = Listing {
+        sort_by: opts.sort_by,
+        format,
+        graph,
+        node_indices,
+    };
 
     if let Some(ref node) = opts.incoming_dependencies {
-        find_and_print_neighbors(node, shared_data.graph.clone(), Direction::Incoming).await?;
+        print_neighbors(&resolve(node)?, &listing, Direction::Incoming)?;
 
-        return Ok(());
+        return Ok(ExitReason::Success);
     }
 
     if let Some(ref node) = opts.outgoing_dependencies {
-        find_and_print_neighbors(node, shared_data.graph.clone(), Direction::Outgoing).await?;
+        print_neighbors(&resolve(node)?, &listing, Direction::Outgoing)?;
 
-        return Ok(());
+        return Ok(ExitReason::Success);
     }
 
-    if let Some(ref node) = opts.node {
-        find_node(node, shared_data.graph.clone()).await?;
+    if let Some(ref node) = opts.dependencies {
+        print_neighborhood(&resolve(node)?, &listing)?;
 
-        return Ok(());
+        return Ok(ExitReason::Success);
     }
 
-    if !opts.nodes.is_empty() {
-        for ref node in opts.nodes {
-            find_node(node, shared_data.graph.clone()).await?;
-        }
+    if let Some(ref node) = opts.external_deps {
+        print_external_dependencies(&resolve(node)?, &listing)?;
 
-        return Ok(());
+        return Ok(ExitReason::Success);
     }
 
-    if opts.missing_definitions {
-        print_missing_definitions(
-            shared_data.graph.clone(),
-            shared_data.missing_definitions.clone(),
-        )
-        .await?;
+    if let Some(ref node) = opts.implementors {
+        let node = resolve(node)?;
 
-        return Ok(());
+        listing.print_json(
+            implementors(&node, graph),
+            &format!("No implementor found for {}", node),
+            print_implementations,
+        )?;
+
+        return Ok(ExitReason::Success);
     }
 
-    if opts.orphans {
-        find_and_print_orphans(shared_data.graph.clone()).await?;
+    if let Some(ref node) = opts.possible_types {
+        let node = resolve(node)?;
 
-        return Ok(());
+        listing.print_json(
+            possible_types(&node, graph),
+            &format!("No possible type found for {}", node),
+            print_possible_types,
+        )?;
+
+        return Ok(ExitReason::Success);
     }
 
-    // Render the graph without edges.
-    let graph = &*shared_data.graph.lock().await;
-    println!("\n{:?}", Dot::with_config(&graph, &[Config::EdgeNoLabel]));
+    if let Some(ref node) = opts.member_of {
+        let node = resolve(node)?;
 
-    Ok(())
+        listing.print_json(
+            member_of(&node, graph),
+            &format!("No interface or union found for {}", node),
+            print_possible_types,
+        )?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref node) = opts.scalar_usage {
+        let node = node.as_deref().map(resolve).transpose()?;
+        let empty = match node {
+            Some(ref node) => format!("No custom scalar found for {}", node),
+            None => String::from("No custom scalar found"),
+        };
+
+        listing.print_json(
+            scalar_usage(node.as_deref(), graph),
+            &empty,
+            print_scalar_usage,
+        )?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref node) = opts.input_usage {
+        let node = resolve(node)?;
+
+        listing.print_json(
+            input_usage(&node, graph),
+            &format!("No field found taking {}", node),
+            print_input_usage,
+        )?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref node) = opts.interfaces_of {
+        let node = resolve(node)?;
+
+        listing.print_json(
+            interfaces_of(&node, graph),
+            &format!("No interface implemented by {}", node),
+            print_implementations,
+        )?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    if opts.depth_metrics {
+        let roots = opts
+            .root
+            .iter()
+            .map(|root| resolve(root))
+            .collect::<Result<Vec<String>, Error>>()?;
+
+        print_depth_metrics(depth_metrics(&roots, graph, node_indices));
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref node) = opts.tree {
+        let direction = match opts.invert {
+            true => Direction::Outgoing,
+            false => Direction::Incoming,
+        };
+
+        match dependency_tree(&resolve(node)?, graph, node_indices, opts.depth, direction) {
+            Some(tree) => print!("{}", tree),
+            None => return Err(Error::NodeNotFound(node.to_owned()).into()),
+        }
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref node) = opts.node {
+        print_node(
+            &resolve(node)?,
+            graph,
+            node_indices,
+            opts.with_extensions,
+            opts.raw,
+        )?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    if !opts.nodes.is_empty() {
+        for node in &opts.nodes {
+            print_node(
+                &resolve(node)?,
+                graph,
+                node_indices,
+                opts.with_extensions,
+                opts.raw,
+            )?;
+        }
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref node) = opts.entity_json {
+        print_entity_json(&resolve(node)?, graph, node_indices)?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    if let Some(ref pattern) = opts.nodes_matching {
+        let entities = nodes_matching(pattern, graph, node_indices);
+
+        if opts.count_only {
+            println!("{}", entities.len());
+
+            return Ok(ExitReason::Success);
+        }
+
+        listing.print(
+            entities,
+            &format!("No node matching {} found", pattern),
+            print_entities,
+        );
+
+        return Ok(ExitReason::Success);
+    }
+
+    if opts.missing_definitions {
+        let (missing_definitions, external_definitions) = match opts.federation {
+            true => {
+                split_external_definitions(graph, &*shared_data.missing_definitions.lock().await)
+            }
+            false => (
+                shared_data.missing_definitions.lock().await.clone(),
+                HashMap::new(),
+            ),
+        };
+        let missing_definitions = &missing_definitions;
+        let count = match opts.group_by {
+            GroupBy::Missing if opts.count_only => {
+                missing_definitions_by_name(graph, missing_definitions).len()
+            }
+            GroupBy::Missing => print_missing_definitions_by_name(
+                missing_definitions_by_name(graph, missing_definitions),
+                opts.wrap,
+            ),
+            GroupBy::Node if opts.count_only => missing_definitions.len(),
+            GroupBy::Node if format == Format::Json => {
+                let missing_definitions = missing_definitions_json(graph, missing_definitions);
+
+                println!("{}", serde_json::to_string_pretty(&missing_definitions)?);
+
+                missing_definitions.len()
+            }
+            GroupBy::Node => print_missing_definitions(
+                missing_definitions_report(graph, missing_definitions),
+                &listing,
+                opts.verbose,
+                opts.wrap,
+            ),
+        };
+
+        if opts.count_only {
+            println!("{}", count);
+        } else if format != Format::Json {
+            print_external_definitions(
+                missing_definitions_report(graph, &external_definitions),
+                &listing,
+                opts.verbose,
+                opts.wrap,
+            );
+        }
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.lint {
+        let findings = lint(graph, node_indices, &config.lint);
+        let count = if opts.count_only {
+            println!("{}", findings.len());
+
+            findings.len()
+        } else {
+            print_lint_findings(findings)
+        };
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.budget {
+        let budget = config.budget.merge(Budget {
+            max_types: opts.max_types,
+            max_fields_per_type: opts.max_fields_per_type,
+            max_arguments_per_field: opts.max_arguments_per_field,
+            max_enum_values: opts.max_enum_values,
+            max_union_members: opts.max_union_members,
+        });
+
+        if budget.is_empty() {
+            bail!("No budget set, add a [budget] table to the configuration or a --max-* flag");
+        }
+
+        let violations = check_budget(graph, &budget);
+        let count = violations.len();
+
+        if opts.count_only {
+            println!("{}", count);
+        } else if format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(&violations)?);
+        } else {
+            print_budget_violations(violations);
+        }
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.check_imports {
+        let violations = import_violations(&*shared_data.files.lock().await, graph);
+        let count = if opts.count_only {
+            println!("{}", violations.len());
+
+            violations.len()
+        } else {
+            print_import_violations(violations)
+        };
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.empty_types {
+        let empty_types = empty_types(graph);
+        let count = if opts.count_only {
+            println!("{}", empty_types.len());
+
+            empty_types.len()
+        } else {
+            print_empty_types(empty_types)
+        };
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.scattered {
+        let scattered_types = scattered_types(graph, opts.scattered_threshold);
+
+        if opts.count_only {
+            println!("{}", scattered_types.len());
+        } else if format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(&scattered_types)?);
+        } else {
+            print_scattered_types(&scattered_types);
+        }
+
+        return Ok(ExitReason::from_findings(scattered_types.len()));
+    }
+
+    if opts.dead_references {
+        let dead_references = dead_references(graph, node_indices);
+        let count = if opts.count_only {
+            println!("{}", dead_references.len());
+
+            dead_references.len()
+        } else {
+            print_dead_references(dead_references)
+        };
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.bridges {
+        let bridges = bridges(graph);
+
+        if opts.count_only {
+            println!("{}", bridges.len());
+        } else {
+            if bridges.is_empty() {
+                eprintln!("No bridge found");
+            }
+
+            for (source, target) in bridges {
+                println!("{} -- {}", source.id, target.id);
+            }
+        }
+
+        return Ok(ExitReason::Success);
+    }
+
+    if opts.unused_fields {
+        let unused_fields = unused_fields(graph);
+        let count = unused_fields
+            .iter()
+            .map(|unused| unused.fields.len())
+            .sum::<usize>();
+
+        if opts.count_only {
+            println!("{}", count);
+        } else if format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(&unused_fields)?);
+        } else {
+            print_unused_fields(unused_fields);
+        }
+
+        return Ok(match opts.fail_if_any && count > 0 {
+            true => ExitReason::ValidationFindings,
+            false => ExitReason::Success,
+        });
+    }
+
+    if opts.docs_coverage {
+        let coverage = docs_coverage(graph);
+        let under = opts
+            .fail_under
+            .map_or(false, |fail_under| coverage.percentage() < fail_under);
+
+        if opts.count_only {
+            println!("{}", coverage.undocumented.len());
+        } else {
+            print_docs_coverage(&coverage);
+        }
+
+        return Ok(match under {
+            true => ExitReason::ValidationFindings,
+            false => ExitReason::Success,
+        });
+    }
+
+    if opts.files {
+        let summaries = summarize_by_file(graph);
+
+        if opts.count_only {
+            println!("{}", summaries.len());
+        } else {
+            print_file_summaries(summaries);
+        }
+
+        return Ok(ExitReason::Success);
+    }
+
+    if opts.ownership {
+        // The owners are relative to the path.
+        let root = style_path(opts.path.clone().unwrap_or_default(), &path_style).await?;
+        let ownership = ownership(graph, &root, opts.depth.unwrap_or(1));
+
+        if opts.count_only {
+            println!("{}", ownership.len());
+        } else if format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(&ownership)?);
+        } else {
+            print_ownership(ownership);
+        }
+
+        return Ok(ExitReason::Success);
+    }
+
+    if opts.orphans {
+        let mut orphans = orphans(graph, &opts.only);
+
+        // Federated entities are resolved through the router.
+        if opts.federation {
+            orphans.retain(|orphan| !orphan.is_federated());
+        }
+
+        if opts.count_only {
+            println!("{}", orphans.len());
+
+            return Ok(ExitReason::from_findings(orphans.len()));
+        }
+
+        let count = listing.print(orphans, "No orphan node found", print_entities);
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    if opts.unused_directives {
+        let unused_directives = unused_directives(graph);
+
+        if opts.count_only {
+            println!("{}", unused_directives.len());
+
+            return Ok(ExitReason::from_findings(unused_directives.len()));
+        }
+
+        let count = listing.print(
+            unused_directives,
+            "No unused directive found",
+            print_entities,
+        );
+
+        return Ok(ExitReason::from_findings(count));
+    }
+
+    let selected;
+    let graph = if opts.select.is_empty() {
+        graph
+    } else {
+        selected = select_nodes(graph, &opts.select);
+        &selected
+    };
+
+    let between;
+    let graph = match opts.between.as_slice() {
+        [] => graph,
+        [from, to] => {
+            let (from, to) = (resolve(from)?, resolve(to)?);
+
+            between = subgraph_between(&from, &to, graph, node_indices);
+            &between
+        }
+        _ => bail!("--between expects two nodes, e.g. Query,User"),
+    };
+
+    let collapsed;
+    let graph = if opts.collapse_extensions {
+        collapsed = collapse_extensions(graph);
+        &collapsed
+    } else {
+        graph
+    };
+
+    if opts.file_graph {
+        let file_graph = file_graph(graph);
+
+        if opts.count_only {
+            println!("{}", file_graph.node_count());
+
+            return Ok(ExitReason::Success);
+        }
+
+        match format {
+            Format::Dot => println!("\n{}", render_file_dot(&file_graph)),
+            Format::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&file_graph_snapshot(&file_graph))?
+            ),
+            Format::Yaml => print!(
+                "{}",
+                serde_yaml::to_string(&file_graph_snapshot(&file_graph))?
+            ),
+            _ => bail!("The file graph can only be rendered as dot, json or yaml"),
+        }
+
+        return Ok(ExitReason::Success);
+    }
+
+    if opts.count_only {
+        println!("{}", graph.node_count());
+
+        return Ok(ExitReason::Success);
+    }
+
+    // The analysis and the --strict checks are done, their errors are
+    // already reported, only the rendering is skipped.
+    if opts.no_default_render {
+        return Ok(ExitReason::Success);
+    }
+
+    if graph.node_count() == 0 {
+        eprintln!("No node left to render, check the filters");
+    }
+
+    if let (Some(image_format), Some(output)) = (opts.render, &opts.output) {
+        let dot = render_graph_dot(graph, opts.wrap, opts.layered);
+
+        render_image(&dot, image_format, opts.layout.unwrap_or_default(), output)?;
+
+        return Ok(ExitReason::Success);
+    }
+
+    match format {
+        // Render the graph without edges.
+        Format::Cytoscape => println!("{}", render_cytoscape(graph)?),
+        Format::GvJson => println!("{}", render_gvjson(graph)?),
+        Format::Html => print!("{}", render_html(graph)?),
+        Format::D2 => print!("{}", render_d2(graph)),
+        Format::Dot => println!("\n{}", render_graph_dot(graph, opts.wrap, opts.layered)),
+        Format::Gexf => print!("{}", render_gexf(graph)),
+        Format::Json => println!("{}", render_json(graph)?),
+        Format::Jsonl => print!("{}", render_jsonl(graph)?),
+        Format::Yaml => print!("{}", render_yaml(graph)?),
+    }
+
+    Ok(ExitReason::Success)
+}
+
+/// Render the whole graph as dot format, optionally in rank layers.
+fn render_graph_dot(graph: &SchemaGraph, wrap: usize, layered: bool) -> String {
+    match layered {
+        true => render_dot_layered(graph, &[dot::Config::EdgeNoLabel], wrap),
+        false => render_dot_wrapped(graph, &[dot::Config::EdgeNoLabel], wrap),
+    }
+}
+
+/// Pipe the dot text into the dot binary of Graphviz, writing the image to
+/// the output path.
+fn render_image(dot: &str, image_format: ImageFormat, layout: Layout, output: &Path) -> Result<()> {
+    let child = std::process::Command::new("dot")
+        .arg(format!("-T{}", image_format.as_str()))
+        .arg(format!("-K{}", layout.as_str()))
+        .arg("-o")
+        .arg(output.as_os_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(error) if error.kind() == ErrorKind::NotFound => bail!(
+            "The dot binary of Graphviz can't be found on the PATH, install it with e.g. \
+             `apt install graphviz`, `brew install graphviz` or `choco install graphviz`"
+        ),
+        Err(error) => return Err(error).context("Failed to run dot"),
+    };
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("Failed to open the stdin of dot")?;
+
+    // Write from another thread while stderr is drained, dot blocking on a
+    // full stderr pipe otherwise. Dropping stdin closes it, letting dot
+    // render. A failed write is only reported after the exit status, dot's
+    // stderr being more helpful.
+    let (written, result) = std::thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(dot.as_bytes()));
+        let result = child.wait_with_output();
+
+        (
+            writer
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+            result,
+        )
+    });
+    let result = result.context("Failed to run dot")?;
+
+    if !result.status.success() {
+        bail!(
+            "dot failed with {}: {}",
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+
+    written.context("Failed to pipe the graph into dot")
+}
+
+/// Parse a glob of --select.
+fn parse_glob(glob: &str) -> std::result::Result<Regex, String> {
+    glob_to_regex(glob).map_err(|error| error.to_string())
+}
+
+/// Read the paths listed in a manifest, relative to its directory, skipping
+/// the blank lines and the comments.
+async fn read_manifest(manifest: &Path) -> Result<Vec<PathBuf>> {
+    let contents = fs::read_to_string(manifest)
+        .await
+        .with_context(|| format!("Failed to read {}", manifest.to_string_lossy()))?;
+    let dir = manifest.parent().unwrap_or_else(|| Path::new(""));
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| dir.join(line))
+        .collect())
+}
+
+/// Print a node or fail with Error::NodeNotFound.
+fn print_node(
+    node: &str,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+    with_extensions: bool,
+    raw: bool,
+) -> Result<()> {
+    // The raw SDL can be piped as is.
+    let print = |entity: &Entity| match raw {
+        true => print!("{}", entity.raw),
+        false => println!("{}", entity),
+    };
+
+    match get_node(node, graph, node_indices) {
+        Some(entity) => {
+            print(&entity);
+
+            // An extension has no extensions of its own.
+            if with_extensions && entity.extends.is_none() {
+                for extension in extensions_of(&entity.name, graph) {
+                    print(&extension);
+                }
+            }
+
+            Ok(())
+        }
+        None => Err(Error::NodeNotFound(node.to_owned()).into()),
+    }
+}
+
+/// Print a node as JSON.
+fn print_entity_json(node: &str, graph: &SchemaGraph, node_indices: &NodeIndices) -> Result<()> {
+    match get_node(node, graph, node_indices) {
+        Some(entity) => {
+            println!("{}", render_entity_json(&entity)?);
+
+            Ok(())
+        }
+        None => Err(Error::NodeNotFound(node.to_owned()).into()),
+    }
+}
+
+/// Lists printed by the views, sorted with --sort-by.
+struct Listing<'a> {
+    sort_by: SortBy,
+    format: Format,
+    graph: &'a SchemaGraph,
+    node_indices: &'a NodeIndices,
+}
+
+impl Listing<'_> {
+    /// Sort a list with --sort-by.
+    fn sort<T: Sortable>(&self, items: &mut [T]) {
+        sort_listed_by(items, self.sort_by, self.graph, self.node_indices);
+    }
+
+    /// Sort a list then print it, the message going to stderr when empty.
+    /// Return the number of items.
+    fn print<T: Sortable>(&self, mut items: Vec<T>, empty: &str, print: fn(&[T])) -> usize {
+        self.sort(&mut items);
+
+        if items.is_empty() {
+            eprintln!("{}", empty);
+        }
+
+        print(&items);
+
+        items.len()
+    }
+
+    /// Sort a list then print it, as is with --format json.
+    fn print_json<T: Sortable + Serialize>(
+        &self,
+        mut items: Vec<T>,
+        empty: &str,
+        print: fn(&[T]),
+    ) -> Result<usize> {
+        if self.format != Format::Json {
+            return Ok(self.print(items, empty, print));
+        }
+
+        self.sort(&mut items);
+        println!("{}", serde_json::to_string_pretty(&items)?);
+
+        Ok(items.len())
+    }
+}
+
+/// Print a node followed by its neighbors.
+fn print_neighbors(node: &str, listing: &Listing<'_>, direction: Direction) -> Result<()> {
+    let (graph, node_indices) = (listing.graph, listing.node_indices);
+
+    // Ensure that the node exists!
+    print_node(node, graph, node_indices, false, false)?;
+
+    listing.print(
+        neighbors(node, graph, node_indices, direction),
+        &format!("No dependencies found for node {}", node),
+        print_entities,
+    );
+
+    Ok(())
+}
+
+/// Print a node followed by its transitive dependencies defined in another
+/// file.
+fn print_external_dependencies(node: &str, listing: &Listing<'_>) -> Result<()> {
+    let (graph, node_indices) = (listing.graph, listing.node_indices);
+
+    // Ensure that the node exists!
+    print_node(node, graph, node_indices, false, false)?;
+
+    listing.print(
+        external_dependencies(node, graph, node_indices),
+        &format!("No external dependencies found for node {}", node),
+        print_entities,
+    );
+
+    Ok(())
+}
+
+/// Print the entities, e.g. the nodes of a list.
+fn print_entities(entities: &[Entity]) {
+    for entity in entities {
+        println!("{}", entity);
+    }
+}
+
+/// Print the implements clauses with the location of their declaration.
+fn print_implementations(implementations: &[Implementation]) {
+    for implementation in implementations {
+        println!(
+            "{} {} {} implements {}",
+            implementation.location(),
+            implementation.graphql,
+            implementation.implementor,
+            implementation.interface
+        );
+    }
+}
+
+/// Print the possible types with the location of the declaration, in SDL
+/// terms.
+fn print_possible_types(possible_types: &[PossibleType]) {
+    for possible_type in possible_types {
+        match possible_type.graphql.base_type() {
+            Some(GraphQLType::Union) => println!(
+                "{} {} {} = {}",
+                possible_type.location(),
+                possible_type.graphql,
+                possible_type.abstract_type,
+                possible_type.object
+            ),
+            _ => println!(
+                "{} {} {} implements {}",
+                possible_type.location(),
+                possible_type.graphql,
+                possible_type.object,
+                possible_type.abstract_type
+            ),
+        }
+    }
+}
+
+/// Print the field arguments accepting an input object, followed by the
+/// input fields nesting it for the indirect ones.
+fn print_input_usage(usages: &[InputUsage]) {
+    for usage in usages {
+        let via = if usage.indirect {
+            format!(" (indirect via {})", usage.via.join(" > "))
+        } else {
+            String::new()
+        };
+
+        println!(
+            "{} {}({}:): {}{}",
+            usage.location(),
+            usage.field,
+            usage.argument,
+            usage.value_type,
+            via
+        );
+    }
+}
+
+/// Print the custom scalars followed by their references.
+fn print_scalar_usage(usages: &[ScalarUsage]) {
+    for usage in usages {
+        println!(
+            "{} {}: {} reference(s) in {} type(s)",
+            usage.location(),
+            usage.scalar,
+            usage.count,
+            usage.types
+        );
+
+        for reference in &usage.references {
+            println!(
+                "  {} {}: {}",
+                reference.location(),
+                reference.coordinate,
+                reference.value_type
+            );
+        }
+    }
+}
+
+/// Print a node followed by its incoming and outgoing dependencies.
+fn print_neighborhood(node: &str, listing: &Listing<'_>) -> Result<()> {
+    let (graph, node_indices) = (listing.graph, listing.node_indices);
+
+    // Ensure that the node exists!
+    print_node(node, graph, node_indices, false, false)?;
+
+    let (mut incoming, mut outgoing) = neighborhood(node, graph, node_indices);
+
+    listing.sort(&mut incoming);
+    listing.sort(&mut outgoing);
+
+    if incoming.is_empty() && outgoing.is_empty() {
+        eprintln!("No dependencies found for node {}", node);
+    }
+
+    for (title, dependencies) in [
+        ("Incoming dependencies", incoming),
+        ("Outgoing dependencies", outgoing),
+    ] {
+        println!("\n## {}", title);
+        print_entities(&dependencies);
+    }
+
+    Ok(())
+}
+
+/// Print missing definitions and return the number of nodes reported.
+fn print_missing_definitions(
+    mut reports: Vec<MissingReport>,
+    listing: &Listing<'_>,
+    verbose: bool,
+    wrap: usize,
+) -> usize {
+    listing.sort(&mut reports);
+
+    if !verbose {
+        print_reports_by_file(
+            &reports,
+            ("missing definition", "missing definitions"),
+            "missing",
+            wrap,
+        );
+
+        return reports.len();
+    }
+
+    for report in &reports {
+        println!(
+            "\n# {} {} not defined in:{}",
+            report.missing.join(", "),
+            if report.missing.len() == 1 {
+                "is"
+            } else {
+                "are"
+            },
+            report.entity,
+        );
+    }
+
+    reports.len()
+}
+
+/// Print the definitions owned by other federated subgraphs.
+fn print_external_definitions(
+    mut reports: Vec<MissingReport>,
+    listing: &Listing<'_>,
+    verbose: bool,
+    wrap: usize,
+) {
+    listing.sort(&mut reports);
+
+    if !verbose {
+        print_reports_by_file(
+            &reports,
+            (
+                "definition owned by another subgraph",
+                "definitions owned by another subgraph",
+            ),
+            "external",
+            wrap,
+        );
+
+        return;
+    }
+
+    for report in &reports {
+        println!(
+            "\n# {} {} owned by another subgraph, referenced in:{}",
+            report.missing.join(", "),
+            if report.missing.len() == 1 {
+                "is"
+            } else {
+                "are"
+            },
+            report.entity,
+        );
+    }
+}
+
+/// Print the reports grouped by file, sorted by path then name, each file
+/// followed by one line per node, e.g. Foo: missing Bar, Baz.
+fn print_reports_by_file(
+    reports: &[MissingReport],
+    (singular, plural): (&str, &str),
+    label: &str,
+    wrap: usize,
+) {
+    let mut reports_by_file: BTreeMap<&Path, Vec<&MissingReport>> = BTreeMap::new();
+
+    for report in reports {
+        reports_by_file
+            .entry(&report.entity.path)
+            .or_default()
+            .push(report);
+    }
+
+    // The reports keep their order within a file, by name or --sort-by.
+    for (path, reports) in reports_by_file {
+        let count = reports
+            .iter()
+            .map(|report| report.missing.len())
+            .sum::<usize>();
+
+        println!(
+            "\n# {} ({} {})",
+            path.to_string_lossy(),
+            count,
+            if count == 1 { singular } else { plural }
+        );
+
+        for report in reports {
+            let prefix = format!("{}: {} ", report.entity.name, label);
+
+            println!(
+                "{}{}",
+                prefix,
+                wrap_list(&report.missing, wrap, prefix.chars().count())
+            );
+        }
+    }
+}
+
+/// Print lint findings with their location and return their number.
+fn print_lint_findings(findings: Vec<LintFinding>) -> usize {
+    if findings.is_empty() {
+        eprintln!("No lint finding");
+    }
+
+    for finding in &findings {
+        let location = finding.entity.location();
+
+        println!("{} [{}] {}", location, finding.rule, finding.message);
+    }
+
+    findings.len()
+}
+
+/// Print the summary of the walk on stderr, not to mix it with the output.
+fn print_walk_summary(summary: &WalkSummary) {
+    eprintln!(
+        "Read {} file(s), skipped {}",
+        summary.read,
+        summary.skipped.len()
+    );
+
+    for path in &summary.skipped {
+        eprintln!("Skipped {}", path.to_string_lossy());
+    }
+}
+
+/// Print the budget violations with their location and return their number.
+fn print_budget_violations(violations: Vec<BudgetViolation>) -> usize {
+    if violations.is_empty() {
+        eprintln!("No budget exceeded");
+    }
+
+    for violation in &violations {
+        match &violation.location {
+            Some(location) => println!("{} [{}] {}", location, violation.budget, violation.message),
+            None => println!("[{}] {}", violation.budget, violation.message),
+        }
+    }
+
+    violations.len()
+}
+
+/// Print the changes between two schemas and return the number of breaking
+/// ones.
+fn print_changes(changes: Vec<Change>) -> usize {
+    if changes.is_empty() {
+        eprintln!("No change found");
+    }
+
+    for change in &changes {
+        println!("[{}] {}", change.criticality, change.message);
+    }
+
+    changes
+        .iter()
+        .filter(|change| change.criticality == Criticality::Breaking)
+        .count()
+}
+
+/// Print the drifts from a snapshot file and return their number.
+fn print_drifts(file: &Path, drifts: Vec<Drift>) -> usize {
+    if drifts.is_empty() {
+        eprintln!("No drift found from {}", file.to_string_lossy());
+    } else {
+        eprintln!(
+            "The schema drifted from {}, run craftql snapshot write to update it",
+            file.to_string_lossy()
+        );
+    }
+
+    for drift in &drifts {
+        println!("{}", drift);
+    }
+
+    drifts.len()
+}
+
+/// Print the depth of each root.
+fn print_depth_metrics(depth_metrics: Vec<(String, usize)>) {
+    if depth_metrics.is_empty() {
+        eprintln!("No root type found");
+    }
+
+    for (root, depth) in depth_metrics {
+        println!("{}: {}", root, depth);
+    }
+}
+
+/// Print the paths of the formatted files and return their number.
+fn print_formatted_files(formatted_files: Vec<(PathBuf, String)>, check: bool) -> usize {
+    if formatted_files.is_empty() {
+        eprintln!("All the files are formatted");
+    } else if check {
+        eprintln!("Some files aren't formatted, run craftql fmt to format them");
+    }
+
+    for (path, _) in &formatted_files {
+        println!("{}", path.to_string_lossy());
+    }
+
+    formatted_files.len()
+}
+
+/// Print the import violations with their location and return their number.
+fn print_import_violations(violations: Vec<ImportViolation>) -> usize {
+    if violations.is_empty() {
+        eprintln!("No import violation found");
+    }
+
+    for violation in &violations {
+        println!(
+            "{}:{} {}",
+            violation.path.to_string_lossy(),
+            violation.line,
+            violation.message
+        );
+    }
+
+    violations.len()
+}
+
+/// Print the types without fields with their location and return their
+/// number.
+fn print_empty_types(empty_types: Vec<Entity>) -> usize {
+    if empty_types.is_empty() {
+        eprintln!("No empty type found");
+    }
+
+    for entity in &empty_types {
+        let location = entity.location();
+
+        println!(
+            "{} {} {} has no fields",
+            location, entity.graphql, entity.name
+        );
+    }
+
+    empty_types.len()
+}
+
+/// Print the definitions of each owner and their dependencies on the other
+/// owners.
+fn print_ownership(ownership: Vec<Ownership>) {
+    for (position, ownership) in ownership.iter().enumerate() {
+        if position > 0 {
+            println!();
+        }
+
+        println!(
+            "{} ({} {}): {}",
+            ownership.owner,
+            ownership.types.len(),
+            if ownership.types.len() == 1 {
+                "type"
+            } else {
+                "types"
+            },
+            ownership
+                .types
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
+        for (label, owners) in [
+            ("depends on", &ownership.dependencies),
+            ("depended on by", &ownership.dependents),
+        ] {
+            for (owner, names) in owners {
+                println!(
+                    "  {} {}: {}",
+                    label,
+                    owner,
+                    names.iter().cloned().collect::<Vec<String>>().join(", ")
+                );
+            }
+        }
+    }
+}
+
+/// Print the missing definitions on stderr, the label telling how they are
+/// referenced, e.g. the applied directives without definition.
+fn print_missing_reports(reports: Vec<MissingReport>, label: &str) {
+    for report in reports {
+        let location = report.entity.location();
+
+        eprintln!(
+            "{} {} {} {}",
+            location,
+            report.entity.name,
+            label,
+            report
+                .missing
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Print the parse errors of the files.
+fn print_parse_errors(errors: &[(PathBuf, String)]) {
+    if errors.is_empty() {
+        eprintln!("No parse error found");
+    }
+
+    for (path, error) in errors {
+        println!("{}: {}", path.to_string_lossy(), error);
+    }
+}
+
+/// Print the types scattered across files with their paths.
+fn print_scattered_types(scattered_types: &[ScatteredType]) {
+    if scattered_types.is_empty() {
+        eprintln!("No scattered type found");
+    }
+
+    for scattered_type in scattered_types {
+        println!(
+            "{} ({} files): {}",
+            scattered_type.name,
+            scattered_type.paths.len(),
+            scattered_type.paths.join(", ")
+        );
+    }
+}
+
+/// Print the members skipped when merging the extensions.
+fn print_merge_conflicts(conflicts: Vec<MergeConflict>) {
+    for conflict in &conflicts {
+        let location = conflict.location();
+
+        eprintln!(
+            "{} {} is already defined, skipped",
+            location, conflict.coordinate
+        );
+    }
+}
+
+/// Print the dead references with their location and return their number.
+fn print_dead_references(dead_references: Vec<DeadReference>) -> usize {
+    if dead_references.is_empty() {
+        eprintln!("No dead reference found");
+    }
+
+    for dead_reference in &dead_references {
+        let location = dead_reference.entity.location();
+
+        println!(
+            "{} {} references unreachable {}",
+            location, dead_reference.field, dead_reference.target
+        );
+    }
+
+    dead_references.len()
+}
+
+/// Print the unused fields with their location, grouped by type.
+fn print_unused_fields(unused_fields: Vec<UnusedFields>) {
+    if unused_fields.is_empty() {
+        eprintln!("No unused field found");
+    }
+
+    for unused in unused_fields {
+        println!("\n# {}", unused.name);
+
+        for field in unused.fields {
+            let location = field.location();
+
+            println!("{} {}", location, field.name);
+        }
+    }
+}
+
+/// Print the entities lacking a description with their location, followed by
+/// the coverage.
+fn print_docs_coverage(coverage: &DocsCoverage) {
+    for entity in &coverage.undocumented {
+        let location = entity.location();
+
+        println!("{} {} lacks a description", location, entity.id);
+    }
+
+    println!(
+        "Docs coverage: {}/{} ({:.2}%)",
+        coverage.documented,
+        coverage.total(),
+        coverage.percentage()
+    );
+}
+
+/// Print the composition issues, naming the subgraph of every entity, and
+/// return their number.
+fn print_composition_issues(issues: Vec<CompositionIssue>) -> usize {
+    if issues.is_empty() {
+        eprintln!("No composition issue found");
+    }
+
+    let location =
+        |subgraph: &str, entity: &Entity| format!("[{}] {}", subgraph, entity.location());
+
+    for issue in &issues {
+        match issue {
+            CompositionIssue::Conflict(name, entities) => println!(
+                "{} is defined without @key nor @shareable in {}",
+                name,
+                entities
+                    .iter()
+                    .map(|(subgraph, entity)| location(subgraph, entity))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            CompositionIssue::Unsatisfied(subgraph, entity, name) => println!(
+                "{} {} references {} which no subgraph defines",
+                location(subgraph, entity),
+                entity.id,
+                name
+            ),
+        }
+    }
+
+    issues.len()
+}
+
+/// Print the definitions of each file with their number of cross-file
+/// dependencies.
+fn print_file_summaries(summaries: Vec<FileSummary>) {
+    for summary in summaries {
+        println!(
+            "{} ({} cross-file {}): {}",
+            summary.path.to_string_lossy(),
+            summary.cross_file_dependencies,
+            if summary.cross_file_dependencies == 1 {
+                "dependency"
+            } else {
+                "dependencies"
+            },
+            summary.definitions.join(", ")
+        );
+    }
+}
+
+/// Print missing definitions grouped by name, most referenced first, and
+/// return the number of missing names reported.
+fn print_missing_definitions_by_name(
+    missing_definitions_by_name: HashMap<String, Vec<Entity>>,
+    wrap: usize,
+) -> usize {
+    let mut missing_definitions_by_name = missing_definitions_by_name
+        .into_iter()
+        .collect::<Vec<(String, Vec<Entity>)>>();
+
+    missing_definitions_by_name.sort_by(|(a_name, a_entities), (b_name, b_entities)| {
+        b_entities
+            .len()
+            .cmp(&a_entities.len())
+            .then_with(|| a_name.cmp(b_name))
+    });
+
+    for (name, entities) in &mut missing_definitions_by_name {
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let prefix = format!("{} is referenced by: ", name);
+
+        println!(
+            "{}{}",
+            prefix,
+            wrap_list(
+                &entities
+                    .iter()
+                    .map(|entity| format!("{} ({})", entity.name, entity.path.to_string_lossy()))
+                    .collect::<Vec<String>>(),
+                wrap,
+                prefix.chars().count()
+            )
+        );
+    }
+
+    missing_definitions_by_name.len()
+}
+
+/// Print the clap error and exit, --help and --version succeeding while the
+/// usage errors exit with ExitReason::Error, clap's own code colliding with
+/// ExitReason::ValidationFindings.
+fn exit_with_usage(error: clap::Error) -> ! {
+    let exit_reason = if error.use_stderr() {
+        ExitReason::Error
+    } else {
+        ExitReason::Success
+    };
+
+    // Nothing more can be reported if the terminal is gone.
+    let _ = error.print();
+
+    exit(exit_reason as i32);
+}
+
+#[async_std::main]
+async fn main() {
+    let matches = Opts::command()
+        .try_get_matches()
+        .unwrap_or_else(|error| exit_with_usage(error));
+    let opts = Opts::from_arg_matches(&matches).unwrap_or_else(|error| exit_with_usage(error));
+    let exit_reason = match run(opts, needs_raw(&matches)).await {
+        Ok(exit_reason) => exit_reason,
+        Err(error) => {
+            let exit_reason = ExitReason::from(&error);
+
+            if exit_reason == ExitReason::Error {
+                eprintln!("Error: {:?}", error);
+            } else {
+                eprintln!("{}", error);
+            }
+
+            exit_reason
+        }
+    };
+
+    exit(exit_reason as i32);
 }
@@ -0,0 +1,359 @@
+use crate::{
+    lint::{get_fields, parse_definition},
+    state::{Entity, SchemaGraph},
+};
+
+use graphql_parser::schema::{Definition, TypeDefinition, TypeExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum number of types of the schema.
+pub const MAX_TYPES: &str = "max_types";
+
+/// Maximum number of fields of an object, interface or input object.
+pub const MAX_FIELDS_PER_TYPE: &str = "max_fields_per_type";
+
+/// Maximum number of arguments of a field.
+pub const MAX_ARGUMENTS_PER_FIELD: &str = "max_arguments_per_field";
+
+/// Maximum number of values of an enum.
+pub const MAX_ENUM_VALUES: &str = "max_enum_values";
+
+/// Maximum number of members of a union.
+pub const MAX_UNION_MEMBERS: &str = "max_union_members";
+
+/// Size limits of a schema, the unset ones being unchecked. The types are
+/// merged with their extensions.
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Budget {
+    /// Maximum number of types, the built-in scalars excluded.
+    pub max_types: Option<usize>,
+    /// Maximum number of fields of an object, interface or input object.
+    pub max_fields_per_type: Option<usize>,
+    /// Maximum number of arguments of a field.
+    pub max_arguments_per_field: Option<usize>,
+    /// Maximum number of values of an enum.
+    pub max_enum_values: Option<usize>,
+    /// Maximum number of members of a union.
+    pub max_union_members: Option<usize>,
+}
+
+impl Budget {
+    /// Check if no limit is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Budget::default()
+    }
+
+    /// Override the limits with the ones set in another budget.
+    pub fn merge(self, other: Budget) -> Self {
+        Budget {
+            max_types: other.max_types.or(self.max_types),
+            max_fields_per_type: other.max_fields_per_type.or(self.max_fields_per_type),
+            max_arguments_per_field: other
+                .max_arguments_per_field
+                .or(self.max_arguments_per_field),
+            max_enum_values: other.max_enum_values.or(self.max_enum_values),
+            max_union_members: other.max_union_members.or(self.max_union_members),
+        }
+    }
+}
+
+/// Budget exceeded by the schema or one of its definitions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetViolation {
+    /// Name of the budget, e.g. max_fields_per_type.
+    pub budget: &'static str,
+    /// Coordinate of the definition, e.g. Foo or Foo.bar, None for the whole
+    /// schema.
+    pub coordinate: Option<String>,
+    /// Location of the definition as path:line:column, None for the whole
+    /// schema.
+    pub location: Option<String>,
+    /// Actual number.
+    pub actual: usize,
+    /// Limit of the budget.
+    pub limit: usize,
+    /// Description of the violation.
+    pub message: String,
+}
+
+/// Members of a type merged with the ones of its extensions, located on its
+/// definition or on its first extension without one.
+#[derive(Default)]
+struct Members<'a> {
+    entity: Option<&'a Entity>,
+    names: BTreeSet<String>,
+}
+
+impl<'a> Members<'a> {
+    /// Add the members of a definition or an extension.
+    fn extend(&mut self, entity: &'a Entity, names: impl IntoIterator<Item = String>) {
+        if self.entity.is_none() || !entity.graphql.is_extension() {
+            self.entity = Some(entity);
+        }
+
+        self.names.extend(names);
+    }
+}
+
+/// Report the members exceeding a limit.
+fn check_members(
+    budget: &'static str,
+    limit: Option<usize>,
+    members: &BTreeMap<String, Members<'_>>,
+    unit: &str,
+    violations: &mut Vec<BudgetViolation>,
+) {
+    let limit = match limit {
+        Some(limit) => limit,
+        None => return,
+    };
+
+    for (coordinate, members) in members {
+        let actual = members.names.len();
+
+        if actual > limit {
+            violations.push(BudgetViolation {
+                budget,
+                coordinate: Some(coordinate.clone()),
+                location: members.entity.map(Entity::location),
+                actual,
+                limit,
+                message: format!(
+                    "{} has {} {}, over the budget of {}",
+                    coordinate, actual, unit, limit
+                ),
+            });
+        }
+    }
+}
+
+/// Check the schema against a budget, reporting every exceeded limit with
+/// the actual number, the violations being grouped by budget and sorted by
+/// coordinate.
+pub fn check_budget(graph: &SchemaGraph, budget: &Budget) -> Vec<BudgetViolation> {
+    let mut violations = vec![];
+    let mut types = BTreeSet::new();
+    let mut fields: BTreeMap<String, Members<'_>> = BTreeMap::new();
+    let mut arguments: BTreeMap<String, Members<'_>> = BTreeMap::new();
+    let mut enum_values: BTreeMap<String, Members<'_>> = BTreeMap::new();
+    let mut union_members: BTreeMap<String, Members<'_>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        if entity.is_built_in() || entity.graphql.base_type().is_none() {
+            continue;
+        }
+
+        if !entity.graphql.is_extension() {
+            types.insert(entity.name.clone());
+        }
+
+        let definition = match parse_definition(entity) {
+            Some(definition) => definition,
+            None => continue,
+        };
+
+        match definition {
+            Definition::TypeDefinition(TypeDefinition::Enum(enum_type)) => enum_values
+                .entry(entity.name.clone())
+                .or_default()
+                .extend(entity, enum_type.values.into_iter().map(|value| value.name)),
+            Definition::TypeExtension(TypeExtension::Enum(enum_type)) => enum_values
+                .entry(entity.name.clone())
+                .or_default()
+                .extend(entity, enum_type.values.into_iter().map(|value| value.name)),
+            Definition::TypeDefinition(TypeDefinition::Union(union)) => union_members
+                .entry(entity.name.clone())
+                .or_default()
+                .extend(entity, union.types),
+            Definition::TypeExtension(TypeExtension::Union(union)) => union_members
+                .entry(entity.name.clone())
+                .or_default()
+                .extend(entity, union.types),
+            Definition::TypeDefinition(TypeDefinition::InputObject(input_object)) => {
+                fields.entry(entity.name.clone()).or_default().extend(
+                    entity,
+                    input_object.fields.into_iter().map(|field| field.name),
+                )
+            }
+            Definition::TypeExtension(TypeExtension::InputObject(input_object)) => {
+                fields.entry(entity.name.clone()).or_default().extend(
+                    entity,
+                    input_object.fields.into_iter().map(|field| field.name),
+                )
+            }
+            definition => {
+                for field in get_fields(definition) {
+                    fields
+                        .entry(entity.name.clone())
+                        .or_default()
+                        .extend(entity, [field.name.clone()]);
+                    arguments
+                        .entry(format!("{}.{}", entity.name, field.name))
+                        .or_default()
+                        .extend(
+                            entity,
+                            field.arguments.into_iter().map(|argument| argument.name),
+                        );
+                }
+            }
+        }
+    }
+
+    if let Some(limit) = budget.max_types.filter(|limit| types.len() > *limit) {
+        violations.push(BudgetViolation {
+            budget: MAX_TYPES,
+            coordinate: None,
+            location: None,
+            actual: types.len(),
+            limit,
+            message: format!(
+                "The schema has {} types, over the budget of {}",
+                types.len(),
+                limit
+            ),
+        });
+    }
+
+    check_members(
+        MAX_FIELDS_PER_TYPE,
+        budget.max_fields_per_type,
+        &fields,
+        "fields",
+        &mut violations,
+    );
+    check_members(
+        MAX_ARGUMENTS_PER_FIELD,
+        budget.max_arguments_per_field,
+        &arguments,
+        "arguments",
+        &mut violations,
+    );
+    check_members(
+        MAX_ENUM_VALUES,
+        budget.max_enum_values,
+        &enum_values,
+        "values",
+        &mut violations,
+    );
+    check_members(
+        MAX_UNION_MEMBERS,
+        budget.max_union_members,
+        &union_members,
+        "members",
+        &mut violations,
+    );
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema::Schema;
+    use async_std::path::PathBuf;
+
+    fn check_sources(contents: &str, budget: &Budget) -> Vec<BudgetViolation> {
+        let schema = Schema::from_sources(vec![(
+            PathBuf::from("some_path/schema.gql"),
+            String::from(contents),
+        )])
+        .unwrap();
+
+        check_budget(schema.graph(), budget)
+    }
+
+    fn messages(violations: &[BudgetViolation]) -> Vec<&str> {
+        violations
+            .iter()
+            .map(|violation| violation.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn check_budget_limits() {
+        let contents = r#"
+            type Query { user(id: ID!, name: String, email: String): User }
+            type User { id: ID! name: String }
+            extend type User { email: String }
+            input UserInput { name: String email: String }
+            enum Role { ADMIN MEMBER }
+            extend enum Role { GUEST }
+            union Actor = User
+            extend union Actor = Query
+            "#;
+
+        assert!(check_sources(contents, &Budget::default()).is_empty());
+        assert_eq!(
+            messages(&check_sources(
+                contents,
+                &Budget {
+                    max_types: Some(4),
+                    ..Budget::default()
+                }
+            )),
+            vec!["The schema has 5 types, over the budget of 4"]
+        );
+        // The extensions are merged into their type.
+        assert_eq!(
+            messages(&check_sources(
+                contents,
+                &Budget {
+                    max_fields_per_type: Some(2),
+                    max_arguments_per_field: Some(2),
+                    max_enum_values: Some(2),
+                    max_union_members: Some(1),
+                    ..Budget::default()
+                }
+            )),
+            vec![
+                "User has 3 fields, over the budget of 2",
+                "Query.user has 3 arguments, over the budget of 2",
+                "Role has 3 values, over the budget of 2",
+                "Actor has 2 members, over the budget of 1",
+            ]
+        );
+
+        let violations = check_sources(
+            contents,
+            &Budget {
+                max_fields_per_type: Some(2),
+                ..Budget::default()
+            },
+        );
+
+        // The violation is located on the definition.
+        assert_eq!(
+            violations[0].location.as_deref(),
+            Some("some_path/schema.gql:3:13")
+        );
+        assert_eq!((violations[0].actual, violations[0].limit), (3, 2));
+    }
+
+    #[test]
+    fn check_budget_merge() {
+        let config = Budget {
+            max_types: Some(10),
+            max_enum_values: Some(5),
+            ..Budget::default()
+        };
+        let flags = Budget {
+            max_enum_values: Some(3),
+            ..Budget::default()
+        };
+
+        assert!(Budget::default().is_empty());
+        assert_eq!(
+            config.merge(flags),
+            Budget {
+                max_types: Some(10),
+                max_enum_values: Some(3),
+                ..Budget::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::state::{Entity, Extraction};
+
+use anyhow::{Context, Result};
+use async_std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Format of the cache entries, bump it when the extraction changes.
+pub const CACHE_FORMAT: u32 = 12;
+
+/// Cached extraction of a file.
+#[derive(Deserialize, Serialize)]
+struct CacheEntry {
+    format: u32,
+    version: String,
+    hash: String,
+    extraction: Extraction,
+    entities: Vec<Entity>,
+}
+
+/// On-disk cache of the entities extracted from each file, keyed by the hash
+/// of its contents.
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+// Caches are the same when they share their directory.
+impl PartialEq for Cache {
+    fn eq(&self, other: &Self) -> bool {
+        self.dir == other.dir
+    }
+}
+
+/// Hash some bytes with 64-bit FNV-1a, stable across runs and platforms.
+pub(crate) fn hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{:016x}", hash)
+}
+
+impl Cache {
+    /// Method to create a new Cache stored in a directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache {
+            dir: dir.into(),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of files loaded from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of files extracted again.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Path of the entry of a file for an extraction variant.
+    fn entry_path(&self, path: &Path, extraction: Extraction) -> PathBuf {
+        let fields = if extraction.fields { "-fields" } else { "" };
+        let federation = if extraction.federation {
+            "-federation"
+        } else {
+            ""
+        };
+        let operations = if extraction.operations {
+            "-operations"
+        } else {
+            ""
+        };
+
+        self.dir.join(format!(
+            "{}{}{}{}.json",
+            hash(path.to_string_lossy().as_bytes()),
+            fields,
+            federation,
+            operations
+        ))
+    }
+
+    /// Return the cached entities of a file, or extract and cache them when
+    /// the file is unknown, changed or cached by another craftql version. Each
+    /// extraction variant is cached separately.
+    pub fn get_or_extract(
+        &self,
+        path: &Path,
+        contents: &str,
+        extraction: Extraction,
+        extract: impl FnOnce() -> Result<Vec<Entity>>,
+    ) -> Result<Vec<Entity>> {
+        let entry_path = self.entry_path(path, extraction);
+        let hash = hash(contents.as_bytes());
+        let cached = fs::read(&entry_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheEntry>(&bytes).ok())
+            .filter(|entry| {
+                entry.format == CACHE_FORMAT
+                    && entry.version == env!("CARGO_PKG_VERSION")
+                    && entry.hash == hash
+                    && entry.extraction == extraction
+            });
+
+        if let Some(entry) = cached {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+
+            return Ok(entry.entities);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let entry = CacheEntry {
+            format: CACHE_FORMAT,
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            hash,
+            extraction,
+            entities: extract()?,
+        };
+
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&entry_path, serde_json::to_vec(&entry)?))
+            .with_context(|| format!("Failed to write {}", entry_path.to_string_lossy()))?;
+
+        Ok(entry.entities)
+    }
+}
@@ -1,2 +1,144 @@
+use crate::{
+    budget::Budget,
+    state::{AnalysisOptions, Format, GraphQL},
+};
+
+use anyhow::{Context, Result};
+use async_std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
 /// List of allowed file extensions.
 pub const ALLOWED_EXTENSIONS: [&str; 2] = ["graphql", "gql"];
+
+/// Extension of the gzip-compressed files, e.g. schema.graphql.gz.
+pub const GZIP_EXTENSION: &str = "gz";
+
+/// List of the built-in scalars.
+pub const BUILT_IN_SCALARS: [&str; 5] = ["Boolean", "Float", "ID", "Int", "String"];
+
+/// List of the built-in directives.
+pub const BUILT_IN_DIRECTIVES: [&str; 5] =
+    ["@deprecated", "@include", "@oneOf", "@skip", "@specifiedBy"];
+
+/// List of the Apollo Federation directives, known in federation mode.
+pub const FEDERATION_DIRECTIVES: [&str; 15] = [
+    "@authenticated",
+    "@composeDirective",
+    "@extends",
+    "@external",
+    "@inaccessible",
+    "@interfaceObject",
+    "@key",
+    "@link",
+    "@override",
+    "@policy",
+    "@provides",
+    "@requires",
+    "@requiresScopes",
+    "@shareable",
+    "@tag",
+];
+
+/// Synthetic path of the built-in scalars.
+pub const BUILT_IN_SCALARS_PATH: &str = "<built-in>";
+
+/// Default number of files read concurrently.
+pub const DEFAULT_JOBS: usize = 16;
+
+/// Default width of the wrapped dependency lists.
+pub const DEFAULT_WRAP_WIDTH: usize = 80;
+
+/// Names of the configuration file, looked up in this order.
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["craftql.toml", ".craftql.toml"];
+
+/// Synthetic path of a schema provided inline.
+pub const INLINE_SCHEMA_PATH: &str = "<inline>";
+
+/// Configuration loaded from a craftql.toml file, CLI flags take precedence.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Default path to get files from, relative to the configuration file.
+    pub path: Option<std::path::PathBuf>,
+    /// Allowed file extensions, defaults to ALLOWED_EXTENSIONS.
+    pub extensions: Option<Vec<String>>,
+    /// Paths to skip, matched against the trailing components of the walked
+    /// files and directories.
+    pub exclude_paths: Vec<String>,
+    /// Default GraphQL type(s) filter.
+    pub filter: Vec<GraphQL>,
+    /// Default output format.
+    pub format: Option<Format>,
+    /// Lint rule toggles, keyed by rule name.
+    pub lint: BTreeMap<String, bool>,
+    /// Size limits checked by --budget.
+    pub budget: Budget,
+}
+
+impl Config {
+    /// Load a configuration file.
+    pub async fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.to_string_lossy()))?;
+        let mut config: Config = toml::from_str(&contents)
+            .with_context(|| format!("Invalid {}", path.to_string_lossy()))?;
+
+        if let (Some(default_path), Some(directory)) = (&config.path, path.parent()) {
+            config.path = Some(directory.join(default_path).into());
+        }
+
+        Ok(config)
+    }
+
+    /// Find the closest configuration file by walking up from a path.
+    pub async fn find(path: &Path) -> Option<PathBuf> {
+        let path = path.canonicalize().await.ok()?;
+        let mut directory = if path.is_dir().await {
+            Some(path.as_path())
+        } else {
+            path.parent()
+        };
+
+        while let Some(current) = directory {
+            for name in CONFIG_FILE_NAMES {
+                let candidate = current.join(name);
+
+                if candidate.is_file().await {
+                    return Some(candidate);
+                }
+            }
+
+            directory = current.parent();
+        }
+
+        None
+    }
+
+    /// Check if a file extension is allowed.
+    pub fn is_extension_allowed(&self, extension: &str) -> bool {
+        AnalysisOptions::from(self).is_extension_allowed(extension)
+    }
+
+    /// Check if a path is excluded.
+    pub fn is_path_excluded(&self, path: &Path) -> bool {
+        AnalysisOptions::from(self).is_path_excluded(path)
+    }
+}
+
+impl From<&Config> for AnalysisOptions {
+    fn from(config: &Config) -> Self {
+        let options = AnalysisOptions::new()
+            .exclude_paths(config.exclude_paths.clone())
+            .filter(config.filter.clone());
+
+        match &config.extensions {
+            Some(extensions) => options.extensions(extensions.clone()),
+            None => options,
+        }
+    }
+}
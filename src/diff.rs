@@ -0,0 +1,758 @@
+use crate::{
+    lint::parse_definition,
+    state::{GraphQL, GraphQLType, SchemaGraph},
+};
+
+use graphql_parser::schema::{Definition, Field, InputValue, Type, TypeDefinition, TypeExtension};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Criticality of a change, following the usual GraphQL rules.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Criticality {
+    /// Breaks the existing clients, e.g. a removed field.
+    Breaking,
+    /// Might change the behavior of the existing clients, e.g. an added enum
+    /// value.
+    Dangerous,
+    /// Compatible with the existing clients, e.g. an added type.
+    Safe,
+}
+
+impl fmt::Display for Criticality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Criticality::Breaking => write!(f, "breaking"),
+            Criticality::Dangerous => write!(f, "dangerous"),
+            Criticality::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+/// Change between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// Criticality of the change.
+    pub criticality: Criticality,
+    /// Coordinate of the changed element, e.g. Foo.bar(baz:).
+    pub coordinate: String,
+    /// Description of the change.
+    pub message: String,
+}
+
+/// Reference to a type, wrapped in lists and/or non-null types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl From<&Type<'_, String>> for TypeRef {
+    fn from(value_type: &Type<'_, String>) -> Self {
+        match value_type {
+            Type::NamedType(name) => TypeRef::Named(name.clone()),
+            Type::ListType(inner) => TypeRef::List(Box::new(TypeRef::from(&**inner))),
+            Type::NonNullType(inner) => TypeRef::NonNull(Box::new(TypeRef::from(&**inner))),
+        }
+    }
+}
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeRef::Named(name) => write!(f, "{}", name),
+            TypeRef::List(inner) => write!(f, "[{}]", inner),
+            TypeRef::NonNull(inner) => write!(f, "{}!", inner),
+        }
+    }
+}
+
+/// Check if an output type change is safe, i.e. only made stricter.
+fn is_safe_output_change(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old, new) {
+        (TypeRef::Named(old), TypeRef::Named(new)) => old == new,
+        (TypeRef::List(old), TypeRef::List(new)) => is_safe_output_change(old, new),
+        (TypeRef::NonNull(old), TypeRef::NonNull(new)) => is_safe_output_change(old, new),
+        (old, TypeRef::NonNull(new)) if !matches!(old, TypeRef::NonNull(_)) => {
+            is_safe_output_change(old, new)
+        }
+        _ => false,
+    }
+}
+
+/// Check if an input type change is safe, i.e. only made looser.
+fn is_safe_input_change(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old, new) {
+        (TypeRef::Named(old), TypeRef::Named(new)) => old == new,
+        (TypeRef::List(old), TypeRef::List(new)) => is_safe_input_change(old, new),
+        (TypeRef::NonNull(old), TypeRef::NonNull(new)) => is_safe_input_change(old, new),
+        (TypeRef::NonNull(old), new) => is_safe_input_change(old, new),
+        _ => false,
+    }
+}
+
+/// Field, argument or input field.
+#[derive(Debug, Clone)]
+struct FieldShape {
+    value_type: TypeRef,
+    default_value: Option<String>,
+    arguments: BTreeMap<String, FieldShape>,
+}
+
+impl FieldShape {
+    /// Check if an argument or input field is required, i.e. non-null
+    /// without a default.
+    fn is_required(&self) -> bool {
+        matches!(self.value_type, TypeRef::NonNull(_)) && self.default_value.is_none()
+    }
+}
+
+impl From<&Field<'_, String>> for FieldShape {
+    fn from(field: &Field<'_, String>) -> Self {
+        FieldShape {
+            value_type: TypeRef::from(&field.field_type),
+            default_value: None,
+            arguments: input_shapes(&field.arguments),
+        }
+    }
+}
+
+impl From<&InputValue<'_, String>> for FieldShape {
+    fn from(input_value: &InputValue<'_, String>) -> Self {
+        FieldShape {
+            value_type: TypeRef::from(&input_value.value_type),
+            default_value: input_value
+                .default_value
+                .as_ref()
+                .map(|value| value.to_string()),
+            arguments: BTreeMap::new(),
+        }
+    }
+}
+
+/// Return the shapes of arguments or input fields, by name.
+fn input_shapes(input_values: &[InputValue<'_, String>]) -> BTreeMap<String, FieldShape> {
+    input_values
+        .iter()
+        .map(|input_value| (input_value.name.clone(), FieldShape::from(input_value)))
+        .collect()
+}
+
+/// Type definition merged with its extensions.
+#[derive(Debug, Default)]
+struct TypeShape {
+    kind: Option<GraphQLType>,
+    fields: BTreeMap<String, FieldShape>,
+    interfaces: BTreeSet<String>,
+    members: BTreeSet<String>,
+    values: BTreeSet<String>,
+}
+
+/// Directive definition.
+#[derive(Debug, Default)]
+struct DirectiveShape {
+    arguments: BTreeMap<String, FieldShape>,
+    locations: BTreeSet<String>,
+}
+
+/// Types and directives of a schema, by name.
+#[derive(Debug, Default)]
+struct SchemaShape {
+    types: BTreeMap<String, TypeShape>,
+    directives: BTreeMap<String, DirectiveShape>,
+}
+
+impl SchemaShape {
+    /// Return the shape of a type, its kind being set by the definition or by
+    /// the first extension.
+    fn type_shape(&mut self, name: &str, kind: GraphQLType) -> &mut TypeShape {
+        let shape = self.types.entry(name.to_owned()).or_default();
+
+        shape.kind.get_or_insert(kind);
+        shape
+    }
+
+    /// Add the fields, interfaces, members and values of a definition or an
+    /// extension.
+    fn add(&mut self, definition: Definition<'_, String>) {
+        let fields = |fields: &[Field<'_, String>]| {
+            fields
+                .iter()
+                .map(|field| (field.name.clone(), FieldShape::from(field)))
+                .collect::<Vec<(String, FieldShape)>>()
+        };
+
+        match definition {
+            Definition::TypeDefinition(TypeDefinition::Object(object)) => {
+                let shape = self.type_shape(&object.name, GraphQLType::Object);
+
+                shape.kind = Some(GraphQLType::Object);
+                shape.fields.extend(fields(&object.fields));
+                shape.interfaces.extend(object.implements_interfaces);
+            }
+            Definition::TypeExtension(TypeExtension::Object(object)) => {
+                let shape = self.type_shape(&object.name, GraphQLType::Object);
+
+                shape.fields.extend(fields(&object.fields));
+                shape.interfaces.extend(object.implements_interfaces);
+            }
+            Definition::TypeDefinition(TypeDefinition::Interface(interface)) => {
+                let shape = self.type_shape(&interface.name, GraphQLType::Interface);
+
+                shape.kind = Some(GraphQLType::Interface);
+                shape.fields.extend(fields(&interface.fields));
+                shape.interfaces.extend(interface.implements_interfaces);
+            }
+            Definition::TypeExtension(TypeExtension::Interface(interface)) => {
+                let shape = self.type_shape(&interface.name, GraphQLType::Interface);
+
+                shape.fields.extend(fields(&interface.fields));
+                shape.interfaces.extend(interface.implements_interfaces);
+            }
+            Definition::TypeDefinition(TypeDefinition::InputObject(input_object)) => {
+                let shape = self.type_shape(&input_object.name, GraphQLType::InputObject);
+
+                shape.kind = Some(GraphQLType::InputObject);
+                shape.fields.extend(input_shapes(&input_object.fields));
+            }
+            Definition::TypeExtension(TypeExtension::InputObject(input_object)) => {
+                self.type_shape(&input_object.name, GraphQLType::InputObject)
+                    .fields
+                    .extend(input_shapes(&input_object.fields));
+            }
+            Definition::TypeDefinition(TypeDefinition::Enum(enum_type)) => {
+                let shape = self.type_shape(&enum_type.name, GraphQLType::Enum);
+
+                shape.kind = Some(GraphQLType::Enum);
+                shape
+                    .values
+                    .extend(enum_type.values.into_iter().map(|value| value.name));
+            }
+            Definition::TypeExtension(TypeExtension::Enum(enum_type)) => {
+                self.type_shape(&enum_type.name, GraphQLType::Enum)
+                    .values
+                    .extend(enum_type.values.into_iter().map(|value| value.name));
+            }
+            Definition::TypeDefinition(TypeDefinition::Union(union)) => {
+                let shape = self.type_shape(&union.name, GraphQLType::Union);
+
+                shape.kind = Some(GraphQLType::Union);
+                shape.members.extend(union.types);
+            }
+            Definition::TypeExtension(TypeExtension::Union(union)) => {
+                self.type_shape(&union.name, GraphQLType::Union)
+                    .members
+                    .extend(union.types);
+            }
+            Definition::TypeDefinition(TypeDefinition::Scalar(scalar)) => {
+                self.type_shape(&scalar.name, GraphQLType::Scalar).kind = Some(GraphQLType::Scalar);
+            }
+            Definition::TypeExtension(TypeExtension::Scalar(scalar)) => {
+                self.type_shape(&scalar.name, GraphQLType::Scalar);
+            }
+            Definition::DirectiveDefinition(directive) => {
+                self.directives.insert(
+                    format!("@{}", directive.name),
+                    DirectiveShape {
+                        arguments: input_shapes(&directive.arguments),
+                        locations: directive
+                            .locations
+                            .iter()
+                            .map(|location| location.as_str().to_owned())
+                            .collect(),
+                    },
+                );
+            }
+            Definition::SchemaDefinition(_) => {}
+        }
+    }
+}
+
+impl From<&SchemaGraph> for SchemaShape {
+    fn from(graph: &SchemaGraph) -> Self {
+        let mut shape = SchemaShape::default();
+
+        for node in graph.node_weights() {
+            if node.entity.graphql.base_type().is_none()
+                && node.entity.graphql != GraphQL::Directive
+            {
+                continue;
+            }
+
+            if let Some(definition) = parse_definition(&node.entity) {
+                shape.add(definition);
+            }
+        }
+
+        shape
+    }
+}
+
+/// Collector of the changes.
+#[derive(Debug, Default)]
+struct Changes(Vec<Change>);
+
+impl Changes {
+    fn push(&mut self, criticality: Criticality, coordinate: &str, message: String) {
+        self.0.push(Change {
+            criticality,
+            coordinate: coordinate.to_owned(),
+            message,
+        });
+    }
+
+    /// Compare the arguments of a field or a directive.
+    fn arguments(
+        &mut self,
+        coordinate: &str,
+        old: &BTreeMap<String, FieldShape>,
+        new: &BTreeMap<String, FieldShape>,
+    ) {
+        for name in old.keys().chain(new.keys()).collect::<BTreeSet<&String>>() {
+            let coordinate = format!("{}({}:)", coordinate, name);
+
+            match (old.get(name), new.get(name)) {
+                (Some(_), None) => self.push(
+                    Criticality::Breaking,
+                    &coordinate,
+                    format!("Argument {} was removed", coordinate),
+                ),
+                (None, Some(new)) if new.is_required() => self.push(
+                    Criticality::Breaking,
+                    &coordinate,
+                    format!("Required argument {} was added", coordinate),
+                ),
+                (None, Some(_)) => self.push(
+                    Criticality::Dangerous,
+                    &coordinate,
+                    format!("Argument {} was added", coordinate),
+                ),
+                (Some(old), Some(new)) => self.input_value(&coordinate, "Argument", old, new),
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// Compare the type and the default value of an argument or an input
+    /// field.
+    fn input_value(&mut self, coordinate: &str, label: &str, old: &FieldShape, new: &FieldShape) {
+        if old.value_type != new.value_type {
+            self.push(
+                match is_safe_input_change(&old.value_type, &new.value_type) {
+                    true => Criticality::Safe,
+                    false => Criticality::Breaking,
+                },
+                coordinate,
+                format!(
+                    "{} {} changed type from {} to {}",
+                    label, coordinate, old.value_type, new.value_type
+                ),
+            );
+        }
+
+        if old.default_value != new.default_value {
+            let print = |default_value: &Option<String>| match default_value {
+                Some(default_value) => default_value.clone(),
+                None => String::from("none"),
+            };
+
+            self.push(
+                Criticality::Dangerous,
+                coordinate,
+                format!(
+                    "Default value of {} changed from {} to {}",
+                    coordinate,
+                    print(&old.default_value),
+                    print(&new.default_value)
+                ),
+            );
+        }
+    }
+
+    /// Compare the fields of an object, an interface or an input object.
+    fn fields(&mut self, name: &str, is_input: bool, old: &TypeShape, new: &TypeShape) {
+        let label = match is_input {
+            true => "Input field",
+            false => "Field",
+        };
+
+        for field in old
+            .fields
+            .keys()
+            .chain(new.fields.keys())
+            .collect::<BTreeSet<&String>>()
+        {
+            let coordinate = format!("{}.{}", name, field);
+
+            match (old.fields.get(field), new.fields.get(field)) {
+                (Some(_), None) => self.push(
+                    Criticality::Breaking,
+                    &coordinate,
+                    format!("{} {} was removed", label, coordinate),
+                ),
+                (None, Some(new)) if is_input && new.is_required() => self.push(
+                    Criticality::Breaking,
+                    &coordinate,
+                    format!("Required input field {} was added", coordinate),
+                ),
+                (None, Some(_)) => self.push(
+                    match is_input {
+                        true => Criticality::Dangerous,
+                        false => Criticality::Safe,
+                    },
+                    &coordinate,
+                    format!("{} {} was added", label, coordinate),
+                ),
+                (Some(old), Some(new)) if is_input => {
+                    self.input_value(&coordinate, label, old, new)
+                }
+                (Some(old), Some(new)) => {
+                    if old.value_type != new.value_type {
+                        self.push(
+                            match is_safe_output_change(&old.value_type, &new.value_type) {
+                                true => Criticality::Safe,
+                                false => Criticality::Breaking,
+                            },
+                            &coordinate,
+                            format!(
+                                "Field {} changed type from {} to {}",
+                                coordinate, old.value_type, new.value_type
+                            ),
+                        );
+                    }
+
+                    self.arguments(&coordinate, &old.arguments, &new.arguments);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// Compare two versions of a type.
+    fn type_shape(&mut self, name: &str, old: &TypeShape, new: &TypeShape) {
+        let kind = |shape: &TypeShape| {
+            shape
+                .kind
+                .map_or("type", |kind| GraphQL::TypeDefinition(kind).as_str())
+        };
+
+        if old.kind != new.kind {
+            self.push(
+                Criticality::Breaking,
+                name,
+                format!("{} changed from {} to {}", name, kind(old), kind(new)),
+            );
+
+            return;
+        }
+
+        self.fields(name, old.kind == Some(GraphQLType::InputObject), old, new);
+        for value in old.values.difference(&new.values) {
+            let coordinate = format!("{}.{}", name, value);
+
+            self.push(
+                Criticality::Breaking,
+                &coordinate,
+                format!("Enum value {} was removed", coordinate),
+            );
+        }
+
+        for value in new.values.difference(&old.values) {
+            let coordinate = format!("{}.{}", name, value);
+
+            self.push(
+                Criticality::Dangerous,
+                &coordinate,
+                format!("Enum value {} was added", coordinate),
+            );
+        }
+
+        for member in old.members.difference(&new.members) {
+            self.push(
+                Criticality::Breaking,
+                name,
+                format!("{} was removed from union {}", member, name),
+            );
+        }
+
+        for member in new.members.difference(&old.members) {
+            self.push(
+                Criticality::Dangerous,
+                name,
+                format!("{} was added to union {}", member, name),
+            );
+        }
+
+        for interface in old.interfaces.difference(&new.interfaces) {
+            self.push(
+                Criticality::Breaking,
+                name,
+                format!("{} no longer implements {}", name, interface),
+            );
+        }
+
+        for interface in new.interfaces.difference(&old.interfaces) {
+            self.push(
+                Criticality::Dangerous,
+                name,
+                format!("{} now implements {}", name, interface),
+            );
+        }
+    }
+
+    /// Compare two versions of a directive.
+    fn directive(&mut self, name: &str, old: &DirectiveShape, new: &DirectiveShape) {
+        self.arguments(name, &old.arguments, &new.arguments);
+
+        for location in old.locations.difference(&new.locations) {
+            self.push(
+                Criticality::Breaking,
+                name,
+                format!("Location {} was removed from {}", location, name),
+            );
+        }
+
+        for location in new.locations.difference(&old.locations) {
+            self.push(
+                Criticality::Safe,
+                name,
+                format!("Location {} was added to {}", location, name),
+            );
+        }
+    }
+}
+
+/// Compare two schemas and classify the changes, ordered by type then by
+/// directive name. The graphs need the raw SDL of their entities.
+pub fn diff(old: &SchemaGraph, new: &SchemaGraph) -> Vec<Change> {
+    let (old, new) = (SchemaShape::from(old), SchemaShape::from(new));
+    let mut changes = Changes::default();
+
+    for name in old
+        .types
+        .keys()
+        .chain(new.types.keys())
+        .collect::<BTreeSet<&String>>()
+    {
+        match (old.types.get(name), new.types.get(name)) {
+            (Some(_), None) => changes.push(
+                Criticality::Breaking,
+                name,
+                format!("Type {} was removed", name),
+            ),
+            (None, Some(_)) => {
+                changes.push(Criticality::Safe, name, format!("Type {} was added", name))
+            }
+            (Some(old), Some(new)) => changes.type_shape(name, old, new),
+            (None, None) => {}
+        }
+    }
+
+    for name in old
+        .directives
+        .keys()
+        .chain(new.directives.keys())
+        .collect::<BTreeSet<&String>>()
+    {
+        match (old.directives.get(name), new.directives.get(name)) {
+            (Some(_), None) => changes.push(
+                Criticality::Breaking,
+                name,
+                format!("Directive {} was removed", name),
+            ),
+            (None, Some(_)) => changes.push(
+                Criticality::Safe,
+                name,
+                format!("Directive {} was added", name),
+            ),
+            (Some(old), Some(new)) => changes.directive(name, old, new),
+            (None, None) => {}
+        }
+    }
+
+    changes.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema::Schema;
+    use async_std::path::PathBuf;
+
+    fn diff_sources(old: &str, new: &str) -> Vec<(Criticality, String)> {
+        let schema = |contents: &str| {
+            Schema::from_sources(vec![(PathBuf::from("schema.gql"), String::from(contents))])
+                .unwrap()
+        };
+
+        diff(schema(old).graph(), schema(new).graph())
+            .into_iter()
+            .map(|change| (change.criticality, change.message))
+            .collect()
+    }
+
+    #[test]
+    fn check_type_changes() {
+        assert_eq!(
+            diff_sources(
+                "type Foo { id: ID } type Bar { id: ID } enum Baz { A }",
+                "type Foo { id: ID } input Bar { id: ID } scalar Qux enum Baz { A }",
+            ),
+            vec![
+                (
+                    Criticality::Breaking,
+                    String::from("Bar changed from object to input_object")
+                ),
+                (Criticality::Safe, String::from("Type Qux was added")),
+            ]
+        );
+        assert_eq!(
+            diff_sources("type Foo { id: ID } scalar Bar", "type Foo { id: ID }"),
+            vec![(Criticality::Breaking, String::from("Type Bar was removed"))]
+        );
+    }
+
+    #[test]
+    fn check_field_changes() {
+        assert_eq!(
+            diff_sources(
+                r#"
+                type Foo { a: String b: String c: String! d(x: Int, y: Int): [ID] }
+                input Bar { a: String b: Int = 1 }
+                "#,
+                r#"
+                type Foo { a: String! c: String d(x: Int!, z: Int!, w: Int): [ID!] e: ID }
+                input Bar { a: String! b: Int = 2 c: ID d: ID! }
+                "#,
+            ),
+            vec![
+                (
+                    Criticality::Breaking,
+                    String::from("Input field Bar.a changed type from String to String!")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("Default value of Bar.b changed from 1 to 2")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("Input field Bar.c was added")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Required input field Bar.d was added")
+                ),
+                (
+                    Criticality::Safe,
+                    String::from("Field Foo.a changed type from String to String!")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Field Foo.b was removed")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Field Foo.c changed type from String! to String")
+                ),
+                (
+                    Criticality::Safe,
+                    String::from("Field Foo.d changed type from [ID] to [ID!]")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("Argument Foo.d(w:) was added")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Argument Foo.d(x:) changed type from Int to Int!")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Argument Foo.d(y:) was removed")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Required argument Foo.d(z:) was added")
+                ),
+                (Criticality::Safe, String::from("Field Foo.e was added")),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_value_member_and_directive_changes() {
+        assert_eq!(
+            diff_sources(
+                r#"
+                enum Color { RED GREEN }
+                union Result = Foo | Bar
+                type Foo implements Node { id: ID! }
+                type Bar { id: ID! }
+                interface Node { id: ID! }
+                directive @auth(role: String) on FIELD_DEFINITION | OBJECT
+                directive @old on FIELD_DEFINITION
+                "#,
+                r#"
+                enum Color { RED }
+                extend enum Color { BLUE }
+                union Result = Foo
+                extend union Result = Baz
+                type Foo { id: ID! }
+                type Bar implements Node { id: ID! }
+                type Baz { id: ID! }
+                interface Node { id: ID! }
+                directive @auth(role: String!) on FIELD_DEFINITION | INTERFACE
+                "#,
+            ),
+            vec![
+                (
+                    Criticality::Dangerous,
+                    String::from("Bar now implements Node")
+                ),
+                (Criticality::Safe, String::from("Type Baz was added")),
+                (
+                    Criticality::Breaking,
+                    String::from("Enum value Color.GREEN was removed")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("Enum value Color.BLUE was added")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Foo no longer implements Node")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Bar was removed from union Result")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("Baz was added to union Result")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Argument @auth(role:) changed type from String to String!")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Location OBJECT was removed from @auth")
+                ),
+                (
+                    Criticality::Safe,
+                    String::from("Location INTERFACE was added to @auth")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Directive @old was removed")
+                ),
+            ]
+        );
+    }
+}
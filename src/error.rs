@@ -0,0 +1,73 @@
+use async_std::path::PathBuf;
+use std::fmt;
+
+/// Typed errors returned by the library, mapped to exit codes by the binary.
+#[derive(Debug)]
+pub enum Error {
+    /// Several nodes match a case-insensitive lookup.
+    AmbiguousNode(String, Vec<String>),
+    /// No GraphQL file found for the provided path.
+    EmptyInput(PathBuf),
+    /// GraphQL files found for the provided path, without any definition.
+    EmptySchema(PathBuf),
+    /// References to types or directives without definition, with --strict.
+    MissingDefinitions(usize),
+    /// Several schema definitions, with their locations.
+    MultipleSchemaDefinitions(Vec<String>),
+    /// Node not found in the graph.
+    NodeNotFound(String),
+    /// Applied directives without definition, with --unknown-directives error.
+    UnknownDirectives(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::AmbiguousNode(node, candidates) => write!(
+                f,
+                "Node {} is ambiguous, candidates are: {}",
+                node,
+                candidates.join(", ")
+            ),
+            Error::EmptyInput(path) => {
+                write!(f, "No GraphQL file found in {}", path.to_string_lossy())
+            }
+            Error::EmptySchema(path) => {
+                write!(
+                    f,
+                    "No GraphQL definition found in {}",
+                    path.to_string_lossy()
+                )
+            }
+            Error::MissingDefinitions(count) => write!(
+                f,
+                "{} missing {} found",
+                count,
+                if *count == 1 {
+                    "definition"
+                } else {
+                    "definitions"
+                }
+            ),
+            Error::MultipleSchemaDefinitions(locations) => write!(
+                f,
+                "Only one schema definition is allowed, found {}: {}",
+                locations.len(),
+                locations.join(", ")
+            ),
+            Error::NodeNotFound(node) => write!(f, "Node {} not found", node),
+            Error::UnknownDirectives(count) => write!(
+                f,
+                "{} unknown {} found",
+                count,
+                if *count == 1 {
+                    "directive"
+                } else {
+                    "directives"
+                }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
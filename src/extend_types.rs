@@ -1,6 +1,7 @@
-use crate::state::{GraphQL, GraphQLType};
+use crate::state::{GraphQL, GraphQLType, Reference};
 
 use graphql_parser::schema;
+use std::collections::{BTreeMap, HashMap};
 
 /// Convert Text to String.
 /// See https://github.com/graphql-rust/graphql-parser/blob/master/src/common.rs#L12-L28
@@ -19,26 +20,61 @@ where
     format!("@{}", convert_text_to_string::<T>(text))
 }
 
-/// Extend id for type extensions.
-/// Only used internally to distinguish between a type and its extension.
-fn get_extended_id(id: String) -> String {
-    format!("{}__", id)
+/// Extend id for type extensions, numbered in the sources order to
+/// distinguish between a type and its extensions, e.g. Foo__1 and Foo__2.
+pub(crate) fn get_extended_id(name: &str, ordinal: usize) -> String {
+    format!("{}__{}", name, ordinal)
 }
 
-/// Extract dependencies from any entity's directives.
+/// Trim a description, graphql_parser keeping the blank lines of block strings.
+fn trim_description(description: &Option<String>) -> Option<String> {
+    description
+        .as_ref()
+        .map(|description| description.trim().to_owned())
+}
+
+/// Build the coordinate of a directive argument, e.g. @auth(requires:).
+pub(crate) fn directive_argument_coordinate(directive: &str, argument: &str) -> String {
+    format!("{}({}:)", directive, argument)
+}
+
+/// Check if a dependency is the coordinate of a directive argument, resolved
+/// to the named type of the argument once the directives are known.
+pub(crate) fn is_directive_argument_coordinate(dependency: &str) -> bool {
+    dependency.starts_with('@') && dependency.ends_with(":)")
+}
+
+/// Extract dependencies from any entity's directives, with the coordinates
+/// of their arguments. Whatever the value, e.g. an enum value, an input object
+/// or a string, the argument references the type given by the directive
+/// definition, e.g. a custom scalar.
 fn get_dependencies_from_directives<'a, T>(directives: &[schema::Directive<'a, T>]) -> Vec<String>
 where
     T: schema::Text<'a>,
 {
     directives
         .iter()
-        .map(|directive| convert_text_to_directive::<T>(&directive.name))
+        .flat_map(|directive| {
+            let name = convert_text_to_directive::<T>(&directive.name);
+            let coordinates = directive
+                .arguments
+                .iter()
+                .map(|(argument, _)| {
+                    directive_argument_coordinate(&name, &convert_text_to_string::<T>(argument))
+                })
+                .collect::<Vec<String>>();
+
+            std::iter::once(name).chain(coordinates)
+        })
         .collect::<Vec<String>>()
 }
 
-fn sort_and_dedupe_dependencies(mut dependencies: Vec<String>) -> Vec<String> {
-    dependencies.sort_by_key(|a| a.to_lowercase());
-    dependencies.dedup();
+/// Sort the dependencies case-insensitively and drop the duplicates, a type
+/// used by several fields being a single dependency.
+pub(crate) fn sort_and_dedupe_dependencies<T: AsRef<str>>(mut dependencies: Vec<T>) -> Vec<T> {
+    // Break the ties on the exact name to keep the duplicates adjacent.
+    dependencies.sort_by_cached_key(|a| (a.as_ref().to_lowercase(), a.as_ref().to_owned()));
+    dependencies.dedup_by(|a, b| a.as_ref() == b.as_ref());
     dependencies
 }
 
@@ -88,14 +124,285 @@ where
         .collect::<Vec<String>>()
 }
 
+/// Parse an Apollo Federation field set, e.g. "id organization { id }", and
+/// return its top-level fields and the type conditions of its fragments.
+pub(crate) fn parse_field_set(field_set: &str) -> (Vec<String>, Vec<String>) {
+    let mut fields = vec![];
+    let mut type_conditions = vec![];
+    let mut depth = 0_usize;
+    let mut is_type_condition = false;
+    let mut chars = field_set.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            // Arguments can't be selected, skip them.
+            '(' => {
+                for char in chars.by_ref() {
+                    if char == ')' {
+                        break;
+                    }
+                }
+            }
+            char if char == '_' || char.is_ascii_alphabetic() => {
+                let mut name = String::from(char);
+
+                while let Some(char) =
+                    chars.next_if(|char| *char == '_' || char.is_ascii_alphanumeric())
+                {
+                    name.push(char);
+                }
+
+                match name.as_str() {
+                    "on" => is_type_condition = true,
+                    _ if is_type_condition => {
+                        type_conditions.push(name);
+                        is_type_condition = false;
+                    }
+                    "__typename" => {}
+                    _ if depth == 0 => fields.push(name),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (fields, type_conditions)
+}
+
+/// Get the field sets of some federation directives, e.g. @key.
+fn get_field_sets<'a, T>(directives: &[schema::Directive<'a, T>], names: &[&str]) -> Vec<String>
+where
+    T: schema::Text<'a>,
+{
+    directives
+        .iter()
+        .filter(|directive| names.contains(&directive.name.as_ref()))
+        .flat_map(|directive| &directive.arguments)
+        .filter_map(|(argument, value)| match value {
+            schema::Value::String(field_set) if argument.as_ref() == "fields" => {
+                Some(field_set.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Resolve field sets against the fields of a type, returning the types of
+/// the selected fields and the type conditions.
+fn resolve_field_sets<'a, T>(
+    field_sets: Vec<String>,
+    fields: &[schema::Field<'a, T>],
+) -> Vec<String>
+where
+    T: schema::Text<'a>,
+{
+    field_sets
+        .iter()
+        .flat_map(|field_set| {
+            let (selected_fields, type_conditions) = parse_field_set(field_set);
+
+            selected_fields
+                .into_iter()
+                .filter_map(|selected_field| {
+                    fields
+                        .iter()
+                        .find(|field| field.name.as_ref() == selected_field)
+                        .map(|field| walk_field_type(&field.field_type))
+                })
+                .chain(type_conditions)
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+/// Get the dependencies of the federation field sets of a type and of its
+/// fields. The @key and @requires field sets select fields of the type, the
+/// @provides ones fields of the field type which is already a dependency.
+fn get_federation_dependencies<'a, T>(
+    directives: &[schema::Directive<'a, T>],
+    fields: &[schema::Field<'a, T>],
+) -> FieldSetDependencies
+where
+    T: schema::Text<'a>,
+{
+    FieldSetDependencies {
+        keys: sort_and_dedupe_dependencies(resolve_field_sets(
+            get_field_sets(directives, &["key"]),
+            fields,
+        )),
+        fields: fields
+            .iter()
+            .map(|field| {
+                (
+                    convert_text_to_string::<T>(&field.name),
+                    sort_and_dedupe_dependencies(resolve_field_sets(
+                        get_field_sets(&field.directives, &["provides", "requires"]),
+                        fields,
+                    )),
+                )
+            })
+            .filter(|(_, dependencies)| !dependencies.is_empty())
+            .collect(),
+    }
+}
+
+/// Dependencies of the Apollo Federation field sets of a definition.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldSetDependencies {
+    /// Dependencies of the @key field sets of the definition.
+    pub keys: Vec<String>,
+    /// Dependencies of the @provides and @requires field sets by field name.
+    pub fields: HashMap<String, Vec<String>>,
+}
+
+/// Field of a definition with its own dependencies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDependencies {
+    /// Name of the field.
+    pub name: String,
+    /// Dependencies of the field.
+    pub dependencies: Vec<String>,
+    /// Description of the field.
+    pub description: Option<String>,
+    /// Position of the field in its source as (line, column).
+    pub position: (usize, usize),
+    /// Raw representation of the field.
+    pub raw: String,
+}
+
+/// Get the dependencies of a field.
+fn get_field_dependencies<'a, T>(field: &schema::Field<'a, T>) -> FieldDependencies
+where
+    T: schema::Text<'a>,
+{
+    FieldDependencies {
+        name: convert_text_to_string::<T>(&field.name),
+        dependencies: sort_and_dedupe_dependencies(walk_field(field)),
+        description: trim_description(&field.description),
+        position: (field.position.line, field.position.column),
+        raw: field.to_string(),
+    }
+}
+
+/// Get the dependencies of an input field.
+fn get_input_value_dependencies<'a, T>(input_value: &schema::InputValue<'a, T>) -> FieldDependencies
+where
+    T: schema::Text<'a>,
+{
+    FieldDependencies {
+        name: convert_text_to_string::<T>(&input_value.name),
+        dependencies: sort_and_dedupe_dependencies(walk_input_value(input_value)),
+        description: trim_description(&input_value.description),
+        position: (input_value.position.line, input_value.position.column),
+        raw: format!("{}\n", input_value),
+    }
+}
+
+/// Get the references of the fields of a definition, their arguments
+/// included.
+fn get_field_references<'a, T>(name: &str, fields: &[schema::Field<'a, T>]) -> Vec<Reference>
+where
+    T: schema::Text<'a>,
+{
+    fields
+        .iter()
+        .flat_map(|field| {
+            let coordinate = format!("{}.{}", name, convert_text_to_string::<T>(&field.name));
+
+            std::iter::once(Reference {
+                field: coordinate.clone(),
+                argument: None,
+                value_type: field.field_type.to_string(),
+            })
+            .chain(field.arguments.iter().map(move |argument| Reference {
+                field: coordinate.clone(),
+                argument: Some(convert_text_to_string::<T>(&argument.name)),
+                value_type: argument.value_type.to_string(),
+            }))
+        })
+        .collect()
+}
+
+/// Get the references of the input fields of an input object.
+fn get_input_field_references<'a, T>(
+    name: &str,
+    input_values: &[schema::InputValue<'a, T>],
+) -> Vec<Reference>
+where
+    T: schema::Text<'a>,
+{
+    input_values
+        .iter()
+        .map(|input_value| Reference {
+            field: format!(
+                "{}.{}",
+                name,
+                convert_text_to_string::<T>(&input_value.name)
+            ),
+            argument: None,
+            value_type: input_value.value_type.to_string(),
+        })
+        .collect()
+}
+
 /// Trait providing extension methods for graphql_parser::schema.
 pub trait ExtendType {
-    /// Method to get the dependencies.
-    fn get_dependencies(&self) -> Vec<String>;
+    /// Method to get the dependencies, the ones of the fields included.
+    fn get_dependencies(&self) -> Vec<String> {
+        sort_and_dedupe_dependencies(
+            self.get_type_dependencies()
+                .into_iter()
+                .chain(
+                    self.get_fields()
+                        .into_iter()
+                        .flat_map(|field| field.dependencies),
+                )
+                .collect::<Vec<String>>(),
+        )
+    }
+    /// Method to get the dependencies of the definition, without the ones of
+    /// its fields.
+    fn get_type_dependencies(&self) -> Vec<String>;
+    /// Method to get the description, if any.
+    fn get_description(&self) -> Option<String>;
+    /// Method to get the fields with their own dependencies.
+    fn get_fields(&self) -> Vec<FieldDependencies>;
+    /// Method to get the dependencies of the Apollo Federation field sets.
+    fn get_field_set_dependencies(&self) -> FieldSetDependencies {
+        FieldSetDependencies::default()
+    }
+    /// Method to get the named types of the arguments of a directive
+    /// definition, keyed by their coordinate.
+    fn get_argument_types(&self) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+    /// Method to get the fields, arguments and input fields with their types
+    /// as written.
+    fn get_references(&self) -> Vec<Reference> {
+        vec![]
+    }
+    /// Method to check if an object, interface or input object has no fields.
+    fn is_empty(&self) -> bool {
+        false
+    }
+    /// Method to get the interfaces of the implements clause of an object or
+    /// interface, in the source order.
+    fn get_interfaces(&self) -> Vec<String> {
+        vec![]
+    }
+    /// Method to get the member types of a union, in the source order.
+    fn get_members(&self) -> Vec<String> {
+        vec![]
+    }
     /// Method to get id and the name, id is optional and can be copied from name.
     fn get_id_and_name(&self) -> (Option<String>, String);
     /// Method to get the internal GraphQL mapped type.
     fn get_mapped_type(&self) -> GraphQL;
+    /// Method to get the (line, column) position in the source.
+    fn get_position(&self) -> (usize, usize);
     /// Method to get the raw representation.
     fn get_raw(&self) -> String;
 }
@@ -104,7 +411,7 @@ impl<'a, T> ExtendType for schema::TypeDefinition<'a, T>
 where
     T: schema::Text<'a>,
 {
-    fn get_dependencies(&self) -> Vec<String> {
+    fn get_type_dependencies(&self) -> Vec<String> {
         match self {
             schema::TypeDefinition::Enum(enum_type) => {
                 sort_and_dedupe_dependencies(
@@ -126,13 +433,9 @@ where
             }
             schema::TypeDefinition::Object(object_type) => {
                 sort_and_dedupe_dependencies(
-                    // Get fields' dependencies.
-                    object_type
-                        .fields
-                        .iter()
-                        .flat_map(|field| walk_field(field))
-                        // Get root directives.
-                        .chain(get_dependencies_from_directives(&object_type.directives))
+                    // Get root directives.
+                    get_dependencies_from_directives(&object_type.directives)
+                        .into_iter()
                         // Get interfaces as dependencies.
                         .chain(
                             object_type
@@ -145,14 +448,8 @@ where
             }
             schema::TypeDefinition::Interface(interface_type) => {
                 sort_and_dedupe_dependencies(
-                    // Get fields' dependencies.
-                    interface_type
-                        .fields
-                        .iter()
-                        .flat_map(|field| walk_field(field))
-                        // Get root directives.
-                        .chain(get_dependencies_from_directives(&interface_type.directives))
-                        .collect::<Vec<String>>(),
+                    // Get root directives.
+                    get_dependencies_from_directives(&interface_type.directives),
                 )
             }
             schema::TypeDefinition::Union(union_type) => {
@@ -169,20 +466,111 @@ where
             }
             schema::TypeDefinition::InputObject(input_object_type) => {
                 sort_and_dedupe_dependencies(
-                    // Get fields' dependencies.
-                    input_object_type
-                        .fields
-                        .iter()
-                        .flat_map(|input_value| walk_input_value(input_value))
-                        // Get root directives.
-                        .chain(get_dependencies_from_directives(
-                            &input_object_type.directives,
-                        ))
-                        .collect::<Vec<String>>(),
+                    // Get root directives.
+                    get_dependencies_from_directives(&input_object_type.directives),
                 )
             }
         }
     }
+    fn get_fields(&self) -> Vec<FieldDependencies> {
+        match self {
+            schema::TypeDefinition::Object(object_type) => object_type
+                .fields
+                .iter()
+                .map(get_field_dependencies)
+                .collect(),
+            schema::TypeDefinition::Interface(interface_type) => interface_type
+                .fields
+                .iter()
+                .map(get_field_dependencies)
+                .collect(),
+            schema::TypeDefinition::InputObject(input_object_type) => input_object_type
+                .fields
+                .iter()
+                .map(get_input_value_dependencies)
+                .collect(),
+            _ => vec![],
+        }
+    }
+    fn get_references(&self) -> Vec<Reference> {
+        let (_, name) = self.get_id_and_name();
+
+        match self {
+            schema::TypeDefinition::Object(object_type) => {
+                get_field_references(&name, &object_type.fields)
+            }
+            schema::TypeDefinition::Interface(interface_type) => {
+                get_field_references(&name, &interface_type.fields)
+            }
+            schema::TypeDefinition::InputObject(input_object_type) => {
+                get_input_field_references(&name, &input_object_type.fields)
+            }
+            _ => vec![],
+        }
+    }
+    fn get_field_set_dependencies(&self) -> FieldSetDependencies {
+        match self {
+            schema::TypeDefinition::Object(object_type) => {
+                get_federation_dependencies(&object_type.directives, &object_type.fields)
+            }
+            schema::TypeDefinition::Interface(interface_type) => {
+                get_federation_dependencies(&interface_type.directives, &interface_type.fields)
+            }
+            _ => FieldSetDependencies::default(),
+        }
+    }
+    fn get_description(&self) -> Option<String> {
+        match self {
+            schema::TypeDefinition::Enum(enum_type) => trim_description(&enum_type.description),
+            schema::TypeDefinition::Scalar(scalar_type) => {
+                trim_description(&scalar_type.description)
+            }
+            schema::TypeDefinition::Object(object_type) => {
+                trim_description(&object_type.description)
+            }
+            schema::TypeDefinition::Interface(interface_type) => {
+                trim_description(&interface_type.description)
+            }
+            schema::TypeDefinition::Union(union_type) => trim_description(&union_type.description),
+            schema::TypeDefinition::InputObject(input_object_type) => {
+                trim_description(&input_object_type.description)
+            }
+        }
+    }
+    fn get_interfaces(&self) -> Vec<String> {
+        let interfaces = match self {
+            schema::TypeDefinition::Object(object_type) => &object_type.implements_interfaces,
+            schema::TypeDefinition::Interface(interface_type) => {
+                &interface_type.implements_interfaces
+            }
+            _ => return vec![],
+        };
+
+        interfaces
+            .iter()
+            .map(|interface| convert_text_to_string::<T>(interface))
+            .collect()
+    }
+    fn get_members(&self) -> Vec<String> {
+        match self {
+            schema::TypeDefinition::Union(union_type) => union_type
+                .types
+                .iter()
+                .map(|member| convert_text_to_string::<T>(member))
+                .collect(),
+            _ => vec![],
+        }
+    }
+    fn is_empty(&self) -> bool {
+        match self {
+            schema::TypeDefinition::Object(object_type) => object_type.fields.is_empty(),
+            schema::TypeDefinition::Interface(interface_type) => interface_type.fields.is_empty(),
+            schema::TypeDefinition::InputObject(input_object_type) => {
+                input_object_type.fields.is_empty()
+            }
+            _ => false,
+        }
+    }
     fn get_id_and_name(&self) -> (Option<String>, String) {
         (
             None,
@@ -208,6 +596,18 @@ where
             }
         }
     }
+    fn get_position(&self) -> (usize, usize) {
+        let position = match self {
+            schema::TypeDefinition::Enum(enum_type) => enum_type.position,
+            schema::TypeDefinition::Scalar(scalar_type) => scalar_type.position,
+            schema::TypeDefinition::Object(object_type) => object_type.position,
+            schema::TypeDefinition::Interface(interface_type) => interface_type.position,
+            schema::TypeDefinition::Union(union_type) => union_type.position,
+            schema::TypeDefinition::InputObject(input_object_type) => input_object_type.position,
+        };
+
+        (position.line, position.column)
+    }
     fn get_raw(&self) -> String {
         match self {
             schema::TypeDefinition::Enum(enum_type) => enum_type.to_string(),
@@ -224,7 +624,7 @@ impl<'a, T> ExtendType for schema::TypeExtension<'a, T>
 where
     T: schema::Text<'a>,
 {
-    fn get_dependencies(&self) -> Vec<String> {
+    fn get_type_dependencies(&self) -> Vec<String> {
         match self {
             schema::TypeExtension::Enum(enum_type_extension) => {
                 sort_and_dedupe_dependencies(
@@ -254,15 +654,9 @@ where
             }
             schema::TypeExtension::Object(object_type_extension) => {
                 sort_and_dedupe_dependencies(
-                    // Get fields' dependencies.
-                    object_type_extension
-                        .fields
-                        .iter()
-                        .flat_map(|field| walk_field(field))
-                        // Get root directives.
-                        .chain(get_dependencies_from_directives(
-                            &object_type_extension.directives,
-                        ))
+                    // Get root directives.
+                    get_dependencies_from_directives(&object_type_extension.directives)
+                        .into_iter()
                         // Get interfaces as dependencies.
                         .chain(
                             object_type_extension
@@ -277,15 +671,9 @@ where
             }
             schema::TypeExtension::Interface(interface_type_extension) => {
                 sort_and_dedupe_dependencies(
-                    // Get fields' dependencies.
-                    interface_type_extension
-                        .fields
-                        .iter()
-                        .flat_map(|field| walk_field(field))
-                        // Get root directives.
-                        .chain(get_dependencies_from_directives(
-                            &interface_type_extension.directives,
-                        ))
+                    // Get root directives.
+                    get_dependencies_from_directives(&interface_type_extension.directives)
+                        .into_iter()
                         // Add extension's source.
                         .chain(vec![convert_text_to_string::<T>(
                             &interface_type_extension.name,
@@ -313,15 +701,9 @@ where
             }
             schema::TypeExtension::InputObject(input_object_type_extension) => {
                 sort_and_dedupe_dependencies(
-                    // Get fields' dependencies.
-                    input_object_type_extension
-                        .fields
-                        .iter()
-                        .flat_map(|input_value| walk_input_value(input_value))
-                        // Get root directives.
-                        .chain(get_dependencies_from_directives(
-                            &input_object_type_extension.directives,
-                        ))
+                    // Get root directives.
+                    get_dependencies_from_directives(&input_object_type_extension.directives)
+                        .into_iter()
                         // Add extension's source.
                         .chain(vec![convert_text_to_string::<T>(
                             &input_object_type_extension.name,
@@ -331,6 +713,97 @@ where
             }
         }
     }
+    fn get_fields(&self) -> Vec<FieldDependencies> {
+        match self {
+            schema::TypeExtension::Object(object_type_extension) => object_type_extension
+                .fields
+                .iter()
+                .map(get_field_dependencies)
+                .collect(),
+            schema::TypeExtension::Interface(interface_type_extension) => interface_type_extension
+                .fields
+                .iter()
+                .map(get_field_dependencies)
+                .collect(),
+            schema::TypeExtension::InputObject(input_object_type_extension) => {
+                input_object_type_extension
+                    .fields
+                    .iter()
+                    .map(get_input_value_dependencies)
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+    fn get_references(&self) -> Vec<Reference> {
+        let (_, name) = self.get_id_and_name();
+
+        match self {
+            schema::TypeExtension::Object(object_type_extension) => {
+                get_field_references(&name, &object_type_extension.fields)
+            }
+            schema::TypeExtension::Interface(interface_type_extension) => {
+                get_field_references(&name, &interface_type_extension.fields)
+            }
+            schema::TypeExtension::InputObject(input_object_type_extension) => {
+                get_input_field_references(&name, &input_object_type_extension.fields)
+            }
+            _ => vec![],
+        }
+    }
+    fn get_field_set_dependencies(&self) -> FieldSetDependencies {
+        match self {
+            schema::TypeExtension::Object(object_type_extension) => get_federation_dependencies(
+                &object_type_extension.directives,
+                &object_type_extension.fields,
+            ),
+            schema::TypeExtension::Interface(interface_type_extension) => {
+                get_federation_dependencies(
+                    &interface_type_extension.directives,
+                    &interface_type_extension.fields,
+                )
+            }
+            _ => FieldSetDependencies::default(),
+        }
+    }
+    fn get_description(&self) -> Option<String> {
+        // Type extensions can't be described.
+        None
+    }
+    fn get_interfaces(&self) -> Vec<String> {
+        let interfaces = match self {
+            schema::TypeExtension::Object(object_type) => &object_type.implements_interfaces,
+            schema::TypeExtension::Interface(interface_type) => {
+                &interface_type.implements_interfaces
+            }
+            _ => return vec![],
+        };
+
+        interfaces
+            .iter()
+            .map(|interface| convert_text_to_string::<T>(interface))
+            .collect()
+    }
+    fn get_members(&self) -> Vec<String> {
+        match self {
+            schema::TypeExtension::Union(union_type) => union_type
+                .types
+                .iter()
+                .map(|member| convert_text_to_string::<T>(member))
+                .collect(),
+            _ => vec![],
+        }
+    }
+    fn is_empty(&self) -> bool {
+        match self {
+            schema::TypeExtension::Object(object_type) => object_type.fields.is_empty(),
+            schema::TypeExtension::Interface(interface_type) => interface_type.fields.is_empty(),
+            schema::TypeExtension::InputObject(input_object_type) => {
+                input_object_type.fields.is_empty()
+            }
+            _ => false,
+        }
+    }
     fn get_id_and_name(&self) -> (Option<String>, String) {
         let name = convert_text_to_string::<T>(match self {
             schema::TypeExtension::Enum(enum_type_extension) => &enum_type_extension.name,
@@ -343,7 +816,7 @@ where
             schema::TypeExtension::InputObject(input_object_type) => &input_object_type.name,
         });
 
-        (Some(get_extended_id(name.clone())), name)
+        (Some(get_extended_id(&name, 1)), name)
     }
     fn get_mapped_type(&self) -> GraphQL {
         match self {
@@ -357,6 +830,18 @@ where
             }
         }
     }
+    fn get_position(&self) -> (usize, usize) {
+        let position = match self {
+            schema::TypeExtension::Enum(enum_type) => enum_type.position,
+            schema::TypeExtension::Scalar(scalar_type) => scalar_type.position,
+            schema::TypeExtension::Object(object_type) => object_type.position,
+            schema::TypeExtension::Interface(interface_type) => interface_type.position,
+            schema::TypeExtension::Union(union_type) => union_type.position,
+            schema::TypeExtension::InputObject(input_object_type) => input_object_type.position,
+        };
+
+        (position.line, position.column)
+    }
     fn get_raw(&self) -> String {
         match self {
             schema::TypeExtension::Enum(enum_type) => enum_type.to_string(),
@@ -373,7 +858,7 @@ impl<'a, T> ExtendType for schema::SchemaDefinition<'a, T>
 where
     T: schema::Text<'a>,
 {
-    fn get_dependencies(&self) -> Vec<String> {
+    fn get_type_dependencies(&self) -> Vec<String> {
         sort_and_dedupe_dependencies(
             // A schema can only have a query, a mutation and a subscription.
             vec![&self.query, &self.mutation, &self.subscription]
@@ -386,6 +871,13 @@ where
                 .collect::<Vec<String>>(),
         )
     }
+    fn get_fields(&self) -> Vec<FieldDependencies> {
+        vec![]
+    }
+    fn get_description(&self) -> Option<String> {
+        // A Schema can't be described.
+        None
+    }
     fn get_id_and_name(&self) -> (Option<String>, String) {
         // A Schema has no name, use a default one.
         (None, String::from("schema"))
@@ -393,6 +885,9 @@ where
     fn get_mapped_type(&self) -> GraphQL {
         GraphQL::Schema
     }
+    fn get_position(&self) -> (usize, usize) {
+        (self.position.line, self.position.column)
+    }
     fn get_raw(&self) -> String {
         self.to_string()
     }
@@ -402,7 +897,7 @@ impl<'a, T> ExtendType for schema::DirectiveDefinition<'a, T>
 where
     T: schema::Text<'a>,
 {
-    fn get_dependencies(&self) -> Vec<String> {
+    fn get_type_dependencies(&self) -> Vec<String> {
         sort_and_dedupe_dependencies(
             self.arguments
                 .iter()
@@ -410,6 +905,40 @@ where
                 .collect::<Vec<String>>(),
         )
     }
+    fn get_fields(&self) -> Vec<FieldDependencies> {
+        vec![]
+    }
+    fn get_argument_types(&self) -> BTreeMap<String, String> {
+        let name = convert_text_to_directive::<T>(&self.name);
+
+        self.arguments
+            .iter()
+            .map(|input_value| {
+                (
+                    directive_argument_coordinate(
+                        &name,
+                        &convert_text_to_string::<T>(&input_value.name),
+                    ),
+                    walk_field_type(&input_value.value_type),
+                )
+            })
+            .collect()
+    }
+    fn get_references(&self) -> Vec<Reference> {
+        let name = convert_text_to_directive::<T>(&self.name);
+
+        self.arguments
+            .iter()
+            .map(|input_value| Reference {
+                field: name.clone(),
+                argument: Some(convert_text_to_string::<T>(&input_value.name)),
+                value_type: input_value.value_type.to_string(),
+            })
+            .collect()
+    }
+    fn get_description(&self) -> Option<String> {
+        trim_description(&self.description)
+    }
     fn get_id_and_name(&self) -> (Option<String>, String) {
         let name = convert_text_to_directive::<T>(&self.name);
         (None, name)
@@ -417,6 +946,9 @@ where
     fn get_mapped_type(&self) -> GraphQL {
         GraphQL::Directive
     }
+    fn get_position(&self) -> (usize, usize) {
+        (self.position.line, self.position.column)
+    }
     fn get_raw(&self) -> String {
         self.to_string()
     }
@@ -449,7 +981,7 @@ mod tests {
 
         let document = parse_schema::<String>(contents).unwrap().to_owned();
 
-        match document.definitions.get(0).unwrap().to_owned() {
+        match document.definitions.first().unwrap().to_owned() {
             schema::Definition::TypeDefinition(type_definition) => assert(
                 type_definition,
                 dependencies,
@@ -496,7 +1028,7 @@ mod tests {
         match_and_assert(
             "extend enum Foo @foo { D @bar }",
             vec!["@bar", "@foo", "Foo"],
-            (Some(String::from("Foo__")), String::from("Foo")),
+            (Some(String::from("Foo__1")), String::from("Foo")),
             GraphQL::TypeExtension(GraphQLType::Enum),
         );
     }
@@ -516,7 +1048,7 @@ mod tests {
         match_and_assert(
             "extend input Foo @test { woot: Int! @deprecated }",
             vec!["@deprecated", "@test", "Foo", "Int"],
-            (Some(String::from("Foo__")), String::from("Foo")),
+            (Some(String::from("Foo__1")), String::from("Foo")),
             GraphQL::TypeExtension(GraphQLType::InputObject),
         );
     }
@@ -536,7 +1068,7 @@ mod tests {
         match_and_assert(
             "extend interface Foo @test { woot: String! @deprecated }",
             vec!["@deprecated", "@test", "Foo", "String"],
-            (Some(String::from("Foo__")), String::from("Foo")),
+            (Some(String::from("Foo__1")), String::from("Foo")),
             GraphQL::TypeExtension(GraphQLType::Interface),
         );
     }
@@ -544,8 +1076,8 @@ mod tests {
     #[test]
     fn test_object() {
         match_and_assert(
-            "type Foo implements Bar @test { id: ID! @skip }",
-            vec!["@skip", "@test", "Bar", "ID"],
+            "type Foo implements Bar @test { id: ID! @skip parent: Foo bar: Bar otherId: ID @skip }",
+            vec!["@skip", "@test", "Bar", "Foo", "ID"],
             (None, String::from("Foo")),
             GraphQL::TypeDefinition(GraphQLType::Object),
         );
@@ -554,13 +1086,174 @@ mod tests {
     #[test]
     fn test_extend_object() {
         match_and_assert(
-            "extend type Foo @test { name: String! @skip }",
+            "extend type Foo @test { name: String! @skip alias: String @skip }",
             vec!["@skip", "@test", "Foo", "String"],
-            (Some(String::from("Foo__")), String::from("Foo")),
+            (Some(String::from("Foo__1")), String::from("Foo")),
             GraphQL::TypeExtension(GraphQLType::Object),
         );
     }
 
+    #[test]
+    fn test_fields() {
+        let document = parse_schema::<String>(
+            "type Foo @test { bar(first: Int): [Bar!] @skip baz: Baz } input FooInput { bar: Bar = null }",
+        )
+        .unwrap()
+        .to_owned();
+        let fields = document
+            .definitions
+            .into_iter()
+            .map(|definition| match definition {
+                schema::Definition::TypeDefinition(type_definition) => (
+                    type_definition.get_type_dependencies(),
+                    type_definition.get_fields(),
+                ),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(fields[0].0, vec!["@test"]);
+        assert_eq!(
+            fields[0].1,
+            vec![
+                FieldDependencies {
+                    name: String::from("bar"),
+                    dependencies: vec![
+                        String::from("@skip"),
+                        String::from("Bar"),
+                        String::from("Int")
+                    ],
+                    description: None,
+                    position: (1, 18),
+                    raw: String::from("bar(first: Int): [Bar!] @skip\n"),
+                },
+                FieldDependencies {
+                    name: String::from("baz"),
+                    dependencies: vec![String::from("Baz")],
+                    description: None,
+                    position: (1, 48),
+                    raw: String::from("baz: Baz\n"),
+                },
+            ]
+        );
+        assert!(fields[1].0.is_empty());
+        assert_eq!(fields[1].1[0].raw, "bar: Bar = null\n");
+    }
+
+    #[test]
+    fn test_descriptions() {
+        let document = parse_schema::<String>(
+            r#"
+            """
+            Foo docs.
+            """
+            type Foo { "Bar docs." bar: Bar baz: Baz }
+            extend type Foo { qux: Int }
+            "Directive docs."
+            directive @foo on FIELD_DEFINITION
+            "#,
+        )
+        .unwrap()
+        .to_owned();
+        let descriptions = document
+            .definitions
+            .into_iter()
+            .map(|definition| match definition {
+                schema::Definition::TypeDefinition(type_definition) => (
+                    type_definition.get_description(),
+                    type_definition
+                        .get_fields()
+                        .into_iter()
+                        .map(|field| field.description)
+                        .collect(),
+                ),
+                schema::Definition::TypeExtension(type_extension) => (
+                    type_extension.get_description(),
+                    type_extension
+                        .get_fields()
+                        .into_iter()
+                        .map(|field| field.description)
+                        .collect(),
+                ),
+                schema::Definition::DirectiveDefinition(directive_definition) => {
+                    (directive_definition.get_description(), vec![])
+                }
+                _ => unreachable!(),
+            })
+            .collect::<Vec<(Option<String>, Vec<Option<String>>)>>();
+
+        assert_eq!(
+            descriptions,
+            vec![
+                (
+                    Some(String::from("Foo docs.")),
+                    vec![Some(String::from("Bar docs.")), None]
+                ),
+                (None, vec![None]),
+                (Some(String::from("Directive docs.")), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_field_set() {
+        assert_eq!(
+            parse_field_set("id organization { id address(kind: HOME) { city } } __typename"),
+            (
+                vec![String::from("id"), String::from("organization")],
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse_field_set("id ... on Book { isbn }"),
+            (vec![String::from("id")], vec![String::from("Book")])
+        );
+        assert_eq!(parse_field_set(""), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_field_set_dependencies() {
+        let document = parse_schema::<String>(
+            r#"
+            type Product @key(fields: "sku organization { id }") @key(fields: "upc") {
+              upc: String!
+              sku: String!
+              organization: Organization!
+              dimensions: Dimension
+              shippingEstimate: Int @requires(fields: "dimensions { size }")
+              seller: Seller @provides(fields: "name ... on Shop { url }")
+            }
+            "#,
+        )
+        .unwrap()
+        .to_owned();
+
+        match document.definitions.first().unwrap() {
+            schema::Definition::TypeDefinition(type_definition) => assert_eq!(
+                type_definition.get_field_set_dependencies(),
+                FieldSetDependencies {
+                    keys: vec![String::from("Organization"), String::from("String")],
+                    fields: HashMap::from([
+                        (String::from("seller"), vec![String::from("Shop")]),
+                        (
+                            String::from("shippingEstimate"),
+                            vec![String::from("Dimension")]
+                        ),
+                    ]),
+                }
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_sort_and_dedupe_dependencies() {
+        assert_eq!(
+            sort_and_dedupe_dependencies(vec!["Int", "foo", "Int", "Foo", "@bar", "foo", "Int"]),
+            vec!["@bar", "Foo", "foo", "Int"]
+        );
+    }
+
     #[test]
     fn test_scalar() {
         match_and_assert(
@@ -576,7 +1269,7 @@ mod tests {
         match_and_assert(
             "extend scalar Foo @test",
             vec!["@test", "Foo"],
-            (Some(String::from("Foo__")), String::from("Foo")),
+            (Some(String::from("Foo__1")), String::from("Foo")),
             GraphQL::TypeExtension(GraphQLType::Scalar),
         );
     }
@@ -596,7 +1289,7 @@ mod tests {
         match_and_assert(
             "extend union Foo @test = D",
             vec!["@test", "D", "Foo"],
-            (Some(String::from("Foo__")), String::from("Foo")),
+            (Some(String::from("Foo__1")), String::from("Foo")),
             GraphQL::TypeExtension(GraphQLType::Union),
         );
     }
@@ -611,6 +1304,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_directive_arguments() {
+        match_and_assert(
+            r#"type Foo @auth(requires: ADMIN) @cache(scope: { level: PRIVATE }, ttl: 60) { bar: String @deprecated(reason: "Woot!") @tag(names: ["a", "b"]) baz: Int @auth(requires: [ADMIN, USER]) }"#,
+            vec![
+                "@auth",
+                "@auth(requires:)",
+                "@cache",
+                "@cache(scope:)",
+                "@cache(ttl:)",
+                "@deprecated",
+                "@deprecated(reason:)",
+                "@tag",
+                "@tag(names:)",
+                "Int",
+                "String",
+            ],
+            (None, String::from("Foo")),
+            GraphQL::TypeDefinition(GraphQLType::Object),
+        );
+
+        let document = parse_schema::<String>(
+            "directive @auth(requires: Role = ADMIN, reason: String) on OBJECT",
+        )
+        .unwrap()
+        .to_owned();
+
+        match document.definitions.first().unwrap() {
+            schema::Definition::DirectiveDefinition(directive_definition) => assert_eq!(
+                directive_definition.get_argument_types(),
+                BTreeMap::from([
+                    (String::from("@auth(reason:)"), String::from("String")),
+                    (String::from("@auth(requires:)"), String::from("Role")),
+                ])
+            ),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_schema() {
         match_and_assert(
@@ -620,4 +1352,110 @@ mod tests {
             GraphQL::Schema,
         );
     }
+
+    #[test]
+    fn test_interfaces() {
+        let document = parse_schema::<String>(
+            "type Foo implements Bar & Baz { id: ID! }
+            extend interface Bar implements Baz { id: ID! }
+            union Qux = Foo",
+        )
+        .unwrap();
+        let interfaces = document
+            .definitions
+            .iter()
+            .map(|definition| match definition {
+                schema::Definition::TypeDefinition(type_definition) => {
+                    type_definition.get_interfaces()
+                }
+                schema::Definition::TypeExtension(type_extension) => {
+                    type_extension.get_interfaces()
+                }
+                _ => vec![],
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        assert_eq!(
+            interfaces,
+            vec![
+                vec![String::from("Bar"), String::from("Baz")],
+                vec![String::from("Baz")],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_members() {
+        let document = parse_schema::<String>(
+            "union Foo @tag = Bar | Baz extend union Foo = Qux type Bar { id: ID }",
+        )
+        .unwrap();
+        let members = document
+            .definitions
+            .iter()
+            .map(|definition| match definition {
+                schema::Definition::TypeDefinition(type_definition) => {
+                    type_definition.get_members()
+                }
+                schema::Definition::TypeExtension(type_extension) => type_extension.get_members(),
+                _ => vec![],
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        assert_eq!(
+            members,
+            vec![
+                vec![String::from("Bar"), String::from("Baz")],
+                vec![String::from("Qux")],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let document = parse_schema::<String>(
+            "type Query { orders(filter: [OrderInput!], first: Int): [Order!]! } \
+             extend input OrderInput { since: Date } \
+             directive @cache(until: Date) on FIELD_DEFINITION",
+        )
+        .unwrap();
+        let reference = |field: &str, argument: Option<&str>, value_type: &str| Reference {
+            field: String::from(field),
+            argument: argument.map(String::from),
+            value_type: String::from(value_type),
+        };
+        let references = document
+            .definitions
+            .iter()
+            .map(|definition| match definition {
+                schema::Definition::TypeDefinition(type_definition) => {
+                    type_definition.get_references()
+                }
+                schema::Definition::TypeExtension(type_extension) => {
+                    type_extension.get_references()
+                }
+                schema::Definition::DirectiveDefinition(directive_definition) => {
+                    directive_definition.get_references()
+                }
+                _ => vec![],
+            })
+            .collect::<Vec<Vec<Reference>>>();
+
+        assert_eq!(
+            references,
+            vec![
+                vec![
+                    reference("Query.orders", None, "[Order!]!"),
+                    reference("Query.orders", Some("filter"), "[OrderInput!]"),
+                    reference("Query.orders", Some("first"), "Int"),
+                ],
+                vec![reference("OrderInput.since", None, "Date")],
+                vec![reference("@cache", Some("until"), "Date")],
+            ]
+        );
+        assert_eq!(references[0][1].coordinate(), "Query.orders(filter:)");
+        assert_eq!(references[0][1].named_type(), "OrderInput");
+    }
 }
@@ -0,0 +1,495 @@
+use crate::{
+    extend_types::ExtendType,
+    introspection::is_introspection_path,
+    state::{AnalysisOptions, GraphQL, GraphQLType, PathStyle},
+    utils::{is_blank_source, is_gzip_path, walk_files},
+};
+
+use anyhow::{bail, Context, Result};
+use async_std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use graphql_parser::{
+    parse_query,
+    schema::{parse_schema, Definition},
+};
+use std::{collections::HashMap, str::FromStr};
+
+/// Order of the definitions of a formatted source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DefinitionOrder {
+    /// Order of the source.
+    Source,
+    /// Sorted by name, the schema and the directives first.
+    #[default]
+    Name,
+    /// Grouped by kind, then sorted by name.
+    Kind,
+}
+
+impl FromStr for DefinitionOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "source" => Ok(DefinitionOrder::Source),
+            "name" => Ok(DefinitionOrder::Name),
+            "kind" => Ok(DefinitionOrder::Kind),
+            unknown => Err(format!(
+                r#"Unknown definition order provided "{}""#,
+                unknown
+            )),
+        }
+    }
+}
+
+/// Options of the formatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Order of the definitions.
+    pub order: DefinitionOrder,
+    /// Line width above which the arguments are wrapped, one per line.
+    pub width: usize,
+    /// Drop the comments following the leading ones instead of failing.
+    pub drop_comments: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            order: DefinitionOrder::default(),
+            width: 80,
+            drop_comments: false,
+        }
+    }
+}
+
+/// Indentation of graphql_parser, used for the wrapped arguments.
+const INDENT: &str = "  ";
+
+/// Sort key of a definition, the extensions following their base definition.
+fn sort_key(definition: &Definition<'_, String>, order: DefinitionOrder) -> (usize, String, bool) {
+    let (graphql, name) = match definition {
+        Definition::SchemaDefinition(schema_definition) => (
+            schema_definition.get_mapped_type(),
+            schema_definition.get_id_and_name().1,
+        ),
+        Definition::TypeDefinition(type_definition) => (
+            type_definition.get_mapped_type(),
+            type_definition.get_id_and_name().1,
+        ),
+        Definition::TypeExtension(type_extension) => (
+            type_extension.get_mapped_type(),
+            type_extension.get_id_and_name().1,
+        ),
+        Definition::DirectiveDefinition(directive_definition) => (
+            directive_definition.get_mapped_type(),
+            directive_definition.get_id_and_name().1,
+        ),
+    };
+    let is_schema = graphql == GraphQL::Schema;
+    let rank = match (graphql.base_type(), order) {
+        (Some(graphql_type), DefinitionOrder::Kind) => match graphql_type {
+            GraphQLType::Scalar => 2,
+            GraphQLType::Interface => 3,
+            GraphQLType::Object => 4,
+            GraphQLType::Union => 5,
+            GraphQLType::Enum => 6,
+            GraphQLType::InputObject => 7,
+        },
+        (Some(_), _) => 2,
+        (None, _) if is_schema => 0,
+        (None, _) => 1,
+    };
+
+    (rank, name, graphql.is_extension())
+}
+
+/// Split the arguments of a line at the top-level commas, returning the
+/// prefix up to the opening parenthesis, the arguments and the suffix from
+/// the closing one. None if the line has no arguments.
+fn split_arguments(line: &str) -> Option<(&str, Vec<&str>, &str)> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut open = None;
+    let mut start = 0;
+    let mut arguments = vec![];
+
+    for (index, character) in line.char_indices() {
+        if in_string {
+            match (escaped, character) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match character {
+            '"' => in_string = true,
+            '(' | '[' | '{' => {
+                if depth == 0 && character == '(' && open.is_none() {
+                    open = Some(index);
+                    start = index + 1;
+                }
+
+                depth += 1;
+            }
+            ')' | ']' | '}' => {
+                depth -= 1;
+
+                if depth == 0 && character == ')' {
+                    if let Some(open) = open {
+                        arguments.push(line[start..index].trim());
+
+                        return Some((&line[..=open], arguments, &line[index..]));
+                    }
+                }
+            }
+            ',' if depth == 1 && open.is_some() => {
+                arguments.push(line[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Wrap the arguments of the lines wider than the width, one per line,
+/// leaving the block strings untouched.
+fn wrap_arguments(formatted: &str, width: usize) -> String {
+    let mut wrapped = String::with_capacity(formatted.len());
+    let mut in_block_string = false;
+
+    for line in formatted.lines() {
+        let block_quotes = line.matches(r#"""""#).count() - line.matches(r#"\""""#).count();
+        let is_candidate = !in_block_string && block_quotes == 0 && line.chars().count() > width;
+
+        if block_quotes % 2 == 1 {
+            in_block_string = !in_block_string;
+        }
+
+        match is_candidate.then(|| split_arguments(line)).flatten() {
+            Some((prefix, arguments, suffix)) => {
+                let indent = &line[..line.len() - line.trim_start().len()];
+
+                wrapped.push_str(prefix);
+                wrapped.push('\n');
+
+                for argument in arguments {
+                    wrapped.push_str(&format!("{}{}{}\n", indent, INDENT, argument));
+                }
+
+                wrapped.push_str(&format!("{}{}\n", indent, suffix));
+            }
+            None => {
+                wrapped.push_str(line);
+                wrapped.push('\n');
+            }
+        }
+    }
+
+    wrapped
+}
+
+/// Return the leading comments of a source, e.g. the imports, the only ones
+/// kept by the formatter.
+fn leading_comments(contents: &str) -> String {
+    let mut comments = contents
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .collect::<Vec<&str>>();
+
+    while comments.last().map_or(false, |line| line.is_empty()) {
+        comments.pop();
+    }
+
+    while comments.first().map_or(false, |line| line.is_empty()) {
+        comments.remove(0);
+    }
+
+    comments.join("\n")
+}
+
+/// Return the lines of the comments of a source, skipping the hashes within
+/// the strings and the block strings.
+fn comment_lines(contents: &str) -> Vec<usize> {
+    // The characters looked for are ASCII, never part of a multibyte one.
+    let bytes = contents.as_bytes();
+    let mut lines = vec![];
+    let mut line = 1;
+    let mut index = 0;
+    let mut in_string = false;
+    let mut in_block_string = false;
+
+    while index < bytes.len() {
+        let rest = &bytes[index..];
+
+        match bytes[index] {
+            b'\\' if in_block_string && rest.starts_with(br#"\""""#) => index += 3,
+            b'\\' if in_string && rest.get(1).map_or(false, |byte| *byte != b'\n') => index += 1,
+            b'"' if !in_string && rest.starts_with(br#"""""#) => {
+                in_block_string = !in_block_string;
+                index += 2;
+            }
+            b'"' if !in_block_string => in_string = !in_string,
+            b'#' if !in_string && !in_block_string => {
+                lines.push(line);
+
+                while index + 1 < bytes.len() && bytes[index + 1] != b'\n' {
+                    index += 1;
+                }
+            }
+            b'\n' => line += 1,
+            _ => {}
+        }
+
+        index += 1;
+    }
+
+    lines
+}
+
+/// Format a schema source in canonical form. The fields keep their order and
+/// the descriptions are kept, but the comments are dropped by the parser,
+/// except the leading ones. The other comments fail the formatting unless
+/// dropped on purpose. None for a blank source or an executable document,
+/// which are left as is.
+pub fn format_source(contents: &str, options: &FormatOptions) -> Result<Option<String>> {
+    if is_blank_source(contents) {
+        return Ok(None);
+    }
+
+    let mut document = match parse_schema::<String>(contents) {
+        Ok(document) => document,
+        Err(_) if parse_query::<String>(contents).is_ok() => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+
+    if !options.drop_comments {
+        let leading_lines = contents
+            .lines()
+            .map(str::trim)
+            .take_while(|line| line.is_empty() || line.starts_with('#'))
+            .count();
+        let lines = comment_lines(contents)
+            .into_iter()
+            .filter(|line| *line > leading_lines)
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>();
+
+        match lines.len() {
+            0 => {}
+            1 => bail!(
+                "The comment on line {} would be dropped, pass --drop-comments to drop it",
+                lines[0]
+            ),
+            _ => bail!(
+                "The comments on lines {} would be dropped, pass --drop-comments to drop them",
+                lines.join(", ")
+            ),
+        }
+    }
+
+    if options.order != DefinitionOrder::Source {
+        // Stable, the duplicates and the extensions keep their order.
+        document
+            .definitions
+            .sort_by_cached_key(|definition| sort_key(definition, options.order));
+    }
+
+    let formatted = wrap_arguments(&document.to_string(), options.width);
+    let comments = leading_comments(contents);
+
+    Ok(Some(match comments.is_empty() {
+        true => formatted,
+        false => format!("{}\n\n{}", comments, formatted),
+    }))
+}
+
+/// Format the schema files of a path, returning the ones which would change
+/// with their formatted contents, sorted by path. The introspection results
+/// and the gzip-compressed files are skipped.
+pub async fn format_files(
+    path: PathBuf,
+    options: &AnalysisOptions,
+    format_options: &FormatOptions,
+) -> Result<Vec<(PathBuf, String)>> {
+    let files = Arc::new(Mutex::new(HashMap::new()));
+    // The files are written back, their paths are kept as walked.
+    let options = options.clone().path_style(PathStyle::AsProvided);
+
+    walk_files(path, files.clone(), Arc::new(options)).await?;
+
+    let files = files.lock().await;
+    let mut formatted_files = vec![];
+    // Sorted so that the first failing file is the same across runs.
+    let mut paths = files.keys().collect::<Vec<&PathBuf>>();
+
+    paths.sort();
+
+    for (path, contents) in paths.into_iter().map(|path| (path, &files[path])) {
+        if is_introspection_path(path) || is_gzip_path(path) {
+            continue;
+        }
+
+        let formatted = format_source(contents, format_options)
+            .with_context(|| format!("Failed to format {}", path.to_string_lossy()))?;
+
+        if let Some(formatted) = formatted {
+            if formatted != *contents {
+                formatted_files.push((path.clone(), formatted));
+            }
+        }
+    }
+
+    formatted_files.sort();
+
+    Ok(formatted_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = include_str!("../tests/format/messy.graphql");
+    const FORMATTED: &str = include_str!("../tests/format/formatted.graphql");
+
+    #[test]
+    fn check_format_source() {
+        let options = FormatOptions {
+            drop_comments: true,
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            format_source(MESSY, &options).unwrap(),
+            Some(String::from(FORMATTED))
+        );
+        assert_eq!(
+            format_source(FORMATTED, &FormatOptions::default()).unwrap(),
+            Some(String::from(FORMATTED))
+        );
+    }
+
+    #[test]
+    fn check_format_source_comments() {
+        let options = FormatOptions::default();
+
+        assert_eq!(
+            format_source(MESSY, &options).unwrap_err().to_string(),
+            "The comment on line 4 would be dropped, pass --drop-comments to drop it"
+        );
+        assert_eq!(
+            format_source("type A {\n  # a\n  id: ID # b\n}\n", &options)
+                .unwrap_err()
+                .to_string(),
+            "The comments on lines 2, 3 would be dropped, pass --drop-comments to drop them"
+        );
+
+        // The hashes of the strings aren't comments.
+        assert!(format_source(
+            "# leading\n\ntype A @key(fields: \"#id\\\"#\") {\n  \"\"\"\n  # \\\"\"\"\n  \"\"\"\n  id: ID\n}\n",
+            &options
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn check_format_source_idempotency() {
+        for order in [
+            DefinitionOrder::Source,
+            DefinitionOrder::Name,
+            DefinitionOrder::Kind,
+        ] {
+            let options = FormatOptions {
+                order,
+                width: 40,
+                drop_comments: true,
+            };
+            let once = format_source(MESSY, &options).unwrap().unwrap();
+
+            assert_eq!(format_source(&once, &options).unwrap(), Some(once));
+        }
+    }
+
+    #[test]
+    fn check_format_source_order() {
+        let names = |order| {
+            format_source(
+                "type B { id: ID } extend type A { b: B } enum Aa { D } type A { id: ID } directive @e on FIELD",
+                &FormatOptions {
+                    order,
+                    ..FormatOptions::default()
+                },
+            )
+            .unwrap()
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with(' ') && line.ends_with('{') || line.starts_with("directive"))
+            .map(String::from)
+            .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            names(DefinitionOrder::Source),
+            [
+                "type B {",
+                "extend type A {",
+                "enum Aa {",
+                "type A {",
+                "directive @e on FIELD"
+            ]
+        );
+        assert_eq!(
+            names(DefinitionOrder::Name),
+            [
+                "directive @e on FIELD",
+                "type A {",
+                "extend type A {",
+                "enum Aa {",
+                "type B {"
+            ]
+        );
+        assert_eq!(
+            names(DefinitionOrder::Kind),
+            [
+                "directive @e on FIELD",
+                "type A {",
+                "extend type A {",
+                "type B {",
+                "enum Aa {"
+            ]
+        );
+    }
+
+    #[test]
+    fn check_format_source_skipped() {
+        let options = FormatOptions::default();
+
+        assert_eq!(format_source("# only a comment\n", &options).unwrap(), None);
+        assert_eq!(
+            format_source("query { user { id } }", &options).unwrap(),
+            None
+        );
+        assert!(format_source("type {", &options).is_err());
+    }
+
+    #[test]
+    fn check_wrap_arguments() {
+        assert_eq!(
+            wrap_arguments(
+                "type Query {\n  users(first: Int = 10, filter: Filter = {a: [1, 2]}, after: String = \"a, (b)\"): [User] @auth\n}\n",
+                40
+            ),
+            "type Query {\n  users(\n    first: Int = 10\n    filter: Filter = {a: [1, 2]}\n    after: String = \"a, (b)\"\n  ): [User] @auth\n}\n"
+        );
+    }
+}
@@ -0,0 +1,453 @@
+use crate::{
+    config::{BUILT_IN_DIRECTIVES, BUILT_IN_SCALARS},
+    utils::inner_extension,
+};
+
+use anyhow::{Context, Result};
+use async_std::path::Path;
+use serde::Deserialize;
+
+/// Extension of the introspection results, e.g. schema.json.
+pub const INTROSPECTION_EXTENSION: &str = "json";
+
+/// Introspection result, either the whole response or its data.
+#[derive(Debug, Deserialize)]
+struct IntrospectionResult {
+    data: Option<IntrospectionData>,
+    #[serde(rename = "__schema")]
+    schema: Option<IntrospectionSchema>,
+}
+
+/// Data of an introspection response.
+#[derive(Debug, Deserialize)]
+struct IntrospectionData {
+    #[serde(rename = "__schema")]
+    schema: IntrospectionSchema,
+}
+
+/// Introspected schema.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionSchema {
+    query_type: Option<NamedTypeRef>,
+    mutation_type: Option<NamedTypeRef>,
+    subscription_type: Option<NamedTypeRef>,
+    types: Vec<FullType>,
+    #[serde(default)]
+    directives: Vec<DirectiveType>,
+}
+
+/// Reference to a root operation type.
+#[derive(Debug, Deserialize)]
+struct NamedTypeRef {
+    name: String,
+}
+
+/// Kind of an introspected type.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum TypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+    List,
+    NonNull,
+}
+
+/// Introspected named type.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FullType {
+    kind: TypeKind,
+    name: String,
+    description: Option<String>,
+    fields: Option<Vec<FieldType>>,
+    input_fields: Option<Vec<InputValueType>>,
+    interfaces: Option<Vec<TypeRef>>,
+    enum_values: Option<Vec<EnumValueType>>,
+    possible_types: Option<Vec<TypeRef>>,
+}
+
+/// Introspected field.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldType {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<InputValueType>,
+    #[serde(rename = "type")]
+    field_type: TypeRef,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+/// Introspected argument or input field.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InputValueType {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    value_type: TypeRef,
+    default_value: Option<String>,
+}
+
+/// Introspected enum value.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnumValueType {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+/// Introspected directive.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DirectiveType {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    locations: Vec<String>,
+    #[serde(default)]
+    args: Vec<InputValueType>,
+    #[serde(default)]
+    is_repeatable: bool,
+}
+
+/// Reference to a type, wrapped in lists and/or non-null types.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: TypeKind,
+    name: Option<String>,
+    of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    /// Print the reference as in SDL, e.g. [Foo!]!.
+    fn to_sdl(&self) -> Result<String> {
+        let of_type = || {
+            self.of_type
+                .as_deref()
+                .context("Missing ofType in an introspected type reference")
+        };
+
+        Ok(match self.kind {
+            TypeKind::NonNull => format!("{}!", of_type()?.to_sdl()?),
+            TypeKind::List => format!("[{}]", of_type()?.to_sdl()?),
+            _ => self
+                .name
+                .clone()
+                .context("Missing name in an introspected type reference")?,
+        })
+    }
+}
+
+/// Check if a path is an introspection result, from its extension.
+pub(crate) fn is_introspection_path(path: &Path) -> bool {
+    inner_extension(path) == INTROSPECTION_EXTENSION
+}
+
+/// Write a description as a block string.
+fn write_description(sdl: &mut String, description: &Option<String>, indent: &str) {
+    if let Some(description) = description {
+        sdl.push_str(&format!(
+            "{}\"\"\"{}\"\"\"\n",
+            indent,
+            description.replace("\"\"\"", "\\\"\"\"")
+        ));
+    }
+}
+
+/// Write the @deprecated directive of a deprecated field or enum value.
+fn write_deprecation(sdl: &mut String, is_deprecated: bool, reason: &Option<String>) {
+    match (is_deprecated, reason) {
+        // JSON strings are valid GraphQL strings.
+        (true, Some(reason)) => sdl.push_str(&format!(
+            " @deprecated(reason: {})",
+            serde_json::Value::from(reason.as_str())
+        )),
+        (true, None) => sdl.push_str(" @deprecated"),
+        _ => {}
+    }
+}
+
+/// Print arguments or input fields, inline or one per line.
+fn input_values_to_sdl(input_values: &[InputValueType], separator: &str) -> Result<String> {
+    input_values
+        .iter()
+        .map(|input_value| {
+            let mut sdl = String::new();
+
+            write_description(&mut sdl, &input_value.description, "");
+            sdl.push_str(&format!(
+                "{}: {}",
+                input_value.name,
+                input_value.value_type.to_sdl()?
+            ));
+
+            if let Some(ref default_value) = input_value.default_value {
+                sdl.push_str(&format!(" = {}", default_value));
+            }
+
+            Ok(sdl)
+        })
+        .collect::<Result<Vec<String>>>()
+        .map(|input_values| input_values.join(separator))
+}
+
+/// Print the implemented interfaces, if any.
+fn implements_to_sdl(interfaces: &Option<Vec<TypeRef>>) -> Result<String> {
+    let names = interfaces
+        .iter()
+        .flatten()
+        .map(TypeRef::to_sdl)
+        .collect::<Result<Vec<String>>>()?;
+
+    Ok(match names.is_empty() {
+        true => String::new(),
+        false => format!(" implements {}", names.join(" & ")),
+    })
+}
+
+/// Print a named type, the introspection types and built-in scalars being
+/// skipped.
+fn type_to_sdl(sdl: &mut String, full_type: &FullType) -> Result<()> {
+    if full_type.name.starts_with("__")
+        || (full_type.kind == TypeKind::Scalar
+            && BUILT_IN_SCALARS.contains(&full_type.name.as_str()))
+    {
+        return Ok(());
+    }
+
+    write_description(sdl, &full_type.description, "");
+
+    let name = &full_type.name;
+
+    match full_type.kind {
+        TypeKind::Scalar => {
+            sdl.push_str(&format!("scalar {}\n", name));
+        }
+        TypeKind::Object | TypeKind::Interface => {
+            let keyword = match full_type.kind {
+                TypeKind::Object => "type",
+                _ => "interface",
+            };
+
+            sdl.push_str(&format!(
+                "{} {}{} {{\n",
+                keyword,
+                name,
+                implements_to_sdl(&full_type.interfaces)?
+            ));
+
+            for field in full_type.fields.iter().flatten() {
+                write_description(sdl, &field.description, "  ");
+                sdl.push_str(&format!("  {}", field.name));
+
+                if !field.args.is_empty() {
+                    sdl.push_str(&format!("({})", input_values_to_sdl(&field.args, ", ")?));
+                }
+
+                sdl.push_str(&format!(": {}", field.field_type.to_sdl()?));
+                write_deprecation(sdl, field.is_deprecated, &field.deprecation_reason);
+                sdl.push('\n');
+            }
+
+            sdl.push_str("}\n");
+        }
+        TypeKind::Union => {
+            let members = full_type
+                .possible_types
+                .iter()
+                .flatten()
+                .map(TypeRef::to_sdl)
+                .collect::<Result<Vec<String>>>()?;
+
+            sdl.push_str(&format!("union {} = {}\n", name, members.join(" | ")));
+        }
+        TypeKind::Enum => {
+            sdl.push_str(&format!("enum {} {{\n", name));
+
+            for enum_value in full_type.enum_values.iter().flatten() {
+                write_description(sdl, &enum_value.description, "  ");
+                sdl.push_str(&format!("  {}", enum_value.name));
+                write_deprecation(
+                    sdl,
+                    enum_value.is_deprecated,
+                    &enum_value.deprecation_reason,
+                );
+                sdl.push('\n');
+            }
+
+            sdl.push_str("}\n");
+        }
+        TypeKind::InputObject => {
+            let input_fields = full_type.input_fields.as_deref().unwrap_or_default();
+
+            sdl.push_str(&format!(
+                "input {} {{\n{}\n}}\n",
+                name,
+                input_values_to_sdl(input_fields, "\n")?
+            ));
+        }
+        TypeKind::List | TypeKind::NonNull => {
+            anyhow::bail!(
+                "Unexpected wrapping type {} in the introspected types",
+                name
+            )
+        }
+    }
+
+    sdl.push('\n');
+
+    Ok(())
+}
+
+/// Print a directive definition, the built-in ones being skipped.
+fn directive_to_sdl(sdl: &mut String, directive: &DirectiveType) -> Result<()> {
+    if BUILT_IN_DIRECTIVES.contains(&format!("@{}", directive.name).as_str()) {
+        return Ok(());
+    }
+
+    write_description(sdl, &directive.description, "");
+    sdl.push_str(&format!("directive @{}", directive.name));
+
+    if !directive.args.is_empty() {
+        sdl.push_str(&format!(
+            "({})",
+            input_values_to_sdl(&directive.args, ", ")?
+        ));
+    }
+
+    if directive.is_repeatable {
+        sdl.push_str(" repeatable");
+    }
+
+    sdl.push_str(&format!(" on {}\n\n", directive.locations.join(" | ")));
+
+    Ok(())
+}
+
+/// Convert an introspection result, with or without its data envelope, to
+/// SDL. The schema definition is only written for non-default root operation
+/// type names. Applied directives aren't introspectable, except @deprecated.
+pub fn introspection_to_sdl(contents: &str) -> Result<String> {
+    let result = serde_json::from_str::<IntrospectionResult>(contents)
+        .context("Failed to deserialize the introspection result")?;
+    let schema = result
+        .data
+        .map(|data| data.schema)
+        .or(result.schema)
+        .context("Missing __schema in the introspection result")?;
+    let mut sdl = String::new();
+
+    let roots = [
+        ("query", &schema.query_type, "Query"),
+        ("mutation", &schema.mutation_type, "Mutation"),
+        ("subscription", &schema.subscription_type, "Subscription"),
+    ];
+
+    if roots.iter().any(|(_, root, default)| {
+        root.as_ref()
+            .map_or(false, |root| root.name.as_str() != *default)
+    }) {
+        sdl.push_str("schema {\n");
+
+        for (operation, root, _) in roots {
+            if let Some(root) = root {
+                sdl.push_str(&format!("  {}: {}\n", operation, root.name));
+            }
+        }
+
+        sdl.push_str("}\n\n");
+    }
+
+    for full_type in &schema.types {
+        type_to_sdl(&mut sdl, full_type)?;
+    }
+
+    for directive in &schema.directives {
+        directive_to_sdl(&mut sdl, directive)?;
+    }
+
+    Ok(sdl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_introspection_to_sdl() {
+        let contents = r#"{
+            "__schema": {
+                "queryType": { "name": "Root" },
+                "mutationType": null,
+                "subscriptionType": null,
+                "types": [
+                    {
+                        "kind": "OBJECT",
+                        "name": "Root",
+                        "description": null,
+                        "fields": [
+                            {
+                                "name": "ids",
+                                "description": "Some ids.",
+                                "args": [],
+                                "type": {
+                                    "kind": "LIST",
+                                    "name": null,
+                                    "ofType": {
+                                        "kind": "NON_NULL",
+                                        "name": null,
+                                        "ofType": { "kind": "SCALAR", "name": "ID", "ofType": null }
+                                    }
+                                },
+                                "isDeprecated": true,
+                                "deprecationReason": null
+                            }
+                        ],
+                        "interfaces": []
+                    },
+                    { "kind": "SCALAR", "name": "ID" },
+                    { "kind": "ENUM", "name": "__TypeKind", "enumValues": [] }
+                ],
+                "directives": [
+                    {
+                        "name": "tag",
+                        "locations": ["OBJECT", "FIELD_DEFINITION"],
+                        "args": [],
+                        "isRepeatable": true
+                    },
+                    { "name": "include", "locations": ["FIELD"], "args": [] }
+                ]
+            }
+        }"#;
+
+        assert_eq!(
+            introspection_to_sdl(contents).unwrap(),
+            r#"schema {
+  query: Root
+}
+
+type Root {
+  """Some ids."""
+  ids: [ID!] @deprecated
+}
+
+directive @tag repeatable on OBJECT | FIELD_DEFINITION
+
+"#
+        );
+        assert!(introspection_to_sdl(r#"{ "data": null }"#).is_err());
+    }
+}
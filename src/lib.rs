@@ -2,12 +2,36 @@
 #![deny(unsafe_code, nonstandard_style)]
 
 //! This library provides all the necessary methods and shared state for the craftql binary.
-//! Not meant to be used on its own! Primarily made for integration testing.
+//! Primarily made for integration testing, [`schema::Schema::from_path`] runs the whole
+//! analysis of a path in one call.
 
+/// Schema size budgets.
+pub mod budget;
+/// On-disk cache of the extracted entities.
+pub mod cache;
 /// Main onfiguration.
 pub mod config;
+/// Breaking change classification between two schemas.
+pub mod diff;
+/// Typed errors.
+pub mod error;
 /// Trait providing extension methods for graphql_parser::schema.
 pub mod extend_types;
+/// Canonical formatting of schema files.
+pub mod formatter;
+/// Introspection results converted to SDL.
+pub mod introspection;
+/// Schema lints.
+pub mod lint;
+/// Executable documents linked to the schema.
+pub mod operations;
+/// Remote schemas fetched via introspection.
+#[cfg(feature = "remote")]
+pub mod remote;
+/// Synchronous analysis API.
+pub mod schema;
+/// Snapshots of an analysis to detect schema drift.
+pub mod snapshot;
 /// Global state.
 pub mod state;
 /// Utilities consumed by the binary.
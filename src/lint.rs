@@ -0,0 +1,849 @@
+use crate::{
+    state::{Entity, GraphQL, GraphQLType, NodeIndices, SchemaGraph},
+    utils::{get_named_type, get_node},
+};
+
+use graphql_parser::{
+    parse_schema,
+    schema::{
+        Definition, Directive, Field, InputValue, Type, TypeDefinition, TypeExtension, Value,
+    },
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Non-null argument or input field defaulting to null.
+pub const NON_NULL_DEFAULT_NULL: &str = "non_null_default_null";
+
+/// Required argument added to an existing field by an extension.
+pub const REQUIRED_ARGUMENT_ON_EXTENDED_FIELD: &str = "required_argument_on_extended_field";
+
+/// Default value or directive argument referencing a value missing from its
+/// enum.
+pub const UNKNOWN_ENUM_VALUE: &str = "unknown_enum_value";
+
+/// Object or interface not covering the fields of an implemented interface
+/// with compatible signatures.
+pub const INCOMPATIBLE_INTERFACE_FIELD: &str = "incompatible_interface_field";
+
+/// Lint finding on a node.
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    /// Entity the finding is reported on.
+    pub entity: Entity,
+    /// Name of the rule.
+    pub rule: &'static str,
+    /// Description of the finding.
+    pub message: String,
+}
+
+/// Check if a rule is enabled, rules are enabled unless toggled off.
+fn is_enabled(rules: &BTreeMap<String, bool>, rule: &str) -> bool {
+    rules.get(rule).copied().unwrap_or(true)
+}
+
+/// Check if an input value is required, i.e. non-null without a default.
+fn is_required(input_value: &InputValue<'_, String>) -> bool {
+    matches!(input_value.value_type, Type::NonNullType(_)) && input_value.default_value.is_none()
+}
+
+/// Parse the raw representation of an entity back to its definition.
+pub(crate) fn parse_definition(entity: &Entity) -> Option<Definition<'_, String>> {
+    parse_schema::<String>(&entity.raw)
+        .ok()?
+        .definitions
+        .into_iter()
+        .next()
+}
+
+/// Return the fields of an object or interface definition or extension.
+pub(crate) fn get_fields(definition: Definition<'_, String>) -> Vec<Field<'_, String>> {
+    match definition {
+        Definition::TypeDefinition(TypeDefinition::Object(object)) => object.fields,
+        Definition::TypeDefinition(TypeDefinition::Interface(interface)) => interface.fields,
+        Definition::TypeExtension(TypeExtension::Object(object)) => object.fields,
+        Definition::TypeExtension(TypeExtension::Interface(interface)) => interface.fields,
+        _ => vec![],
+    }
+}
+
+/// Return the input values of a definition with their coordinate, e.g.
+/// Foo.bar(baz:) for an argument or FooInput.bar for an input field.
+pub(crate) fn get_input_values<'a>(
+    name: &str,
+    definition: Definition<'a, String>,
+) -> Vec<(String, InputValue<'a, String>)> {
+    let arguments = |fields: Vec<Field<'a, String>>| {
+        fields
+            .into_iter()
+            .flat_map(|field| {
+                let field_name = field.name;
+
+                field.arguments.into_iter().map(move |argument| {
+                    (
+                        format!("{}.{}({}:)", name, field_name, argument.name),
+                        argument,
+                    )
+                })
+            })
+            .collect::<Vec<(String, InputValue<'a, String>)>>()
+    };
+    let input_fields = |fields: Vec<InputValue<'a, String>>| {
+        fields
+            .into_iter()
+            .map(|field| (format!("{}.{}", name, field.name), field))
+            .collect::<Vec<(String, InputValue<'a, String>)>>()
+    };
+
+    match definition {
+        Definition::TypeDefinition(TypeDefinition::InputObject(input_object)) => {
+            input_fields(input_object.fields)
+        }
+        Definition::TypeExtension(TypeExtension::InputObject(input_object)) => {
+            input_fields(input_object.fields)
+        }
+        Definition::DirectiveDefinition(directive) => directive
+            .arguments
+            .into_iter()
+            .map(|argument| (format!("{}({}:)", name, argument.name), argument))
+            .collect(),
+        definition => arguments(get_fields(definition)),
+    }
+}
+
+/// Return the values of the enums, merged with the ones of their extensions.
+fn get_enum_values(graph: &SchemaGraph) -> BTreeMap<String, BTreeSet<String>> {
+    let mut enum_values: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let values = match parse_definition(&node.entity) {
+            Some(Definition::TypeDefinition(TypeDefinition::Enum(enum_type))) => enum_type.values,
+            Some(Definition::TypeExtension(TypeExtension::Enum(enum_type))) => enum_type.values,
+            _ => continue,
+        };
+
+        enum_values
+            .entry(node.entity.name.clone())
+            .or_default()
+            .extend(values.into_iter().map(|value| value.name));
+    }
+
+    enum_values
+}
+
+/// Return the named types of the fields of the input objects, merged with the
+/// ones of their extensions.
+fn get_input_field_types(graph: &SchemaGraph) -> BTreeMap<String, BTreeMap<String, String>> {
+    let mut input_field_types: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let fields = match parse_definition(&node.entity) {
+            Some(Definition::TypeDefinition(TypeDefinition::InputObject(input_object))) => {
+                input_object.fields
+            }
+            Some(Definition::TypeExtension(TypeExtension::InputObject(input_object))) => {
+                input_object.fields
+            }
+            _ => continue,
+        };
+
+        input_field_types
+            .entry(node.entity.name.clone())
+            .or_default()
+            .extend(
+                fields
+                    .into_iter()
+                    .map(|field| (field.name, get_named_type(&field.value_type).to_owned())),
+            );
+    }
+
+    input_field_types
+}
+
+/// Fields and implemented interfaces of an object or interface, merged with
+/// the ones of its extensions.
+#[derive(Default)]
+struct Implementor<'a> {
+    interface: bool,
+    interfaces: Vec<String>,
+    fields: Vec<Field<'a, String>>,
+}
+
+/// Return the objects and interfaces by name, merged with their extensions.
+fn get_implementors(graph: &SchemaGraph) -> BTreeMap<String, Implementor<'_>> {
+    let mut implementors: BTreeMap<String, Implementor<'_>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let (interface, interfaces, fields) = match parse_definition(&node.entity) {
+            Some(Definition::TypeDefinition(TypeDefinition::Object(object))) => {
+                (false, object.implements_interfaces, object.fields)
+            }
+            Some(Definition::TypeExtension(TypeExtension::Object(object))) => {
+                (false, object.implements_interfaces, object.fields)
+            }
+            Some(Definition::TypeDefinition(TypeDefinition::Interface(interface))) => {
+                (true, interface.implements_interfaces, interface.fields)
+            }
+            Some(Definition::TypeExtension(TypeExtension::Interface(interface))) => {
+                (true, interface.implements_interfaces, interface.fields)
+            }
+            _ => continue,
+        };
+        let implementor = implementors.entry(node.entity.name.clone()).or_default();
+
+        implementor.interface |= interface;
+        implementor.interfaces.extend(interfaces);
+        implementor.fields.extend(fields);
+    }
+
+    implementors
+}
+
+/// Return the members of the unions, merged with the ones of their extensions.
+fn get_union_members(graph: &SchemaGraph) -> BTreeMap<String, BTreeSet<String>> {
+    let mut union_members: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let types = match parse_definition(&node.entity) {
+            Some(Definition::TypeDefinition(TypeDefinition::Union(union))) => union.types,
+            Some(Definition::TypeExtension(TypeExtension::Union(union))) => union.types,
+            _ => continue,
+        };
+
+        union_members
+            .entry(node.entity.name.clone())
+            .or_default()
+            .extend(types);
+    }
+
+    union_members
+}
+
+/// Check if the type of a field is a valid implementation of the type of the
+/// interface field, i.e. the same type, a non-null or a subtype of it.
+/// http://spec.graphql.org/draft/#IsValidImplementationFieldType()
+fn is_valid_implementation_type(
+    field_type: &Type<'_, String>,
+    interface_type: &Type<'_, String>,
+    implementors: &BTreeMap<String, Implementor<'_>>,
+    union_members: &BTreeMap<String, BTreeSet<String>>,
+) -> bool {
+    let is_valid = |field_type, interface_type| {
+        is_valid_implementation_type(field_type, interface_type, implementors, union_members)
+    };
+
+    match (field_type, interface_type) {
+        (Type::NonNullType(field_type), Type::NonNullType(interface_type)) => {
+            is_valid(field_type, interface_type)
+        }
+        (Type::NonNullType(field_type), interface_type) => is_valid(field_type, interface_type),
+        (_, Type::NonNullType(_)) => false,
+        (Type::ListType(field_type), Type::ListType(interface_type)) => {
+            is_valid(field_type, interface_type)
+        }
+        (Type::ListType(_), _) | (_, Type::ListType(_)) => false,
+        (Type::NamedType(field_type), Type::NamedType(interface_type)) => {
+            field_type == interface_type
+                || union_members
+                    .get(interface_type)
+                    .map_or(false, |members| members.contains(field_type))
+                || implementors.get(field_type).map_or(false, |implementor| {
+                    implementor.interfaces.contains(interface_type)
+                })
+        }
+    }
+}
+
+/// Return the named types of the arguments of the directives, by directive
+/// name (without @) and argument name.
+fn get_directive_argument_types(graph: &SchemaGraph) -> BTreeMap<(String, String), String> {
+    graph
+        .node_weights()
+        .filter_map(|node| match parse_definition(&node.entity) {
+            Some(Definition::DirectiveDefinition(directive)) => Some(directive),
+            _ => None,
+        })
+        .flat_map(|directive| {
+            let name = directive.name;
+
+            directive.arguments.into_iter().map(move |argument| {
+                (
+                    (name.clone(), argument.name),
+                    get_named_type(&argument.value_type).to_owned(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Return the directives applied to a definition with the coordinate of the
+/// element they are applied on, e.g. Foo, Foo.bar, Foo.bar(baz:) or Foo.BAR.
+fn get_applied_directives<'a>(
+    name: &str,
+    definition: Definition<'a, String>,
+) -> Vec<(String, Directive<'a, String>)> {
+    let on = |coordinate: String, directives: Vec<Directive<'a, String>>| {
+        directives
+            .into_iter()
+            .map(move |directive| (coordinate.clone(), directive))
+    };
+    let fields = |fields: Vec<Field<'a, String>>| {
+        fields
+            .into_iter()
+            .flat_map(|field| {
+                let coordinate = format!("{}.{}", name, field.name);
+                let arguments = field
+                    .arguments
+                    .into_iter()
+                    .flat_map(|argument| {
+                        on(
+                            format!("{}.{}({}:)", name, field.name, argument.name),
+                            argument.directives,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                on(coordinate, field.directives).chain(arguments)
+            })
+            .collect::<Vec<_>>()
+    };
+    let input_fields = |fields: Vec<InputValue<'a, String>>| {
+        fields
+            .into_iter()
+            .flat_map(|field| on(format!("{}.{}", name, field.name), field.directives))
+            .collect::<Vec<_>>()
+    };
+    let root = |directives: Vec<Directive<'a, String>>| on(name.to_owned(), directives);
+
+    match definition {
+        Definition::TypeDefinition(TypeDefinition::Object(object)) => root(object.directives)
+            .chain(fields(object.fields))
+            .collect(),
+        Definition::TypeExtension(TypeExtension::Object(object)) => root(object.directives)
+            .chain(fields(object.fields))
+            .collect(),
+        Definition::TypeDefinition(TypeDefinition::Interface(interface)) => {
+            root(interface.directives)
+                .chain(fields(interface.fields))
+                .collect()
+        }
+        Definition::TypeExtension(TypeExtension::Interface(interface)) => {
+            root(interface.directives)
+                .chain(fields(interface.fields))
+                .collect()
+        }
+        Definition::TypeDefinition(TypeDefinition::InputObject(input_object)) => {
+            root(input_object.directives)
+                .chain(input_fields(input_object.fields))
+                .collect()
+        }
+        Definition::TypeExtension(TypeExtension::InputObject(input_object)) => {
+            root(input_object.directives)
+                .chain(input_fields(input_object.fields))
+                .collect()
+        }
+        Definition::TypeDefinition(TypeDefinition::Enum(enum_type)) => root(enum_type.directives)
+            .chain(
+                enum_type
+                    .values
+                    .into_iter()
+                    .flat_map(|value| on(format!("{}.{}", name, value.name), value.directives)),
+            )
+            .collect(),
+        Definition::TypeExtension(TypeExtension::Enum(enum_type)) => root(enum_type.directives)
+            .chain(
+                enum_type
+                    .values
+                    .into_iter()
+                    .flat_map(|value| on(format!("{}.{}", name, value.name), value.directives)),
+            )
+            .collect(),
+        Definition::TypeDefinition(TypeDefinition::Scalar(scalar)) => {
+            root(scalar.directives).collect()
+        }
+        Definition::TypeExtension(TypeExtension::Scalar(scalar)) => {
+            root(scalar.directives).collect()
+        }
+        Definition::TypeDefinition(TypeDefinition::Union(union)) => {
+            root(union.directives).collect()
+        }
+        Definition::TypeExtension(TypeExtension::Union(union)) => root(union.directives).collect(),
+        Definition::DirectiveDefinition(directive) => directive
+            .arguments
+            .into_iter()
+            .flat_map(|argument| on(format!("{}({}:)", name, argument.name), argument.directives))
+            .collect(),
+        Definition::SchemaDefinition(schema) => {
+            on(String::from("schema"), schema.directives).collect()
+        }
+    }
+}
+
+/// Return the enum values referenced by a value of a type missing from their
+/// enum, along with the name of the enum. The input objects are walked down
+/// their fields.
+fn get_unknown_enum_values(
+    value: &Value<'_, String>,
+    type_name: &str,
+    enum_values: &BTreeMap<String, BTreeSet<String>>,
+    input_field_types: &BTreeMap<String, BTreeMap<String, String>>,
+) -> Vec<(String, String)> {
+    match value {
+        Value::Enum(value) => match enum_values.get(type_name) {
+            Some(values) if !values.contains(value) => {
+                vec![(value.to_owned(), type_name.to_owned())]
+            }
+            _ => vec![],
+        },
+        Value::List(list) => list
+            .iter()
+            .flat_map(|value| {
+                get_unknown_enum_values(value, type_name, enum_values, input_field_types)
+            })
+            .collect(),
+        Value::Object(object) => match input_field_types.get(type_name) {
+            Some(field_types) => object
+                .iter()
+                .filter_map(|(name, value)| {
+                    field_types.get(name).map(|field_type| {
+                        get_unknown_enum_values(value, field_type, enum_values, input_field_types)
+                    })
+                })
+                .flatten()
+                .collect(),
+            None => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// Report the non-null input values defaulting to null.
+fn lint_non_null_default_null(entity: &Entity, findings: &mut Vec<LintFinding>) {
+    let definition = match parse_definition(entity) {
+        Some(definition) => definition,
+        None => return,
+    };
+
+    for (coordinate, input_value) in get_input_values(&entity.name, definition) {
+        if matches!(input_value.value_type, Type::NonNullType(_))
+            && matches!(input_value.default_value, Some(Value::Null))
+        {
+            findings.push(LintFinding {
+                entity: entity.clone(),
+                rule: NON_NULL_DEFAULT_NULL,
+                message: format!("{} is non-null but defaults to null", coordinate),
+            });
+        }
+    }
+}
+
+/// Report the required arguments added by an extension to the fields of its
+/// base type.
+fn lint_required_argument_on_extended_field(
+    entity: &Entity,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+    findings: &mut Vec<LintFinding>,
+) {
+    let base = match entity
+        .extends
+        .as_deref()
+        .and_then(|extends| get_node(extends, graph, node_indices))
+    {
+        Some(base) => base,
+        None => return,
+    };
+    let (base_fields, extension_fields) = match (parse_definition(&base), parse_definition(entity))
+    {
+        (Some(base), Some(extension)) => (get_fields(base), get_fields(extension)),
+        _ => return,
+    };
+
+    for field in extension_fields {
+        let base_field = match base_fields
+            .iter()
+            .find(|base_field| base_field.name == field.name)
+        {
+            Some(base_field) => base_field,
+            None => continue,
+        };
+
+        for argument in field.arguments.iter().filter(|argument| {
+            is_required(argument)
+                && !base_field
+                    .arguments
+                    .iter()
+                    .any(|base_argument| base_argument.name == argument.name)
+        }) {
+            findings.push(LintFinding {
+                entity: entity.clone(),
+                rule: REQUIRED_ARGUMENT_ON_EXTENDED_FIELD,
+                message: format!(
+                    "{}.{}({}:) is a required argument added to an existing field",
+                    entity.name, field.name, argument.name
+                ),
+            });
+        }
+    }
+}
+
+/// Report the default values and directive arguments referencing a value
+/// missing from their enum.
+fn lint_unknown_enum_value(
+    entity: &Entity,
+    enum_values: &BTreeMap<String, BTreeSet<String>>,
+    input_field_types: &BTreeMap<String, BTreeMap<String, String>>,
+    directive_argument_types: &BTreeMap<(String, String), String>,
+    findings: &mut Vec<LintFinding>,
+) {
+    let definition = match parse_definition(entity) {
+        Some(definition) => definition,
+        None => return,
+    };
+    let mut report = |message: String| {
+        findings.push(LintFinding {
+            entity: entity.clone(),
+            rule: UNKNOWN_ENUM_VALUE,
+            message,
+        })
+    };
+
+    for (coordinate, input_value) in get_input_values(&entity.name, definition.clone()) {
+        let default_value = match &input_value.default_value {
+            Some(default_value) => default_value,
+            None => continue,
+        };
+
+        for (value, enum_name) in get_unknown_enum_values(
+            default_value,
+            get_named_type(&input_value.value_type),
+            enum_values,
+            input_field_types,
+        ) {
+            report(format!(
+                "{} defaults to {} which is not a value of {}",
+                coordinate, value, enum_name
+            ));
+        }
+    }
+
+    for (coordinate, directive) in get_applied_directives(&entity.name, definition) {
+        for (argument, value) in &directive.arguments {
+            let type_name =
+                match directive_argument_types.get(&(directive.name.clone(), argument.clone())) {
+                    Some(type_name) => type_name,
+                    None => continue,
+                };
+
+            for (value, enum_name) in
+                get_unknown_enum_values(value, type_name, enum_values, input_field_types)
+            {
+                report(format!(
+                    "@{}({}:) on {} references {} which is not a value of {}",
+                    directive.name, argument, coordinate, value, enum_name
+                ));
+            }
+        }
+    }
+}
+
+/// Report the fields of the implemented interfaces missing from an object or
+/// interface, or defined with incompatible types or arguments.
+fn lint_incompatible_interface_field(
+    entity: &Entity,
+    implementors: &BTreeMap<String, Implementor<'_>>,
+    union_members: &BTreeMap<String, BTreeSet<String>>,
+    findings: &mut Vec<LintFinding>,
+) {
+    let implementor = match implementors.get(&entity.name) {
+        Some(implementor) => implementor,
+        None => return,
+    };
+    let mut report = |message: String| {
+        findings.push(LintFinding {
+            entity: entity.clone(),
+            rule: INCOMPATIBLE_INTERFACE_FIELD,
+            message,
+        })
+    };
+
+    // The undefined interfaces are reported as missing definitions.
+    for (interface_name, interface) in implementor.interfaces.iter().filter_map(|name| {
+        implementors
+            .get(name)
+            .filter(|interface| interface.interface)
+            .map(|interface| (name, interface))
+    }) {
+        for interface_field in &interface.fields {
+            let coordinate = format!("{}.{}", entity.name, interface_field.name);
+            let field = match implementor
+                .fields
+                .iter()
+                .find(|field| field.name == interface_field.name)
+            {
+                Some(field) => field,
+                None => {
+                    report(format!(
+                        "{} is missing, required by {}",
+                        coordinate, interface_name
+                    ));
+
+                    continue;
+                }
+            };
+
+            if !is_valid_implementation_type(
+                &field.field_type,
+                &interface_field.field_type,
+                implementors,
+                union_members,
+            ) {
+                report(format!(
+                    "{} is of type {} which is not compatible with {} of {}",
+                    coordinate, field.field_type, interface_field.field_type, interface_name
+                ));
+            }
+
+            for interface_argument in &interface_field.arguments {
+                match field
+                    .arguments
+                    .iter()
+                    .find(|argument| argument.name == interface_argument.name)
+                {
+                    Some(argument) if argument.value_type != interface_argument.value_type => {
+                        report(format!(
+                            "{}({}:) is of type {} instead of {} as in {}",
+                            coordinate,
+                            argument.name,
+                            argument.value_type,
+                            interface_argument.value_type,
+                            interface_name
+                        ))
+                    }
+                    Some(_) => {}
+                    None => report(format!(
+                        "{}({}:) is missing, required by {}",
+                        coordinate, interface_argument.name, interface_name
+                    )),
+                }
+            }
+
+            for argument in field.arguments.iter().filter(|argument| {
+                is_required(argument)
+                    && !interface_field
+                        .arguments
+                        .iter()
+                        .any(|interface_argument| interface_argument.name == argument.name)
+            }) {
+                report(format!(
+                    "{}({}:) is a required argument not defined by {}",
+                    coordinate, argument.name, interface_name
+                ));
+            }
+        }
+    }
+}
+
+/// Lint the nodes of the graph with the enabled rules, rules are toggled by
+/// name and enabled by default.
+pub fn lint(
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+    rules: &BTreeMap<String, bool>,
+) -> Vec<LintFinding> {
+    let mut findings = vec![];
+    let (enum_values, input_field_types, directive_argument_types) =
+        if is_enabled(rules, UNKNOWN_ENUM_VALUE) {
+            (
+                get_enum_values(graph),
+                get_input_field_types(graph),
+                get_directive_argument_types(graph),
+            )
+        } else {
+            (BTreeMap::new(), BTreeMap::new(), BTreeMap::new())
+        };
+    let (implementors, union_members) = if is_enabled(rules, INCOMPATIBLE_INTERFACE_FIELD) {
+        (get_implementors(graph), get_union_members(graph))
+    } else {
+        (BTreeMap::new(), BTreeMap::new())
+    };
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        if is_enabled(rules, NON_NULL_DEFAULT_NULL) {
+            lint_non_null_default_null(entity, &mut findings);
+        }
+
+        if is_enabled(rules, UNKNOWN_ENUM_VALUE) {
+            lint_unknown_enum_value(
+                entity,
+                &enum_values,
+                &input_field_types,
+                &directive_argument_types,
+                &mut findings,
+            );
+        }
+
+        if is_enabled(rules, REQUIRED_ARGUMENT_ON_EXTENDED_FIELD)
+            && matches!(
+                entity.graphql,
+                GraphQL::TypeExtension(GraphQLType::Object)
+                    | GraphQL::TypeExtension(GraphQLType::Interface)
+            )
+        {
+            lint_required_argument_on_extended_field(entity, graph, node_indices, &mut findings);
+        }
+
+        // Reported once on the definition, the extensions being merged.
+        if is_enabled(rules, INCOMPATIBLE_INTERFACE_FIELD)
+            && matches!(
+                entity.graphql,
+                GraphQL::TypeDefinition(GraphQLType::Object)
+                    | GraphQL::TypeDefinition(GraphQLType::Interface)
+            )
+        {
+            lint_incompatible_interface_field(entity, &implementors, &union_members, &mut findings);
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema::Schema;
+    use async_std::path::PathBuf;
+
+    fn lint_sources(contents: &str, rules: &BTreeMap<String, bool>) -> Vec<LintFinding> {
+        let schema = Schema::from_sources(vec![(
+            PathBuf::from("some_path/schema.gql"),
+            String::from(contents),
+        )])
+        .unwrap();
+
+        lint(schema.graph(), schema.node_indices(), rules)
+    }
+
+    #[test]
+    fn check_non_null_default_null() {
+        let findings = lint_sources(
+            r#"
+            type Query { users(first: Int! = null, after: ID = null, last: Int! = 10): [ID] }
+            input UserInput { name: String! = null }
+            directive @limit(max: Int! = null) on FIELD_DEFINITION
+            "#,
+            &BTreeMap::new(),
+        );
+        let mut messages = findings
+            .iter()
+            .map(|finding| finding.message.as_str())
+            .collect::<Vec<&str>>();
+        messages.sort_unstable();
+
+        assert!(findings
+            .iter()
+            .all(|finding| finding.rule == NON_NULL_DEFAULT_NULL));
+        assert_eq!(
+            messages,
+            vec![
+                "@limit(max:) is non-null but defaults to null",
+                "Query.users(first:) is non-null but defaults to null",
+                "UserInput.name is non-null but defaults to null",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_required_argument_on_extended_field() {
+        let contents = r#"
+            type User { posts(first: Int): [ID] friends: [ID] }
+            extend type User { posts(first: Int, status: String!, sort: String! = "asc"): [ID] }
+            extend type User { avatar(size: Int!): String }
+            "#;
+        let findings = lint_sources(contents, &BTreeMap::new());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, REQUIRED_ARGUMENT_ON_EXTENDED_FIELD);
+        assert_eq!(findings[0].entity.id, "User__1");
+        assert_eq!(
+            findings[0].message,
+            "User.posts(status:) is a required argument added to an existing field"
+        );
+
+        // Rules can be toggled off.
+        let rules = BTreeMap::from([(String::from(REQUIRED_ARGUMENT_ON_EXTENDED_FIELD), false)]);
+
+        assert!(lint_sources(contents, &rules).is_empty());
+    }
+
+    #[test]
+    fn check_unknown_enum_value() {
+        let contents = r#"
+            directive @auth(requires: Role) on OBJECT | FIELD_DEFINITION
+            enum Role { ADMIN }
+            extend enum Role { EDITOR }
+            input UserInput { role: Role = EDITOR roles: [Role] = [ADMIN, OWNER] }
+            extend input UserInput { filter: UserInput }
+            type Query {
+                users(role: Role = VIEWER, input: UserInput = { roles: [EDITOR], filter: { role: GUEST } }): [User] @auth(requires: ADMIN)
+            }
+            type User @auth(requires: EDITOR) { name(role: Role @auth(requires: GUEST)): String }
+            "#;
+        let findings = lint_sources(contents, &BTreeMap::new());
+        let mut messages = findings
+            .iter()
+            .map(|finding| finding.message.as_str())
+            .collect::<Vec<&str>>();
+        messages.sort_unstable();
+
+        assert!(findings
+            .iter()
+            .all(|finding| finding.rule == UNKNOWN_ENUM_VALUE));
+        assert_eq!(
+            messages,
+            vec![
+                "@auth(requires:) on User.name(role:) references GUEST which is not a value of Role",
+                "Query.users(input:) defaults to GUEST which is not a value of Role",
+                "Query.users(role:) defaults to VIEWER which is not a value of Role",
+                "UserInput.roles defaults to OWNER which is not a value of Role",
+            ]
+        );
+
+        let rules = BTreeMap::from([(String::from(UNKNOWN_ENUM_VALUE), false)]);
+
+        assert!(lint_sources(contents, &rules).is_empty());
+    }
+
+    #[test]
+    fn check_incompatible_interface_field() {
+        let contents = r#"
+            interface Node { id: ID! friends(first: Int): [Node] }
+            type User implements Node { id: ID! friends(first: Int, after: ID): [User!]! }
+            type Bot implements Node { friends(first: String): Node }
+            extend type Bot { id: ID }
+            "#;
+        let findings = lint_sources(contents, &BTreeMap::new());
+
+        assert!(findings
+            .iter()
+            .all(|finding| finding.rule == INCOMPATIBLE_INTERFACE_FIELD
+                && finding.entity.name == "Bot"));
+        assert_eq!(
+            findings
+                .iter()
+                .map(|finding| finding.message.as_str())
+                .collect::<Vec<&str>>(),
+            [
+                "Bot.id is of type ID which is not compatible with ID! of Node",
+                "Bot.friends is of type Node which is not compatible with [Node] of Node",
+                "Bot.friends(first:) is of type String instead of Int as in Node",
+            ]
+        );
+
+        let rules = BTreeMap::from([(String::from(INCOMPATIBLE_INTERFACE_FIELD), false)]);
+
+        assert!(lint_sources(contents, &rules).is_empty());
+    }
+}
@@ -0,0 +1,646 @@
+use crate::{
+    extend_types::sort_and_dedupe_dependencies,
+    lint::{get_fields, parse_definition},
+    state::{
+        AnalysisOptions, Data, Entity, GraphQL, GraphQLType, Interner, Name, NodeIndices,
+        SchemaGraph, State, UnusedFields,
+    },
+    utils::{
+        add_node_and_dependencies, collect_files, get_named_type, is_blank_source, wire_edges,
+    },
+};
+
+use anyhow::{Context, Result};
+use async_std::path::{Path, PathBuf};
+use graphql_parser::{
+    parse_query, parse_schema,
+    query::{
+        Definition, Directive, OperationDefinition, Selection, SelectionSet, TypeCondition,
+        VariableDefinition,
+    },
+    schema, Pos,
+};
+use petgraph::{graph::NodeIndex, Direction};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Named types of the fields of the objects and interfaces, by type and field.
+type FieldTypes = HashMap<(String, String), String>;
+
+/// Return the named types of the fields of the objects and interfaces, their
+/// extensions included. Relies on the raw SDL of the entities.
+fn field_types(graph: &SchemaGraph) -> FieldTypes {
+    graph
+        .node_weights()
+        .filter(|node| node.entity.graphql.base_type().is_some())
+        .flat_map(|node| {
+            let name = &node.entity.name;
+
+            parse_definition(&node.entity)
+                .map(get_fields)
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |field| {
+                    (
+                        (name.clone(), field.name),
+                        get_named_type(&field.field_type).to_owned(),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Return the root operation types of the root schema, or the default ones,
+/// as (query, mutation, subscription).
+pub(crate) fn root_types(graph: &SchemaGraph) -> (String, String, String) {
+    let schema = graph
+        .node_weights()
+        .find(|node| node.entity.graphql == GraphQL::Schema)
+        .and_then(|node| match parse_definition(&node.entity) {
+            Some(schema::Definition::SchemaDefinition(schema)) => Some(schema),
+            _ => None,
+        });
+
+    match schema {
+        Some(schema) => (
+            schema.query.unwrap_or_default(),
+            schema.mutation.unwrap_or_default(),
+            schema.subscription.unwrap_or_default(),
+        ),
+        None => (
+            String::from("Query"),
+            String::from("Mutation"),
+            String::from("Subscription"),
+        ),
+    }
+}
+
+/// Return the id of a named operation, apart from the ids of the types.
+fn operation_id(name: &str) -> String {
+    format!("operation:{}", name)
+}
+
+/// Return the id of a fragment, apart from the ids of the types.
+fn fragment_id(name: &str) -> String {
+    format!("fragment:{}", name)
+}
+
+/// Get the directives used in an executable document.
+fn get_directives(directives: &[Directive<'_, String>]) -> Vec<String> {
+    directives
+        .iter()
+        .map(|directive| format!("@{}", directive.name))
+        .collect()
+}
+
+/// Recursively walk a selection set on a type, if known, to get the
+/// dependencies. A selected field depends on its node with the field
+/// granularity, on its type otherwise, an unknown field on its coordinate.
+fn walk_selection_set(
+    selection_set: &SelectionSet<'_, String>,
+    parent: Option<&str>,
+    field_types: &FieldTypes,
+    node_indices: &NodeIndices,
+    dependencies: &mut Vec<String>,
+) {
+    for selection in &selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                dependencies.extend(get_directives(&field.directives));
+
+                // Skip the introspection fields, e.g. __typename.
+                if field.name.starts_with("__") {
+                    continue;
+                }
+
+                let field_type = parent.and_then(|parent| {
+                    let coordinate = format!("{}.{}", parent, field.name);
+
+                    match field_types.get(&(parent.to_owned(), field.name.clone())) {
+                        Some(field_type) => {
+                            dependencies.push(match node_indices.contains_key(&*coordinate) {
+                                true => coordinate,
+                                false => field_type.clone(),
+                            });
+
+                            Some(field_type.as_str())
+                        }
+                        None => {
+                            // Only report the fields of the known types.
+                            if node_indices.contains_key(parent) {
+                                dependencies.push(coordinate);
+                            }
+
+                            None
+                        }
+                    }
+                });
+
+                walk_selection_set(
+                    &field.selection_set,
+                    field_type,
+                    field_types,
+                    node_indices,
+                    dependencies,
+                );
+            }
+            Selection::FragmentSpread(fragment_spread) => {
+                dependencies.push(fragment_id(&fragment_spread.fragment_name));
+                dependencies.extend(get_directives(&fragment_spread.directives));
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                let parent = match &inline_fragment.type_condition {
+                    Some(TypeCondition::On(type_condition)) => {
+                        dependencies.push(type_condition.clone());
+
+                        Some(type_condition.as_str())
+                    }
+                    None => parent,
+                };
+
+                dependencies.extend(get_directives(&inline_fragment.directives));
+                walk_selection_set(
+                    &inline_fragment.selection_set,
+                    parent,
+                    field_types,
+                    node_indices,
+                    dependencies,
+                );
+            }
+        }
+    }
+}
+
+/// Schema the executable documents are resolved against.
+struct Resolver<'a> {
+    field_types: FieldTypes,
+    node_indices: &'a NodeIndices,
+    root_types: (String, String, String),
+}
+
+impl Resolver<'_> {
+    /// Get the dependencies of an operation or a fragment on a type.
+    fn get_dependencies(
+        &self,
+        root: &str,
+        variables: &[VariableDefinition<'_, String>],
+        directives: &[Directive<'_, String>],
+        selection_set: &SelectionSet<'_, String>,
+    ) -> Vec<String> {
+        let mut dependencies = vec![root.to_owned()];
+
+        dependencies.extend(
+            variables
+                .iter()
+                .map(|variable| get_named_type(&variable.var_type).to_owned()),
+        );
+        dependencies.extend(get_directives(directives));
+        walk_selection_set(
+            selection_set,
+            Some(root),
+            &self.field_types,
+            self.node_indices,
+            &mut dependencies,
+        );
+
+        sort_and_dedupe_dependencies(dependencies)
+    }
+
+    /// Get the GraphQL type, the name, the position and the dependencies of
+    /// a named operation or a fragment, anonymous operations being skipped.
+    fn resolve(
+        &self,
+        definition: &Definition<'_, String>,
+    ) -> Option<(GraphQL, String, Pos, Vec<String>)> {
+        let (query, mutation, subscription) = &self.root_types;
+
+        match definition {
+            Definition::Operation(OperationDefinition::Query(operation)) => Some((
+                GraphQL::Operation,
+                operation.name.clone()?,
+                operation.position,
+                self.get_dependencies(
+                    query,
+                    &operation.variable_definitions,
+                    &operation.directives,
+                    &operation.selection_set,
+                ),
+            )),
+            Definition::Operation(OperationDefinition::Mutation(operation)) => Some((
+                GraphQL::Operation,
+                operation.name.clone()?,
+                operation.position,
+                self.get_dependencies(
+                    mutation,
+                    &operation.variable_definitions,
+                    &operation.directives,
+                    &operation.selection_set,
+                ),
+            )),
+            Definition::Operation(OperationDefinition::Subscription(operation)) => Some((
+                GraphQL::Operation,
+                operation.name.clone()?,
+                operation.position,
+                self.get_dependencies(
+                    subscription,
+                    &operation.variable_definitions,
+                    &operation.directives,
+                    &operation.selection_set,
+                ),
+            )),
+            Definition::Operation(OperationDefinition::SelectionSet(_)) => None,
+            Definition::Fragment(fragment) => {
+                let TypeCondition::On(type_condition) = &fragment.type_condition;
+
+                Some((
+                    GraphQL::Fragment,
+                    fragment.name.clone(),
+                    fragment.position,
+                    self.get_dependencies(
+                        type_condition,
+                        &[],
+                        &fragment.directives,
+                        &fragment.selection_set,
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+/// Parse an executable document and return the entities of its named
+/// operations and fragments. Schema documents, living next to the
+/// operations, are skipped.
+fn parse_operations_source(
+    path: &Path,
+    contents: &str,
+    resolver: &Resolver<'_>,
+    options: &AnalysisOptions,
+) -> Result<Vec<Entity>> {
+    if is_blank_source(contents) {
+        return Ok(vec![]);
+    }
+
+    let document = match parse_query::<String>(contents) {
+        Ok(document) => document,
+        Err(_) if parse_schema::<String>(contents).is_ok() => return Ok(vec![]),
+        Err(error) => return Err(error).context(format!("Failed to parse {}", path.display())),
+    };
+
+    Ok(document
+        .definitions
+        .iter()
+        .filter_map(|definition| {
+            let (graphql, name, position, dependencies) = resolver.resolve(definition)?;
+
+            options.is_graphql_allowed(&graphql).then(|| {
+                let id = match graphql {
+                    GraphQL::Fragment => fragment_id(&name),
+                    _ => operation_id(&name),
+                };
+
+                Entity::new(
+                    dependencies,
+                    graphql,
+                    Some(id),
+                    name,
+                    path,
+                    Some((position.line, position.column)),
+                    match options.keeps_raw() {
+                        true => definition.to_string(),
+                        false => String::new(),
+                    },
+                )
+            })
+        })
+        .collect())
+}
+
+/// Add the named operations and fragments of executable documents to a graph
+/// already populated with the schema, wired to the types and fields they
+/// select.
+pub(crate) fn populate_operations<'a>(
+    sources: impl IntoIterator<Item = (&'a PathBuf, &'a str)>,
+    options: &AnalysisOptions,
+    graph: &mut SchemaGraph,
+    dependencies: &mut HashMap<NodeIndex, Vec<Name>>,
+    missing_definitions: &mut HashMap<NodeIndex, Vec<Name>>,
+    node_indices: &mut NodeIndices,
+) -> Result<()> {
+    let mut sources = sources.into_iter().collect::<Vec<_>>();
+    let mut entities = vec![];
+
+    // Sort by path to keep the node insertion order deterministic.
+    sources.sort_by_key(|(path, _)| *path);
+
+    {
+        let resolver = Resolver {
+            field_types: field_types(graph),
+            node_indices,
+            root_types: root_types(graph),
+        };
+
+        for (path, contents) in sources {
+            entities.extend(parse_operations_source(path, contents, &resolver, options)?);
+        }
+    }
+
+    let mut interner = Interner::default();
+    let mut operations_dependencies = HashMap::new();
+
+    for entity in entities {
+        add_node_and_dependencies(
+            entity,
+            graph,
+            &mut operations_dependencies,
+            node_indices,
+            &mut interner,
+        );
+    }
+
+    missing_definitions.extend(wire_edges(
+        graph,
+        &operations_dependencies,
+        node_indices,
+        options,
+    ));
+    dependencies.extend(operations_dependencies);
+
+    Ok(())
+}
+
+/// Return the fields of the objects and interfaces, grouped by type, which
+/// no operation nor fragment selects. A field selected on an interface is
+/// used for all its implementations. The underscored fields of the root
+/// operation types, e.g. _service, are skipped. Relies on the field nodes.
+pub fn unused_fields(graph: &SchemaGraph) -> Vec<UnusedFields> {
+    let (query, mutation, subscription) = root_types(graph);
+    let mut output_types = HashSet::new();
+    let mut interfaces: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        if let GraphQL::TypeDefinition(GraphQLType::Object | GraphQLType::Interface)
+        | GraphQL::TypeExtension(GraphQLType::Object | GraphQLType::Interface) = entity.graphql
+        {
+            output_types.insert(entity.name.as_str());
+            interfaces
+                .entry(&entity.name)
+                .or_default()
+                .extend(entity.interfaces.iter().cloned());
+        }
+    }
+
+    let is_selected = |index: NodeIndex| {
+        graph
+            .neighbors_directed(index, Direction::Outgoing)
+            .any(|dependent| {
+                matches!(
+                    graph[dependent].entity.graphql,
+                    GraphQL::Operation | GraphQL::Fragment
+                )
+            })
+    };
+    let used = graph
+        .node_indices()
+        .filter(|index| graph[*index].entity.graphql == GraphQL::Field && is_selected(*index))
+        .map(|index| graph[index].entity.name.as_str())
+        .collect::<HashSet<&str>>();
+    let mut unused: BTreeMap<&str, Vec<Entity>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+        let (parent, field) = match entity.name.split_once('.') {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        if entity.graphql != GraphQL::Field
+            || !output_types.contains(parent)
+            || used.contains(entity.name.as_str())
+            || ([query.as_str(), mutation.as_str(), subscription.as_str()].contains(&parent)
+                && field.starts_with('_'))
+        {
+            continue;
+        }
+
+        let is_used_through_interface = interfaces
+            .get(parent)
+            .into_iter()
+            .flatten()
+            .any(|interface| used.contains(format!("{}.{}", interface, field).as_str()));
+
+        if !is_used_through_interface {
+            unused.entry(parent).or_default().push(entity.clone());
+        }
+    }
+
+    unused
+        .into_iter()
+        .map(|(name, fields)| UnusedFields {
+            name: name.to_owned(),
+            fields,
+        })
+        .collect()
+}
+
+/// Collect the executable documents of a path and link their named
+/// operations and fragments to the built graph of the state.
+pub async fn link_operations(path: PathBuf, shared_data: &Data) -> Result<()> {
+    let operations = State::with_options((*shared_data.options).clone()).shared;
+
+    collect_files(path, &operations).await?;
+
+    let files = operations.files.lock().await;
+
+    populate_operations(
+        files
+            .iter()
+            .map(|(path, contents)| (path, contents.as_str())),
+        &shared_data.options,
+        &mut *shared_data.graph.lock().await,
+        &mut *shared_data.dependencies.lock().await,
+        &mut *shared_data.missing_definitions.lock().await,
+        &mut *shared_data.node_indices.lock().await,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{state::Granularity, utils::populate_graph};
+
+    #[test]
+    fn check_unused_fields() {
+        let schema = (
+            PathBuf::from("schema.gql"),
+            String::from(
+                "type Query { node: Node _empty: String } interface Node { id: ID! } type Foo implements Node { id: ID! bar: String } type Baz { id: ID! }",
+            ),
+        );
+        let operations = (
+            PathBuf::from("operations.gql"),
+            String::from("query GetNode { node { id ... on Foo { __typename } } }"),
+        );
+        let options = AnalysisOptions::new().granularity(Granularity::Field);
+        let mut graph = SchemaGraph::new();
+        let mut dependencies = HashMap::new();
+        let mut missing_definitions = HashMap::new();
+        let mut node_indices = HashMap::new();
+
+        populate_graph(
+            [(&schema.0, schema.1.as_str())],
+            &options,
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+        )
+        .unwrap();
+        populate_operations(
+            [(&operations.0, operations.1.as_str())],
+            &options,
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+        )
+        .unwrap();
+
+        let unused = unused_fields(&graph)
+            .into_iter()
+            .map(|unused| {
+                (
+                    unused.name,
+                    unused
+                        .fields
+                        .into_iter()
+                        .map(|field| field.name)
+                        .collect::<Vec<String>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Foo.id is selected through Node, Query._empty is skipped.
+        assert_eq!(
+            unused,
+            [
+                (String::from("Baz"), vec![String::from("Baz.id")]),
+                (String::from("Foo"), vec![String::from("Foo.bar")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_populate_operations() {
+        let schema = (
+            PathBuf::from("schema.gql"),
+            String::from(
+                "schema { query: Root } type Root { search: [Result] } union Result = Post | User type Post { title: String } type User { id: ID }",
+            ),
+        );
+        let operations = [
+            schema.clone(),
+            (
+                PathBuf::from("operations.gql"),
+                String::from(
+                    "query Search { search { ... on Post { title } __typename } } { search { __typename } }",
+                ),
+            ),
+        ];
+        let options = AnalysisOptions::new();
+        let mut graph = SchemaGraph::new();
+        let mut dependencies = HashMap::new();
+        let mut missing_definitions = HashMap::new();
+        let mut node_indices = HashMap::new();
+
+        populate_graph(
+            [(&schema.0, schema.1.as_str())],
+            &options,
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+        )
+        .unwrap();
+
+        let nodes = graph.node_count();
+
+        populate_operations(
+            operations
+                .iter()
+                .map(|(path, contents)| (path, contents.as_str())),
+            &options,
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+        )
+        .unwrap();
+
+        // The schema and the anonymous operation are skipped, the inline
+        // fragment narrows the union down to one of its types.
+        assert_eq!(graph.node_count(), nodes + 1);
+        assert_eq!(
+            graph[node_indices["operation:Search"]].entity.dependencies,
+            ["Post", "Result", "Root", "String"]
+                .map(Name::from)
+                .to_vec()
+        );
+        assert!(missing_definitions.is_empty());
+    }
+
+    #[test]
+    fn check_operation_ids() {
+        let schema = (
+            PathBuf::from("schema.gql"),
+            String::from("type Query { user: User } type User { id: ID }"),
+        );
+        let operations = (
+            PathBuf::from("operations.gql"),
+            String::from("query User { user { ...User } } fragment User on User { id }"),
+        );
+        let options = AnalysisOptions::new();
+        let mut graph = SchemaGraph::new();
+        let mut dependencies = HashMap::new();
+        let mut missing_definitions = HashMap::new();
+        let mut node_indices = HashMap::new();
+
+        populate_graph(
+            [(&schema.0, schema.1.as_str())],
+            &options,
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+        )
+        .unwrap();
+        populate_operations(
+            [(&operations.0, operations.1.as_str())],
+            &options,
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+        )
+        .unwrap();
+
+        // The operation and the fragment named after the type don't collide
+        // with it.
+        assert_eq!(
+            graph[node_indices["User"]].entity.graphql.as_str(),
+            "object"
+        );
+        assert_eq!(graph[node_indices["operation:User"]].entity.name, "User");
+        assert_eq!(
+            graph[node_indices["operation:User"]].entity.dependencies,
+            ["fragment:User", "Query", "User"].map(Name::from).to_vec()
+        );
+        assert_eq!(
+            graph[node_indices["fragment:User"]].entity.graphql,
+            GraphQL::Fragment
+        );
+        assert!(missing_definitions.is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::introspection::introspection_to_sdl;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+/// Synthetic path of a schema fetched from an endpoint, named after its host,
+/// e.g. <remote:api.example.com>.graphql.
+pub fn remote_path(url: &str) -> String {
+    let host = surf::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .unwrap_or_else(|| url.to_owned());
+
+    format!("<remote:{}>.graphql", host)
+}
+
+/// Standard introspection query, as sent by the GraphQL tooling.
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+    directives {
+      name
+      description
+      locations
+      args { ...InputValue }
+      isRepeatable
+    }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType {
+              kind
+              name
+              ofType {
+                kind
+                name
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// Header sent with the introspection query, parsed from "Name: value".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Name of the header.
+    pub name: String,
+    /// Value of the header.
+    pub value: String,
+}
+
+impl std::str::FromStr for Header {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() => Ok(Header {
+                name: name.trim().to_owned(),
+                value: value.trim().to_owned(),
+            }),
+            _ => Err(format!(
+                r#"Invalid header provided "{}", expected "Name: value""#,
+                s
+            )),
+        }
+    }
+}
+
+/// POST the introspection query to a GraphQL endpoint and convert the result
+/// to SDL. The GraphQL errors of the response are reported as an error.
+pub async fn fetch_schema(url: &str, headers: &[Header]) -> Result<String> {
+    let mut request = surf::post(url)
+        .body_json(&json!({
+            "operationName": "IntrospectionQuery",
+            "query": INTROSPECTION_QUERY,
+        }))
+        .map_err(|error| anyhow!(error))?;
+
+    for header in headers {
+        request = request.header(header.name.as_str(), header.value.as_str());
+    }
+
+    let mut response = request
+        .await
+        .map_err(|error| anyhow!(error))
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    let body = response
+        .body_string()
+        .await
+        .map_err(|error| anyhow!(error))
+        .with_context(|| format!("Failed to read the response of {}", url))?;
+
+    // Endpoints may answer the GraphQL errors with a non-success status.
+    let result = serde_json::from_str::<Value>(&body).unwrap_or_default();
+
+    if let Some(errors) = result["errors"]
+        .as_array()
+        .filter(|errors| !errors.is_empty())
+    {
+        bail!(
+            "The introspection query failed on {}: {}",
+            url,
+            errors
+                .iter()
+                .map(|error| error["message"].as_str().unwrap_or("unknown error"))
+                .collect::<Vec<&str>>()
+                .join(", ")
+        );
+    }
+
+    if !response.status().is_success() {
+        bail!("Failed to fetch {}, HTTP status {}", url, response.status());
+    }
+
+    introspection_to_sdl(&body).with_context(|| format!("Failed to read {}", url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::introspection::is_introspection_path;
+    use async_std::path::Path;
+
+    #[test]
+    fn check_remote_path() {
+        let path = remote_path("https://api.example.com/graphql/schema.json");
+
+        // The fetched schema is already converted to SDL.
+        assert_eq!(path, "<remote:api.example.com>.graphql");
+        assert!(!is_introspection_path(Path::new(&path)));
+        assert_eq!(remote_path("not a url"), "<remote:not a url>.graphql");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    state::{AnalysisOptions, Entity, GraphQL, Name, NodeIndices, SchemaGraph},
+    utils::{neighbors, orphans, populate_graph, unused_directives, walk_files},
+};
+
+use anyhow::Result;
+use async_std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use petgraph::{graph::NodeIndex, Direction};
+use std::collections::HashMap;
+
+/// Options of Schema::from_path, e.g. the filters or the allowed extensions,
+/// built with the chained calls of the analysis options.
+pub type SchemaOptions = AnalysisOptions;
+
+/// Synchronous and lock-free analysis of a GraphQL schema.
+#[derive(Debug, Default)]
+pub struct Schema {
+    graph: SchemaGraph,
+    dependencies: HashMap<NodeIndex, Vec<Name>>,
+    missing_definitions: HashMap<NodeIndex, Vec<Name>>,
+    node_indices: NodeIndices,
+}
+
+impl Schema {
+    /// Method to create a new Schema from (path, contents) sources.
+    pub fn from_sources(sources: impl IntoIterator<Item = (PathBuf, String)>) -> Result<Self> {
+        Schema::from_sources_with_options(sources, &AnalysisOptions::default())
+    }
+
+    /// Method to create a new Schema from (path, contents) sources with
+    /// analysis options.
+    pub fn from_sources_with_options(
+        sources: impl IntoIterator<Item = (PathBuf, String)>,
+        options: &AnalysisOptions,
+    ) -> Result<Self> {
+        let sources = sources.into_iter().collect::<Vec<(PathBuf, String)>>();
+        let mut schema = Schema::default();
+
+        populate_graph(
+            sources
+                .iter()
+                .map(|(path, contents)| (path, contents.as_str())),
+            options,
+            &mut schema.graph,
+            &mut schema.dependencies,
+            &mut schema.missing_definitions,
+            &mut schema.node_indices,
+        )?;
+
+        Ok(schema)
+    }
+
+    /// Method to create a new Schema from the files of a path, walking the
+    /// directories.
+    ///
+    /// ```
+    /// use craftql::schema::{Schema, SchemaOptions};
+    ///
+    /// # async_std::task::block_on(async {
+    /// let options = SchemaOptions::new().extensions(["gql"]);
+    /// let schema = Schema::from_path("tests/fixtures", &options).await.unwrap();
+    ///
+    /// assert!(schema.node("Episode").is_some());
+    /// # })
+    /// ```
+    pub async fn from_path(path: impl Into<PathBuf>, options: &SchemaOptions) -> Result<Self> {
+        let path = path.into();
+        let files = Arc::new(Mutex::new(HashMap::new()));
+
+        walk_files(path.clone(), files.clone(), Arc::new(options.clone())).await?;
+
+        let files = std::mem::take(&mut *files.lock().await);
+
+        Schema::from_sources_with_options(files, options)
+    }
+
+    /// Underlying graph.
+    pub fn graph(&self) -> &SchemaGraph {
+        &self.graph
+    }
+
+    /// Dependencies of each node.
+    pub fn dependencies(&self) -> &HashMap<NodeIndex, Vec<Name>> {
+        &self.dependencies
+    }
+
+    /// Missing definitions of each node.
+    pub fn missing_definitions(&self) -> &HashMap<NodeIndex, Vec<Name>> {
+        &self.missing_definitions
+    }
+
+    /// Node indices by id.
+    pub fn node_indices(&self) -> &NodeIndices {
+        &self.node_indices
+    }
+
+    /// Entities of all the nodes, in insertion order.
+    ///
+    /// ```
+    /// use async_std::path::PathBuf;
+    /// use craftql::schema::Schema;
+    ///
+    /// let schema = Schema::from_sources(vec![(
+    ///     PathBuf::from("schema.gql"),
+    ///     String::from("type Foo { bar: Bar } type Bar { id: ID! }"),
+    /// )])
+    /// .unwrap();
+    /// let names = schema
+    ///     .entities()
+    ///     .map(|entity| entity.name.as_str())
+    ///     .collect::<Vec<&str>>();
+    ///
+    /// assert_eq!(names, vec!["Foo", "Bar"]);
+    /// ```
+    pub fn entities(&self) -> impl Iterator<Item = &Entity> {
+        self.graph.node_weights().map(|node| &node.entity)
+    }
+
+    /// Entities of the nodes of a GraphQL type, in insertion order.
+    ///
+    /// ```
+    /// use async_std::path::PathBuf;
+    /// use craftql::{schema::Schema, state::GraphQL};
+    ///
+    /// let schema = Schema::from_sources(vec![(
+    ///     PathBuf::from("schema.gql"),
+    ///     String::from("enum Color { RED } type Foo { color: Color } extend enum Color { BLUE }"),
+    /// )])
+    /// .unwrap();
+    /// let enums = schema
+    ///     .entities_of_kind("enum".parse::<GraphQL>().unwrap())
+    ///     .map(|entity| entity.id.as_str())
+    ///     .collect::<Vec<&str>>();
+    ///
+    /// assert_eq!(enums, vec!["Color"]);
+    /// ```
+    pub fn entities_of_kind(&self, graphql: GraphQL) -> impl Iterator<Item = &Entity> {
+        self.entities()
+            .filter(move |entity| entity.graphql == graphql)
+    }
+
+    /// Dependencies of a node, sorted alphabetically.
+    ///
+    /// ```
+    /// use async_std::path::PathBuf;
+    /// use craftql::schema::Schema;
+    ///
+    /// let schema = Schema::from_sources(vec![(
+    ///     PathBuf::from("schema.gql"),
+    ///     String::from("type Foo { bar: Bar id: ID! } type Bar { id: ID! }"),
+    /// )])
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     schema.dependencies_of("Foo"),
+    ///     Some(vec!["Bar", "ID"])
+    /// );
+    /// assert_eq!(schema.dependencies_of("Baz"), None);
+    /// ```
+    pub fn dependencies_of(&self, name: &str) -> Option<Vec<&str>> {
+        self.node(name).map(|entity| {
+            entity
+                .dependencies
+                .iter()
+                .map(|dependency| &**dependency)
+                .collect()
+        })
+    }
+
+    /// Orphan nodes, the directives excepted.
+    pub fn orphans(&self) -> Vec<Entity> {
+        orphans(&self.graph, &[])
+    }
+
+    /// Declared directives which are never used.
+    pub fn unused_directives(&self) -> Vec<Entity> {
+        unused_directives(&self.graph)
+    }
+
+    /// Neighbors of a node in the provided direction.
+    pub fn neighbors(&self, name: &str, direction: Direction) -> Vec<Entity> {
+        neighbors(name, &self.graph, &self.node_indices, direction)
+    }
+
+    /// Entity of a node.
+    pub fn node(&self, name: &str) -> Option<&Entity> {
+        self.node_indices
+            .get(name)
+            .map(|index| &self.graph[*index].entity)
+    }
+
+    /// Entities of the extensions of a type, in insertion order.
+    ///
+    /// ```
+    /// use async_std::path::PathBuf;
+    /// use craftql::schema::Schema;
+    ///
+    /// let schema = Schema::from_sources(vec![(
+    ///     PathBuf::from("schema.gql"),
+    ///     String::from("type Foo { id: ID! } extend type Foo { bar: ID } extend type Foo { baz: ID }"),
+    /// )])
+    /// .unwrap();
+    /// let ids = schema
+    ///     .extensions_of("Foo")
+    ///     .map(|entity| entity.id.as_str())
+    ///     .collect::<Vec<&str>>();
+    ///
+    /// assert_eq!(ids, vec!["Foo__1", "Foo__2"]);
+    /// ```
+    pub fn extensions_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Entity> {
+        self.entities()
+            .filter(move |entity| entity.extends.as_deref() == Some(name))
+    }
+}
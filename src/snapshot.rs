@@ -0,0 +1,245 @@
+use crate::{
+    cache::hash,
+    state::{GraphQL, Node, SchemaGraph},
+};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Version of the snapshot format, bumped on incompatible changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Node of a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotNode {
+    /// Id of the node.
+    pub id: String,
+    /// GraphQL type of the node.
+    pub graphql: GraphQL,
+    /// Dependencies of the node, sorted.
+    pub dependencies: Vec<String>,
+    /// Hash of the raw representation of the node.
+    pub hash: String,
+}
+
+/// Deterministic snapshot of an analysis, everything being sorted by id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Version of the snapshot format.
+    pub version: u32,
+    /// Nodes of the graph.
+    pub nodes: Vec<SnapshotNode>,
+    /// Edges of the graph as (dependency, dependent) ids.
+    pub edges: Vec<(String, String)>,
+}
+
+impl Snapshot {
+    /// Method to create a snapshot from a graph, built with the raw
+    /// representations kept.
+    pub fn from_graph(graph: &SchemaGraph) -> Self {
+        let mut nodes = graph
+            .node_weights()
+            .map(|Node { entity, .. }| {
+                let mut dependencies = entity
+                    .dependencies
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>();
+                dependencies.sort();
+
+                SnapshotNode {
+                    id: entity.id.clone(),
+                    graphql: entity.graphql.clone(),
+                    dependencies,
+                    hash: hash(entity.raw.as_bytes()),
+                }
+            })
+            .collect::<Vec<SnapshotNode>>();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut edges = graph
+            .edge_indices()
+            .filter_map(|edge| graph.edge_endpoints(edge))
+            .map(|(source, target)| {
+                (
+                    graph[source].entity.id.clone(),
+                    graph[target].entity.id.clone(),
+                )
+            })
+            .collect::<Vec<(String, String)>>();
+        edges.sort();
+        edges.dedup();
+
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Parse a snapshot, rejecting the other versions of the format.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let snapshot = serde_json::from_str::<Snapshot>(contents)?;
+
+        if snapshot.version != SNAPSHOT_VERSION {
+            bail!(
+                "Unsupported snapshot version {}, expected {}",
+                snapshot.version,
+                SNAPSHOT_VERSION
+            );
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Render the snapshot as pretty JSON, ending with a newline.
+    pub fn render(&self) -> Result<String> {
+        Ok(format!("{}\n", serde_json::to_string_pretty(self)?))
+    }
+}
+
+/// Difference between a snapshot and the current analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+    /// A node missing from the snapshot.
+    Added(String, GraphQL),
+    /// A node missing from the current analysis.
+    Removed(String, GraphQL),
+    /// A node whose definition changed.
+    Changed(String, GraphQL),
+    /// A node whose dependencies changed, as (id, added, removed).
+    Dependencies(String, Vec<String>, Vec<String>),
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::Added(id, graphql) => write!(f, "Added {} {}", graphql, id),
+            Drift::Removed(id, graphql) => write!(f, "Removed {} {}", graphql, id),
+            Drift::Changed(id, graphql) => write!(f, "Changed {} {}", graphql, id),
+            Drift::Dependencies(id, added, removed) => {
+                let changes = added
+                    .iter()
+                    .map(|dependency| format!("+{}", dependency))
+                    .chain(removed.iter().map(|dependency| format!("-{}", dependency)))
+                    .collect::<Vec<String>>();
+
+                write!(f, "Dependencies of {} changed: {}", id, changes.join(", "))
+            }
+        }
+    }
+}
+
+/// Compare a snapshot to the current one, sorted by id. The edges being
+/// resolved from the dependencies, their changes are reported through the
+/// dependencies of the nodes.
+pub fn drift(snapshot: &Snapshot, current: &Snapshot) -> Vec<Drift> {
+    let old = snapshot
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect::<BTreeMap<&str, &SnapshotNode>>();
+    let new = current
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect::<BTreeMap<&str, &SnapshotNode>>();
+    let ids = old.keys().chain(new.keys()).collect::<BTreeSet<&&str>>();
+    let mut drifts = vec![];
+
+    for id in ids {
+        match (old.get(*id), new.get(*id)) {
+            (Some(old), None) => drifts.push(Drift::Removed(old.id.clone(), old.graphql.clone())),
+            (None, Some(new)) => drifts.push(Drift::Added(new.id.clone(), new.graphql.clone())),
+            (Some(old), Some(new)) => {
+                if old.hash != new.hash || old.graphql != new.graphql {
+                    drifts.push(Drift::Changed(new.id.clone(), new.graphql.clone()));
+                }
+
+                let added = new
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| !old.dependencies.contains(dependency))
+                    .cloned()
+                    .collect::<Vec<String>>();
+                let removed = old
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| !new.dependencies.contains(dependency))
+                    .cloned()
+                    .collect::<Vec<String>>();
+
+                if !added.is_empty() || !removed.is_empty() {
+                    drifts.push(Drift::Dependencies(new.id.clone(), added, removed));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    drifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{schema::Schema, state::AnalysisOptions};
+    use async_std::path::PathBuf;
+
+    fn snapshot(source: &str) -> Snapshot {
+        let schema = Schema::from_sources_with_options(
+            [(PathBuf::from("schema.graphql"), String::from(source))],
+            &AnalysisOptions::new().keep_raw(true),
+        )
+        .unwrap();
+
+        Snapshot::from_graph(schema.graph())
+    }
+
+    #[test]
+    fn check_snapshot_roundtrip() {
+        let snapshot = snapshot("type Query { user: User } type User { id: ID! }");
+        let rendered = snapshot.render().unwrap();
+
+        assert_eq!(Snapshot::parse(&rendered).unwrap(), snapshot);
+        assert_eq!(
+            snapshot
+                .nodes
+                .iter()
+                .map(|node| node.id.as_str())
+                .collect::<Vec<&str>>(),
+            ["Query", "User"]
+        );
+        assert_eq!(
+            snapshot.edges,
+            [(String::from("User"), String::from("Query"))]
+        );
+        assert!(Snapshot::parse(&rendered.replace("\"version\": 1", "\"version\": 0")).is_err());
+    }
+
+    #[test]
+    fn check_drift() {
+        let old = snapshot("type Query { user: User } type User { id: ID! } enum Role { A }");
+        let new = snapshot("type Query { user: User post: Post } type User { id: ID! name: String } type Post { id: ID! }");
+
+        assert_eq!(
+            drift(&old, &new)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>(),
+            [
+                "Added object Post",
+                "Changed object Query",
+                "Dependencies of Query changed: +Post",
+                "Removed enum Role",
+                "Changed object User",
+                "Dependencies of User changed: +String",
+            ]
+        );
+        assert!(drift(&new, &new).is_empty());
+    }
+}
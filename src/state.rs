@@ -1,9 +1,23 @@
+use crate::{
+    cache::Cache,
+    config::{
+        ALLOWED_EXTENSIONS, BUILT_IN_DIRECTIVES, BUILT_IN_SCALARS_PATH, DEFAULT_JOBS,
+        DEFAULT_WRAP_WIDTH, FEDERATION_DIRECTIVES,
+    },
+    introspection::INTROSPECTION_EXTENSION,
+};
+
 use async_std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 use petgraph::{graph::NodeIndex, Graph};
-use std::{collections::HashMap, fmt, str::FromStr};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
 
 /// Global state.
 #[derive(Debug)]
@@ -34,6 +48,12 @@ pub enum GraphQLType {
 pub enum GraphQL<T = GraphQLType> {
     /// Directive type.
     Directive,
+    /// Field of a type, only with the field granularity.
+    Field,
+    /// Fragment of an executable document.
+    Fragment,
+    /// Named operation of an executable document.
+    Operation,
     /// Schema type.
     Schema,
     /// TypeDefinition type.
@@ -49,6 +69,9 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             GraphQL::Directive => write!(f, "Directive"),
+            GraphQL::Field => write!(f, "Field"),
+            GraphQL::Fragment => write!(f, "Fragment"),
+            GraphQL::Operation => write!(f, "Operation"),
             GraphQL::Schema => write!(f, "Schema"),
             GraphQL::TypeDefinition(graphql_type) => write!(f, "{:?}", graphql_type),
             GraphQL::TypeExtension(graphql_type) => write!(f, "{:?} extension", graphql_type),
@@ -64,12 +87,15 @@ impl FromStr for GraphQL {
             "directive" => Ok(GraphQL::Directive),
             "enum" => Ok(GraphQL::TypeDefinition(GraphQLType::Enum)),
             "enum_extension" => Ok(GraphQL::TypeExtension(GraphQLType::Enum)),
+            "field" => Ok(GraphQL::Field),
+            "fragment" => Ok(GraphQL::Fragment),
             "input_object" => Ok(GraphQL::TypeDefinition(GraphQLType::InputObject)),
             "input_object_extension" => Ok(GraphQL::TypeExtension(GraphQLType::InputObject)),
             "interface" => Ok(GraphQL::TypeDefinition(GraphQLType::Interface)),
             "interface_extension" => Ok(GraphQL::TypeExtension(GraphQLType::Interface)),
             "object" => Ok(GraphQL::TypeDefinition(GraphQLType::Object)),
             "object_extension" => Ok(GraphQL::TypeExtension(GraphQLType::Object)),
+            "operation" => Ok(GraphQL::Operation),
             "scalar" => Ok(GraphQL::TypeDefinition(GraphQLType::Scalar)),
             "scalar_extension" => Ok(GraphQL::TypeExtension(GraphQLType::Scalar)),
             "schema" => Ok(GraphQL::Schema),
@@ -80,21 +106,395 @@ impl FromStr for GraphQL {
     }
 }
 
+impl GraphQL {
+    /// Check if it is a type extension.
+    pub fn is_extension(&self) -> bool {
+        matches!(self, GraphQL::TypeExtension(_))
+    }
+
+    /// Return the core type of a type definition or extension.
+    pub fn base_type(&self) -> Option<GraphQLType> {
+        match self {
+            GraphQL::TypeDefinition(graphql_type) | GraphQL::TypeExtension(graphql_type) => {
+                Some(*graphql_type)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the string accepted by FromStr.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphQL::Directive => "directive",
+            GraphQL::Field => "field",
+            GraphQL::Fragment => "fragment",
+            GraphQL::Operation => "operation",
+            GraphQL::Schema => "schema",
+            GraphQL::TypeDefinition(GraphQLType::Enum) => "enum",
+            GraphQL::TypeDefinition(GraphQLType::InputObject) => "input_object",
+            GraphQL::TypeDefinition(GraphQLType::Interface) => "interface",
+            GraphQL::TypeDefinition(GraphQLType::Object) => "object",
+            GraphQL::TypeDefinition(GraphQLType::Scalar) => "scalar",
+            GraphQL::TypeDefinition(GraphQLType::Union) => "union",
+            GraphQL::TypeExtension(GraphQLType::Enum) => "enum_extension",
+            GraphQL::TypeExtension(GraphQLType::InputObject) => "input_object_extension",
+            GraphQL::TypeExtension(GraphQLType::Interface) => "interface_extension",
+            GraphQL::TypeExtension(GraphQLType::Object) => "object_extension",
+            GraphQL::TypeExtension(GraphQLType::Scalar) => "scalar_extension",
+            GraphQL::TypeExtension(GraphQLType::Union) => "union_extension",
+        }
+    }
+}
+
+impl fmt::Display for GraphQL {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// Serialize to the same strings accepted by FromStr.
+impl Serialize for GraphQL {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for GraphQL {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Output formats of the graph.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Cytoscape.js elements JSON format.
+    Cytoscape,
+    /// D2 diagram language.
+    D2,
+    /// Graphviz .dot format.
+    Dot,
+    /// Gephi GEXF 1.2 format.
+    Gexf,
+    /// Graphviz JSON format, as dot -Tjson without the layout.
+    GvJson,
+    /// Single-file interactive HTML viewer.
+    Html,
+    /// JSON format.
+    Json,
+    /// JSON Lines format, one node or edge per line.
+    Jsonl,
+    /// YAML format.
+    Yaml,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cytoscape" => Ok(Format::Cytoscape),
+            "d2" => Ok(Format::D2),
+            "dot" => Ok(Format::Dot),
+            "gexf" => Ok(Format::Gexf),
+            "gvjson" => Ok(Format::GvJson),
+            "html" => Ok(Format::Html),
+            "json" => Ok(Format::Json),
+            "jsonl" | "json-lines" => Ok(Format::Jsonl),
+            "yaml" => Ok(Format::Yaml),
+            unknown => Err(format!(r#"Unknown format provided "{}""#, unknown)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Format {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Granularity of the nodes of the graph.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Granularity {
+    /// Fields are nodes too, between their type and the types they reference.
+    Field,
+    /// Only types are nodes.
+    #[default]
+    Type,
+}
+
+/// Style of the paths stored for the walked files, and thus printed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PathStyle {
+    /// Paths as walked from the provided ones.
+    #[default]
+    AsProvided,
+    /// Canonicalized absolute paths.
+    Absolute,
+    /// Paths relative to a directory.
+    RelativeTo(PathBuf),
+}
+
+impl FromStr for Granularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "field" => Ok(Granularity::Field),
+            "type" => Ok(Granularity::Type),
+            unknown => Err(format!(r#"Unknown granularity provided "{}""#, unknown)),
+        }
+    }
+}
+
+/// Grouping of the missing definitions report.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GroupBy {
+    /// Group by missing definition name.
+    Missing,
+    /// Group by node referencing missing definitions.
+    Node,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "missing" => Ok(GroupBy::Missing),
+            "node" => Ok(GroupBy::Node),
+            unknown => Err(format!(r#"Unknown grouping provided "{}""#, unknown)),
+        }
+    }
+}
+
+/// Ordering of the listed entities.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// By degree, i.e. number of edges, the most connected first.
+    Degree,
+    /// By name.
+    #[default]
+    Name,
+    /// By path, then by position.
+    Path,
+    /// By GraphQL type.
+    Type,
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "degree" => Ok(SortBy::Degree),
+            "name" => Ok(SortBy::Name),
+            "path" => Ok(SortBy::Path),
+            "type" => Ok(SortBy::Type),
+            unknown => Err(format!(r#"Unknown sorting provided "{}""#, unknown)),
+        }
+    }
+}
+
+/// Image formats of --render, produced by Graphviz.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// PNG image.
+    Png,
+    /// SVG image.
+    Svg,
+}
+
+impl ImageFormat {
+    /// Return the string accepted by FromStr, also the -T flag of dot.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(ImageFormat::Png),
+            "svg" => Ok(ImageFormat::Svg),
+            unknown => Err(format!(r#"Unknown image format provided "{}""#, unknown)),
+        }
+    }
+}
+
+/// Graphviz layout engines of --layout.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// Hierarchical layout.
+    #[default]
+    Dot,
+    /// Force-directed layout, for large graphs.
+    Fdp,
+    /// Spring model layout, for large graphs.
+    Neato,
+}
+
+impl Layout {
+    /// Return the string accepted by FromStr, also the -K flag of dot.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Layout::Dot => "dot",
+            Layout::Fdp => "fdp",
+            Layout::Neato => "neato",
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Layout::Dot),
+            "fdp" => Ok(Layout::Fdp),
+            "neato" => Ok(Layout::Neato),
+            unknown => Err(format!(r#"Unknown layout provided "{}""#, unknown)),
+        }
+    }
+}
+
+/// Severity of the applied directives without definition.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnknownDirectives {
+    /// Fail the run.
+    Error,
+    /// Print a warning.
+    Warn,
+    /// Skip them silently.
+    Ignore,
+}
+
+impl FromStr for UnknownDirectives {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(UnknownDirectives::Error),
+            "warn" => Ok(UnknownDirectives::Warn),
+            "ignore" => Ok(UnknownDirectives::Ignore),
+            unknown => Err(format!(r#"Unknown severity provided "{}""#, unknown)),
+        }
+    }
+}
+
+/// Dependency resolved from the argument of a directive, e.g. Role via
+/// @auth(requires:).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Dependency {
+    /// Name of the dependency.
+    pub name: String,
+    /// Coordinate of the directive argument.
+    pub via: String,
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} via {}", self.name, self.via)
+    }
+}
+
+/// Field, argument or input field of a definition with its type as written,
+/// e.g. [Date!]! for Post.dates.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Reference {
+    /// Coordinate of the field or input field, or of the directive of a
+    /// directive definition argument, e.g. Query.orders or @cache.
+    pub field: String,
+    /// Name of the argument, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument: Option<String>,
+    /// Type of the reference as written.
+    pub value_type: String,
+}
+
+impl Reference {
+    /// Return the coordinate of the reference, e.g. Query.orders(filter:).
+    pub fn coordinate(&self) -> String {
+        match &self.argument {
+            Some(argument) => format!("{}({}:)", self.field, argument),
+            None => self.field.clone(),
+        }
+    }
+
+    /// Return the named type of the reference, lists and non-null types
+    /// unwrapped.
+    pub fn named_type(&self) -> &str {
+        self.value_type
+            .trim_matches(|character| matches!(character, '[' | ']' | '!'))
+    }
+}
+
 /// Represents a GraphQL entity.
-#[derive(Clone)]
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
 pub struct Entity {
-    /// Dependencies of an entity.
-    pub dependencies: Vec<String>,
+    /// Named types of the arguments of a directive definition, keyed by their
+    /// coordinate, e.g. @auth(requires:).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub argument_types: BTreeMap<String, String>,
+    /// Dependencies of an entity, sorted case-insensitively without duplicates.
+    pub dependencies: Vec<Name>,
+    /// Dependencies resolved from the arguments of the used directives, the
+    /// ones also plainly referenced, e.g. by a field, being left out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directive_dependencies: Vec<Dependency>,
+    /// Description of the entity, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Object, interface or input object, or an extension of one, without
+    /// fields.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub empty: bool,
+    /// Name of the type extended by an extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     /// GraphQL type of the entity.
     pub graphql: GraphQL,
+    /// Interfaces of the implements clause of an object or interface, or of
+    /// an extension of one, kept apart from the other dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interfaces: Vec<String>,
     /// Id of the entity.
     pub id: String,
+    /// Member types of a union, or of an extension of one, kept apart from
+    /// the other dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
     /// Name of the entity.
     pub name: String,
     /// Path of the entity.
+    #[serde(with = "path")]
     pub path: PathBuf,
-    /// Raw representation of the entity.
+    /// Position of the entity in its source as (line, column).
+    pub position: Option<(usize, usize)>,
+    /// Raw representation of the entity, empty when not kept.
     pub raw: String,
+    /// Fields, arguments and input fields of the entity with their types as
+    /// written.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
 }
 
 impl Entity {
@@ -104,61 +504,205 @@ impl Entity {
         graphql: GraphQL,
         id: Option<String>,
         name: String,
-        path: PathBuf,
+        path: &Path,
+        position: Option<(usize, usize)>,
         raw: String,
     ) -> Self {
         Entity {
-            dependencies,
+            argument_types: BTreeMap::new(),
+            dependencies: dependencies.into_iter().map(Name::from).collect(),
+            directive_dependencies: vec![],
+            description: None,
+            empty: false,
+            extends: graphql.is_extension().then(|| name.clone()),
             graphql,
+            interfaces: vec![],
             // If no custom id is provided, use the name.
             id: match id {
                 Some(id) => id,
                 None => name.clone(),
             },
+            members: vec![],
             name,
-            path,
+            path: path.to_path_buf(),
+            position,
             raw,
+            references: vec![],
         }
     }
-}
 
-// Used in graph generation.
-impl fmt::Debug for Entity {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Set the description of the entity.
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Flag an object, interface or input object without fields.
+    pub fn with_empty(mut self, empty: bool) -> Self {
+        self.empty = empty;
+        self
+    }
+
+    /// Set the interfaces of the implements clause.
+    pub fn with_interfaces(mut self, interfaces: Vec<String>) -> Self {
+        self.interfaces = interfaces;
+        self
+    }
+
+    /// Set the member types of a union.
+    pub fn with_members(mut self, members: Vec<String>) -> Self {
+        self.members = members;
+        self
+    }
+
+    /// Set the named types of the arguments of a directive definition.
+    pub fn with_argument_types(mut self, argument_types: BTreeMap<String, String>) -> Self {
+        self.argument_types = argument_types;
+        self
+    }
+
+    /// Set the fields, arguments and input fields with their types.
+    pub fn with_references(mut self, references: Vec<Reference>) -> Self {
+        self.references = references;
+        self
+    }
+
+    /// Check if the entity is a federated one, i.e. carries a @key.
+    pub fn is_federated(&self) -> bool {
+        self.dependencies
+            .iter()
+            .any(|dependency| &**dependency == "@key")
+    }
+
+    /// Check if the entity is a synthesized built-in scalar.
+    pub fn is_built_in(&self) -> bool {
+        self.path == Path::new(BUILT_IN_SCALARS_PATH)
+    }
+
+    /// Return the location of the entity as path:line:column, or only its
+    /// path without position.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+
+    /// Return the label of the entity, i.e. its name and GraphQL type followed
+    /// by its dependencies wrapped at a width, 0 disabling the wrapping.
+    pub fn label(&self, width: usize) -> String {
         if self.dependencies.is_empty() {
-            write!(f, "{} ({:?})", self.name, self.graphql)
+            format!("{} ({:?})", self.name, self.graphql)
         } else {
-            write!(
-                f,
+            format!(
                 "{} ({:?})\n\n[{}]",
                 self.name,
                 self.graphql,
-                self.dependencies.join(", ")
+                // Leave room for the closing bracket.
+                wrap_list(&self.dependencies, width.saturating_sub(1), 1)
             )
         }
     }
 }
 
-// Used with flags like --node.
+/// Format a path and a position as path:line:column, clickable in most
+/// editors.
+fn location(path: &Path, position: Option<(usize, usize)>) -> String {
+    match position {
+        Some((line, column)) => format!("{}:{}:{}", path.to_string_lossy(), line, column),
+        None => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Join items with commas, breaking the lines wider than the width after a
+/// comma. The first line starts at the indent column, the next ones are
+/// indented with as many spaces. A width of 0 disables the wrapping.
+pub fn wrap_list<T: AsRef<str>>(items: &[T], width: usize, indent: usize) -> String {
+    let mut wrapped = String::new();
+    let mut column = indent;
+
+    for (position, item) in items.iter().enumerate() {
+        let item = item.as_ref();
+        let length = item.chars().count();
+        // Leave room for the comma following the item, if any.
+        let room = length + usize::from(position < items.len() - 1);
+
+        if position > 0 {
+            wrapped.push(',');
+            column += 1;
+
+            if width > 0 && column + 1 + room > width {
+                wrapped.push('\n');
+                wrapped.push_str(&" ".repeat(indent));
+                column = indent;
+            } else {
+                wrapped.push(' ');
+                column += 1;
+            }
+        }
+
+        wrapped.push_str(item);
+        column += length;
+    }
+
+    wrapped
+}
+
+impl AsRef<Entity> for Entity {
+    fn as_ref(&self) -> &Entity {
+        self
+    }
+}
+
+// Used in graph generation.
+impl fmt::Debug for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label(DEFAULT_WRAP_WIDTH))
+    }
+}
+
+// Used with flags like --node, the path:line:column location is clickable
+// in most editors.
 impl fmt::Display for Entity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\n# {}\n{}", self.path.to_string_lossy(), self.raw)
+        write!(f, "\n# {}\n{}", self.location(), self.raw)
+    }
+}
+
+// async-std's PathBuf doesn't implement serde's traits, go through std's one.
+mod path {
+    use async_std::path::PathBuf;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(path: &PathBuf, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        AsRef::<std::path::Path>::as_ref(path).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        std::path::PathBuf::deserialize(deserializer).map(PathBuf::from)
     }
 }
 
 /// A Node containing an Entity and a unique id.
+#[derive(Deserialize, PartialEq, Serialize)]
 pub struct Node {
     /// Node's entity.
     pub entity: Entity,
     /// Using the entity name as id is safe as it is unique.
     /// http://spec.graphql.org/draft/#sec-Schema
-    pub id: String,
+    pub id: Name,
 }
 
 impl Node {
     /// Method to create a new Node.
-    pub fn new(entity: Entity, id: String) -> Self {
-        Node { entity, id }
+    pub fn new(entity: Entity, id: impl Into<Name>) -> Self {
+        Node {
+            entity,
+            id: id.into(),
+        }
     }
 }
 
@@ -168,28 +712,888 @@ impl fmt::Debug for Node {
     }
 }
 
+/// Edge of the graph, going from a dependency to its dependent.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Edge {
+    /// Name of the dependency which created the edge, as referenced by the
+    /// dependent, e.g. the base type of an extension.
+    pub dependency: Name,
+    /// Reason of the edge when it isn't a plain reference, e.g. the argument
+    /// of the directive resolving to the dependency.
+    pub reason: Option<String>,
+}
+
+impl Edge {
+    /// Method to create a new Edge.
+    pub fn new(dependency: impl Into<Name>) -> Self {
+        Edge {
+            dependency: dependency.into(),
+            reason: None,
+        }
+    }
+
+    /// Method to set the reason of the edge.
+    pub fn with_reason(mut self, reason: String) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+// The label of the edge in the dot output.
+impl fmt::Debug for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "{} via {}", self.dependency, reason),
+            None => write!(f, "{}", self.dependency),
+        }
+    }
+}
+
+/// Serializable node of a graph snapshot.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SnapshotNode {
+    /// Dependencies of the node.
+    pub dependencies: Vec<String>,
+    /// Description of the node, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// GraphQL type of the node.
+    pub graphql: GraphQL,
+    /// Id of the node.
+    pub id: String,
+    /// Name of the node.
+    pub name: String,
+    /// Path of the node.
+    pub path: String,
+}
+
+/// Serializable edge of a graph snapshot, referencing nodes by id.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SnapshotEdge {
+    /// Id of the source node.
+    pub source: String,
+    /// Id of the target node.
+    pub target: String,
+}
+
+/// Serializable representation of the graph shared by the JSON and YAML outputs.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct GraphSnapshot {
+    /// Nodes of the graph.
+    pub nodes: Vec<SnapshotNode>,
+    /// Edges of the graph.
+    pub edges: Vec<SnapshotEdge>,
+}
+
+/// Edge of the file graph.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct FileEdge {
+    /// Path of the file of the dependencies.
+    pub source: String,
+    /// Path of the file of the dependents.
+    pub target: String,
+    /// Number of type-level edges between the two files.
+    pub weight: usize,
+}
+
+/// Serializable representation of the file graph.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct FileGraphSnapshot {
+    /// Paths of the files.
+    pub nodes: Vec<String>,
+    /// Edges between the files.
+    pub edges: Vec<FileEdge>,
+}
+
+/// Data of a Cytoscape.js node.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CytoscapeNodeData {
+    /// Id of the node.
+    pub id: String,
+    /// Name of the node.
+    pub name: String,
+    /// GraphQL type of the node.
+    pub graphql_type: GraphQL,
+    /// Path of the node.
+    pub path: String,
+}
+
+/// Data of a Cytoscape.js edge.
+#[derive(Debug, Serialize)]
+pub struct CytoscapeEdgeData {
+    /// Unique id of the edge.
+    pub id: String,
+    /// Id of the source node.
+    pub source: String,
+    /// Id of the target node.
+    pub target: String,
+}
+
+/// Cytoscape.js element, wrapping its data.
+#[derive(Debug, Serialize)]
+pub struct CytoscapeElement<T> {
+    /// Data of the element.
+    pub data: T,
+}
+
+/// Cytoscape.js elements.
+#[derive(Debug, Serialize)]
+pub struct CytoscapeElements {
+    /// Node elements.
+    pub nodes: Vec<CytoscapeElement<CytoscapeNodeData>>,
+    /// Edge elements.
+    pub edges: Vec<CytoscapeElement<CytoscapeEdgeData>>,
+}
+
+/// Cytoscape.js graph, see https://js.cytoscape.org/#notation/elements-json.
+#[derive(Debug, Serialize)]
+pub struct CytoscapeGraph {
+    /// Elements of the graph.
+    pub elements: CytoscapeElements,
+}
+
+/// Graphviz JSON node, see https://graphviz.org/docs/outputs/json/.
+#[derive(Debug, Serialize)]
+pub struct GraphvizObject {
+    /// Index of the node in the objects.
+    #[serde(rename = "_gvid")]
+    pub gvid: usize,
+    /// Name of the node, i.e. its id.
+    pub name: String,
+    /// Label of the node.
+    pub label: String,
+    /// GraphQL type of the node.
+    pub graphql: GraphQL,
+}
+
+/// Graphviz JSON edge, from tail to head.
+#[derive(Debug, Serialize)]
+pub struct GraphvizEdge {
+    /// Index of the edge in the edges.
+    #[serde(rename = "_gvid")]
+    pub gvid: usize,
+    /// Index of the source node.
+    pub tail: usize,
+    /// Index of the target node.
+    pub head: usize,
+}
+
+/// Graphviz JSON graph, without the layout attributes.
+#[derive(Debug, Serialize)]
+pub struct GraphvizGraph {
+    /// Name of the graph.
+    pub name: String,
+    /// Graphs are directed.
+    pub directed: bool,
+    /// Graphs aren't strict.
+    pub strict: bool,
+    /// Number of subgraphs, always zero.
+    #[serde(rename = "_subgraph_cnt")]
+    pub subgraph_cnt: usize,
+    /// Nodes of the graph.
+    pub objects: Vec<GraphvizObject>,
+    /// Edges of the graph.
+    pub edges: Vec<GraphvizEdge>,
+}
+
+/// Graph of the nodes, edges going from the dependencies to their dependents.
+pub type SchemaGraph = Graph<Node, Edge>;
+
+/// Graph of the files, edges going from the file of the dependencies to the
+/// one of the dependents, weighted by the number of type-level edges.
+pub type FileGraph = Graph<String, usize>;
+
+/// Node indices by id.
+pub type NodeIndices = HashMap<Name, NodeIndex>;
+
+/// Shared name of a node or a dependency, compared and ordered as a string.
+pub type Name = Arc<str>;
+
+/// Interner handing out a single shared allocation per distinct name.
+#[derive(Debug, Default)]
+pub struct Interner {
+    names: HashSet<Name>,
+}
+
+impl Interner {
+    /// Return the shared name matching a string, interning it if needed.
+    pub fn intern(&mut self, name: &str) -> Name {
+        if let Some(interned) = self.names.get(name) {
+            return interned.clone();
+        }
+
+        let interned = Name::from(name);
+
+        self.names.insert(interned.clone());
+
+        interned
+    }
+}
+
+/// Missing definitions of a node.
+#[derive(Debug, Clone)]
+pub struct MissingReport {
+    /// Entity referencing the missing definitions.
+    pub entity: Entity,
+    /// Names of the missing definitions.
+    pub missing: Vec<Name>,
+}
+
+impl AsRef<Entity> for MissingReport {
+    fn as_ref(&self) -> &Entity {
+        &self.entity
+    }
+}
+
+/// Serializable missing definitions of a node, for the JSON output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingDefinitions {
+    /// Id of the node referencing the missing definitions.
+    pub node: String,
+    /// Path of the node.
+    pub path: String,
+    /// Names of the missing definitions, sorted.
+    pub missing: Vec<String>,
+}
+
+/// Member of an extension already defined by its base or by a previous
+/// extension, skipped when merging the extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Path of the extension.
+    pub path: PathBuf,
+    /// Position of the extension in its source as (line, column).
+    pub position: Option<(usize, usize)>,
+    /// Coordinate of the duplicate member, e.g. User.name.
+    pub coordinate: String,
+}
+
+impl MergeConflict {
+    /// Return the location of the extension as path:line:column.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+}
+
+/// Field of a node referencing a defined type unreachable from the root
+/// operation types.
+#[derive(Debug, Clone)]
+pub struct DeadReference {
+    /// Entity holding the field.
+    pub entity: Entity,
+    /// Coordinate of the field, e.g. Foo.bar.
+    pub field: String,
+    /// Name of the unreachable type.
+    pub target: String,
+}
+
+/// graphql-import style comment, e.g. # import Foo, Bar from "./foo.graphql".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// Imported names, either * or a type optionally followed by a field.
+    pub names: Vec<String>,
+    /// Path of the imported file, relative to the importing one.
+    pub from: String,
+    /// Line of the comment.
+    pub line: usize,
+}
+
+/// Import referencing a file which isn't analyzed or a name it doesn't define.
+#[derive(Debug, Clone)]
+pub struct ImportViolation {
+    /// Path of the importing file.
+    pub path: PathBuf,
+    /// Line of the import comment.
+    pub line: usize,
+    /// Description of the violation.
+    pub message: String,
+}
+
+/// Fields of a type selected by no operation nor fragment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnusedFields {
+    /// Name of the type.
+    #[serde(rename = "type")]
+    pub name: String,
+    /// Field nodes of the type.
+    pub fields: Vec<Entity>,
+}
+
+/// Summary of a walk, i.e. the number of files read and the files skipped
+/// because of their extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalkSummary {
+    /// Number of files read.
+    pub read: usize,
+    /// Files skipped because of their extension, sorted.
+    pub skipped: Vec<PathBuf>,
+}
+
+impl WalkSummary {
+    /// Add the files of another walk.
+    pub fn extend(&mut self, other: WalkSummary) {
+        self.read += other.read;
+        self.skipped.extend(other.skipped);
+        self.skipped.sort();
+    }
+}
+
+/// Definitions contributed by a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSummary {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Ids of the definitions of the file, fields excluded.
+    pub definitions: Vec<String>,
+    /// Number of dependencies defined in another file.
+    pub cross_file_dependencies: usize,
+}
+
+/// Definitions of an owner, i.e. a directory, and its dependencies on the
+/// other owners.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Ownership {
+    /// Directory of the owner, relative to the analyzed path.
+    pub owner: String,
+    /// Names of the definitions of the owner, fields and extensions excluded.
+    pub types: BTreeSet<String>,
+    /// Names of the dependencies defined by the other owners, by owner.
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// Names of the definitions the other owners depend on, by owner.
+    pub dependents: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Implements clause of an object or interface, or of an extension of one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Implementation {
+    /// Name of the implementing type.
+    pub implementor: String,
+    /// Name of the implemented interface.
+    pub interface: String,
+    /// Path of the definition or extension declaring the clause.
+    #[serde(with = "path")]
+    pub path: PathBuf,
+    /// Position of the definition or extension declaring the clause as
+    /// (line, column).
+    pub position: Option<(usize, usize)>,
+    /// GraphQL type of the definition or extension declaring the clause.
+    pub graphql: GraphQL,
+}
+
+impl Implementation {
+    /// Return the location of the declaration as path:line:column.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+}
+
+/// Possible type of an abstract type, i.e. an object implementing an
+/// interface or a member of a union, like the possibleTypes of introspection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PossibleType {
+    /// Name of the interface or union.
+    pub abstract_type: String,
+    /// Name of the object.
+    pub object: String,
+    /// Path of the definition or extension declaring the relationship, i.e.
+    /// the object for an interface and the union for a union.
+    #[serde(with = "path")]
+    pub path: PathBuf,
+    /// Position of the definition or extension declaring the relationship
+    /// as (line, column).
+    pub position: Option<(usize, usize)>,
+    /// GraphQL type of the definition or extension declaring the
+    /// relationship.
+    pub graphql: GraphQL,
+}
+
+impl PossibleType {
+    /// Return the location of the declaration as path:line:column.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+}
+
+/// Field, argument or input field typed with a custom scalar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScalarReference {
+    /// Coordinate of the reference, e.g. Foo.bar, Foo.bar(baz:) or
+    /// FooInput.bar.
+    pub coordinate: String,
+    /// Name of the type or directive holding the reference.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Type of the reference as written, e.g. [DateTime!]!.
+    pub value_type: String,
+    /// Path of the definition or extension holding the reference.
+    #[serde(with = "path")]
+    pub path: PathBuf,
+    /// Position of the definition or extension holding the reference as
+    /// (line, column).
+    pub position: Option<(usize, usize)>,
+}
+
+impl ScalarReference {
+    /// Return the location of the reference as path:line:column.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+}
+
+/// References of a custom scalar across the schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScalarUsage {
+    /// Name of the scalar.
+    pub scalar: String,
+    /// Path of the scalar definition.
+    #[serde(with = "path")]
+    pub path: PathBuf,
+    /// Position of the scalar definition as (line, column).
+    pub position: Option<(usize, usize)>,
+    /// Number of references.
+    pub count: usize,
+    /// Number of distinct types or directives holding a reference.
+    pub types: usize,
+    /// References sorted by coordinate.
+    pub references: Vec<ScalarReference>,
+}
+
+impl ScalarUsage {
+    /// Return the location of the scalar definition as path:line:column.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+}
+
+/// Argument of a field accepting an input object, either directly or nested
+/// inside other input objects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InputUsage {
+    /// Coordinate of the field, e.g. Query.orders.
+    pub field: String,
+    /// Name of the argument.
+    pub argument: String,
+    /// Type of the argument as written, e.g. [OrderFilterInput!].
+    pub value_type: String,
+    /// Path of the definition or extension holding the field.
+    #[serde(with = "path")]
+    pub path: PathBuf,
+    /// Position of the definition or extension holding the field as
+    /// (line, column).
+    pub position: Option<(usize, usize)>,
+    /// Whether the input object is nested inside the argument type.
+    pub indirect: bool,
+    /// Input fields leading from the argument type to the input object,
+    /// e.g. SearchInput.order, empty for a direct usage.
+    pub via: Vec<String>,
+}
+
+impl InputUsage {
+    /// Return the location of the field as path:line:column.
+    pub fn location(&self) -> String {
+        location(&self.path, self.position)
+    }
+}
+
+/// Type whose definition and extensions span several files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScatteredType {
+    /// Name of the type.
+    pub name: String,
+    /// Distinct paths of the definition and the extensions, sorted.
+    pub paths: Vec<String>,
+}
+
+/// Documentation coverage of the type definitions, and of the fields when
+/// they are nodes.
+#[derive(Debug, Clone)]
+pub struct DocsCoverage {
+    /// Number of described entities.
+    pub documented: usize,
+    /// Entities lacking a description, in the nodes order.
+    pub undocumented: Vec<Entity>,
+}
+
+impl DocsCoverage {
+    /// Return the number of entities which can be described.
+    pub fn total(&self) -> usize {
+        self.documented + self.undocumented.len()
+    }
+
+    /// Return the percentage of described entities, 100 without any.
+    pub fn percentage(&self) -> f64 {
+        match self.total() {
+            0 => 100.0,
+            total => self.documented as f64 * 100.0 / total as f64,
+        }
+    }
+}
+
+/// Subgraph to compose, provided as name=path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subgraph {
+    /// Name of the subgraph.
+    pub name: String,
+    /// Path to get the files of the subgraph from.
+    pub path: PathBuf,
+}
+
+impl FromStr for Subgraph {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((name, path)) if !name.is_empty() && !path.is_empty() => Ok(Subgraph {
+                name: name.to_owned(),
+                path: PathBuf::from(path),
+            }),
+            _ => Err(format!(
+                r#"Invalid subgraph provided "{}", expected name=path"#,
+                s
+            )),
+        }
+    }
+}
+
+/// Graph built from the files of a subgraph.
+#[derive(Debug, Default)]
+pub struct SubgraphSchema {
+    /// Name of the subgraph.
+    pub name: String,
+    /// Graph of the subgraph.
+    pub graph: SchemaGraph,
+    /// Missing definitions of the subgraph, by node.
+    pub missing_definitions: HashMap<NodeIndex, Vec<Name>>,
+}
+
+/// Issue preventing subgraphs from being composed.
+#[derive(Debug, Clone)]
+pub enum CompositionIssue {
+    /// Object type defined in several subgraphs, by (subgraph, entity), some
+    /// of them without @key nor @shareable.
+    Conflict(String, Vec<(String, Entity)>),
+    /// Definition referenced by an entity of a subgraph, as (subgraph, entity,
+    /// name), and defined in none.
+    Unsatisfied(String, Box<Entity>, Name),
+}
+
+/// Variant of the extraction of the sources, each one cached separately.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Extraction {
+    /// Extract the fields as entities.
+    pub fields: bool,
+    /// Add the dependencies of the federation field sets.
+    pub federation: bool,
+    /// Skip the executable documents instead of failing to parse them.
+    pub operations: bool,
+}
+
+/// Normalization of the ids before matching the dependencies to the nodes.
+#[derive(Clone)]
+pub struct Normalizer(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl Normalizer {
+    /// Wrap a normalization function.
+    pub fn new(normalize: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Normalizer(Arc::new(normalize))
+    }
+
+    /// Normalize an id.
+    pub fn normalize(&self, id: &str) -> String {
+        (self.0)(id)
+    }
+}
+
+impl fmt::Debug for Normalizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Normalizer")
+    }
+}
+
+// Normalizers are the same when they share their function.
+impl PartialEq for Normalizer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Options of an analysis, built with chained calls.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalysisOptions {
+    cache: Option<Arc<Cache>>,
+    exclude_paths: Vec<String>,
+    exclude_types: Vec<GraphQL>,
+    extensions: Option<Vec<String>>,
+    federation: bool,
+    filter: Vec<GraphQL>,
+    granularity: Granularity,
+    introspection: bool,
+    ignore_missing: Vec<String>,
+    jobs: Option<usize>,
+    known_directives: Vec<String>,
+    normalizer: Option<Normalizer>,
+    operations: bool,
+    path_style: PathStyle,
+    skip_raw: bool,
+    treat_builtins_as_nodes: bool,
+}
+
+impl AnalysisOptions {
+    /// Method to create new default options.
+    pub fn new() -> Self {
+        AnalysisOptions::default()
+    }
+
+    /// Cache the entities extracted from each file in a directory.
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache = Some(Arc::new(Cache::new(cache_dir)));
+        self
+    }
+
+    /// Skip the paths matching the trailing components of the walked files and
+    /// directories.
+    pub fn exclude_paths(
+        mut self,
+        exclude_paths: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.exclude_paths = exclude_paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Drop nodes by GraphQL type(s), takes precedence over the filter.
+    pub fn exclude_types(mut self, exclude_types: impl IntoIterator<Item = GraphQL>) -> Self {
+        self.exclude_types = exclude_types.into_iter().collect();
+        self
+    }
+
+    /// Allowed file extensions, defaults to ALLOWED_EXTENSIONS.
+    pub fn extensions(mut self, extensions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extensions = Some(extensions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Resolve the Apollo Federation field sets as dependencies and treat the
+    /// federation directives as known.
+    pub fn federation(mut self, federation: bool) -> Self {
+        self.federation = federation;
+        self
+    }
+
+    /// Keep only the nodes of the GraphQL type(s), all of them if empty.
+    pub fn filter(mut self, filter: impl IntoIterator<Item = GraphQL>) -> Self {
+        self.filter = filter.into_iter().collect();
+        self
+    }
+
+    /// Granularity of the nodes, types only by default.
+    pub fn granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Walk the .json files as introspection results too, a .json file given
+    /// as a path being always read as such.
+    pub fn introspection(mut self, introspection: bool) -> Self {
+        self.introspection = introspection;
+        self
+    }
+
+    /// Skip the executable documents found along the schema files instead of
+    /// failing to parse them, see link_operations.
+    pub fn operations(mut self, operations: bool) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Maximum number of files read concurrently, defaults to DEFAULT_JOBS.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Names not reported as missing definitions, e.g. the types stitched at
+    /// runtime.
+    pub fn ignore_missing(
+        mut self,
+        ignore_missing: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.ignore_missing = ignore_missing.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Normalize the ids of the nodes and of the dependencies not matching
+    /// any node as is, e.g. to strip a version suffix, defaults to the
+    /// identity. The first node wins when several share a normalized id.
+    pub fn normalizer(
+        mut self,
+        normalize: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.normalizer = Some(Normalizer::new(normalize));
+        self
+    }
+
+    /// Directives not reported as missing definitions on top of the built-in
+    /// ones, with or without the leading @.
+    pub fn known_directives(
+        mut self,
+        known_directives: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.known_directives = known_directives
+            .into_iter()
+            .map(|directive| {
+                let directive = directive.into();
+
+                match directive.starts_with('@') {
+                    true => directive,
+                    false => format!("@{}", directive),
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Style of the paths stored for the walked files, as provided by
+    /// default.
+    pub fn path_style(mut self, path_style: PathStyle) -> Self {
+        self.path_style = path_style;
+        self
+    }
+
+    /// Keep the raw SDL of the entities, enabled by default. Without it,
+    /// Entity.raw is left empty.
+    pub fn keep_raw(mut self, keep_raw: bool) -> Self {
+        self.skip_raw = !keep_raw;
+        self
+    }
+
+    /// Add the referenced built-in scalars as nodes instead of skipping them.
+    pub fn treat_builtins_as_nodes(mut self, treat_builtins_as_nodes: bool) -> Self {
+        self.treat_builtins_as_nodes = treat_builtins_as_nodes;
+        self
+    }
+
+    /// Check if a file extension is allowed.
+    pub fn is_extension_allowed(&self, extension: &str) -> bool {
+        (self.introspection && extension == INTROSPECTION_EXTENSION)
+            || match &self.extensions {
+                Some(extensions) => extensions.iter().any(|allowed| allowed == extension),
+                None => ALLOWED_EXTENSIONS.contains(&extension),
+            }
+    }
+
+    /// Check if a path is excluded.
+    pub fn is_path_excluded(&self, path: &Path) -> bool {
+        self.exclude_paths
+            .iter()
+            .any(|exclude_path| path.ends_with(exclude_path))
+    }
+
+    /// Check if a GraphQL type is kept, excluded types take precedence over
+    /// the filter.
+    pub fn is_graphql_allowed(&self, graphql: &GraphQL) -> bool {
+        !self.exclude_types.contains(graphql)
+            && (self.filter.is_empty() || self.filter.contains(graphql))
+    }
+
+    /// Return the cache, if any.
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_deref()
+    }
+
+    /// Return the style of the paths stored for the walked files.
+    pub fn stored_path_style(&self) -> &PathStyle {
+        &self.path_style
+    }
+
+    /// Return the maximum number of files read concurrently, at least one.
+    pub fn concurrency(&self) -> usize {
+        self.jobs.unwrap_or(DEFAULT_JOBS).max(1)
+    }
+
+    /// Check if a dependency is a built-in, federation or known directive.
+    pub fn is_known_directive(&self, dependency: &str) -> bool {
+        BUILT_IN_DIRECTIVES.contains(&dependency)
+            || (self.federation && FEDERATION_DIRECTIVES.contains(&dependency))
+            || self
+                .known_directives
+                .iter()
+                .any(|directive| directive == dependency)
+    }
+
+    /// Check if a missing definition is ignored.
+    pub fn is_missing_ignored(&self, dependency: &str) -> bool {
+        self.ignore_missing.iter().any(|name| name == dependency)
+    }
+
+    /// Return the normalizer of the ids, if any.
+    pub fn id_normalizer(&self) -> Option<&Normalizer> {
+        self.normalizer.as_ref()
+    }
+
+    /// Check if the raw SDL of the entities is kept.
+    pub fn keeps_raw(&self) -> bool {
+        !self.skip_raw
+    }
+
+    /// Check if the fields are added as nodes.
+    pub fn fields_as_nodes(&self) -> bool {
+        self.granularity == Granularity::Field
+    }
+
+    /// Check if the built-in scalars are added as nodes.
+    pub fn builtins_as_nodes(&self) -> bool {
+        self.treat_builtins_as_nodes
+    }
+
+    /// Check if the Apollo Federation awareness is enabled.
+    pub fn is_federation_enabled(&self) -> bool {
+        self.federation
+    }
+
+    /// Return the extraction variant of the sources.
+    pub fn extraction(&self) -> Extraction {
+        Extraction {
+            fields: self.fields_as_nodes() && self.is_graphql_allowed(&GraphQL::Field),
+            federation: self.federation,
+            operations: self.operations,
+        }
+    }
+}
+
 /// Data holding the thread-safe mutexes.
 #[derive(Debug, Clone)]
 pub struct Data {
     /// Dependencies mutex.
-    pub dependencies: Arc<Mutex<HashMap<NodeIndex, Vec<String>>>>,
+    pub dependencies: Arc<Mutex<HashMap<NodeIndex, Vec<Name>>>>,
     /// Files mutex.
     pub files: Arc<Mutex<HashMap<PathBuf, String>>>,
     /// Graph mutex.
-    pub graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
+    pub graph: Arc<Mutex<SchemaGraph>>,
     /// Missing definition mutex.
-    pub missing_definitions: Arc<Mutex<HashMap<NodeIndex, Vec<String>>>>,
+    pub missing_definitions: Arc<Mutex<HashMap<NodeIndex, Vec<Name>>>>,
+    /// Node indices mutex.
+    pub node_indices: Arc<Mutex<NodeIndices>>,
+    /// Options of the analysis.
+    pub options: Arc<AnalysisOptions>,
 }
 
 impl State {
     /// Method to create a new State.
     pub fn new() -> Self {
+        State::with_options(AnalysisOptions::default())
+    }
+
+    /// Method to create a new State with analysis options.
+    pub fn with_options(options: AnalysisOptions) -> Self {
         State {
             shared: Data {
                 dependencies: Arc::new(Mutex::new(HashMap::new())),
                 files: Arc::new(Mutex::new(HashMap::new())),
-                graph: Arc::new(Mutex::new(Graph::<Node, (NodeIndex, NodeIndex)>::new())),
+                graph: Arc::new(Mutex::new(SchemaGraph::new())),
                 missing_definitions: Arc::new(Mutex::new(HashMap::new())),
+                node_indices: Arc::new(Mutex::new(HashMap::new())),
+                options: Arc::new(options),
             },
         }
     }
@@ -200,3 +1604,144 @@ impl Default for State {
         State::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAPHQL_TYPES: [&str; 17] = [
+        "directive",
+        "enum",
+        "enum_extension",
+        "field",
+        "fragment",
+        "input_object",
+        "input_object_extension",
+        "interface",
+        "interface_extension",
+        "object",
+        "object_extension",
+        "operation",
+        "scalar",
+        "scalar_extension",
+        "schema",
+        "union",
+        "union_extension",
+    ];
+
+    #[test]
+    fn check_wrap_list() {
+        let items = ["Alpha", "Beta", "Gamma", "Delta"];
+
+        assert_eq!(wrap_list(&items, 0, 1), "Alpha, Beta, Gamma, Delta");
+        assert_eq!(wrap_list(&items, 14, 1), "Alpha, Beta,\n Gamma, Delta");
+        assert_eq!(wrap_list(&items, 1, 0), "Alpha,\nBeta,\nGamma,\nDelta");
+        assert_eq!(wrap_list::<&str>(&[], 10, 0), "");
+    }
+
+    #[test]
+    fn check_graphql_round_trip() {
+        for graphql_type in GRAPHQL_TYPES {
+            let graphql = graphql_type.parse::<GraphQL>().unwrap();
+            let json = serde_json::to_string(&graphql).unwrap();
+
+            assert_eq!(json, format!(r#""{}""#, graphql_type));
+            assert_eq!(serde_json::from_str::<GraphQL>(&json).unwrap(), graphql);
+        }
+
+        assert!(serde_json::from_str::<GraphQL>(r#""object_definition""#).is_err());
+    }
+
+    #[test]
+    fn check_graphql_helpers() {
+        for graphql_type in GRAPHQL_TYPES {
+            let graphql = graphql_type.parse::<GraphQL>().unwrap();
+            let base_type = graphql_type.trim_end_matches("_extension");
+
+            // The CLI output and the filter values round-trip.
+            assert_eq!(graphql.as_str(), graphql_type);
+            assert_eq!(graphql.to_string().parse::<GraphQL>().unwrap(), graphql);
+            assert_eq!(graphql.is_extension(), graphql_type.ends_with("_extension"));
+            assert_eq!(
+                graphql.base_type().map(GraphQL::TypeDefinition),
+                match base_type {
+                    "directive" | "field" | "fragment" | "operation" | "schema" => None,
+                    _ => Some(base_type.parse::<GraphQL>().unwrap()),
+                }
+            );
+        }
+
+        assert_eq!(
+            GraphQL::TypeExtension(GraphQLType::InputObject).base_type(),
+            Some(GraphQLType::InputObject)
+        );
+        assert_eq!(GraphQL::Directive.base_type(), None);
+        assert!(!GraphQL::TypeDefinition(GraphQLType::Union).is_extension());
+    }
+
+    #[test]
+    fn check_node_round_trip() {
+        for graphql_type in GRAPHQL_TYPES {
+            let graphql = graphql_type.parse::<GraphQL>().unwrap();
+            let id = match graphql {
+                GraphQL::TypeExtension(_) => Some(String::from("Foo__1")),
+                _ => None,
+            };
+            let entity = Entity::new(
+                vec![String::from("Bar")],
+                graphql,
+                id,
+                String::from("Foo"),
+                Path::new("some_path/Foo.gql"),
+                Some((1, 1)),
+                String::from("type Foo { bar: Bar }"),
+            );
+            let node = Node::new(entity.clone(), entity.id.clone());
+            let json = serde_json::to_string(&node).unwrap();
+
+            assert!(json.contains(r#""path":"some_path/Foo.gql""#));
+            assert_eq!(serde_json::from_str::<Node>(&json).unwrap(), node);
+            assert_eq!(
+                serde_yaml::from_str::<Entity>(&serde_yaml::to_string(&entity).unwrap()).unwrap(),
+                entity
+            );
+        }
+    }
+
+    #[test]
+    fn check_subgraph_parsing() {
+        assert_eq!(
+            "accounts=./accounts".parse::<Subgraph>(),
+            Ok(Subgraph {
+                name: String::from("accounts"),
+                path: PathBuf::from("./accounts"),
+            })
+        );
+        assert!("accounts".parse::<Subgraph>().is_err());
+        assert!("=./accounts".parse::<Subgraph>().is_err());
+    }
+
+    #[test]
+    fn check_graph_snapshot_round_trip() {
+        let snapshot = GraphSnapshot {
+            nodes: vec![SnapshotNode {
+                dependencies: vec![String::from("Foo")],
+                description: Some(String::from("Extended Foo.")),
+                graphql: GraphQL::TypeExtension(GraphQLType::Object),
+                id: String::from("Foo__1"),
+                name: String::from("Foo"),
+                path: String::from("some_path/Foo.gql"),
+            }],
+            edges: vec![SnapshotEdge {
+                source: String::from("Foo__1"),
+                target: String::from("Foo"),
+            }],
+        };
+        let json = serde_json::to_string(&snapshot).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<GraphSnapshot>(&json).unwrap(),
+            snapshot
+        );
+    }
+}
@@ -1,10 +1,26 @@
 use crate::{
-    config::ALLOWED_EXTENSIONS,
-    extend_types::ExtendType,
-    state::{Entity, GraphQL, GraphQLType, Node},
+    config::{Config, BUILT_IN_SCALARS, BUILT_IN_SCALARS_PATH, DEFAULT_WRAP_WIDTH, GZIP_EXTENSION},
+    error::Error,
+    extend_types::{
+        get_extended_id, is_directive_argument_coordinate, sort_and_dedupe_dependencies,
+        ExtendType, FieldDependencies, FieldSetDependencies,
+    },
+    introspection::{introspection_to_sdl, is_introspection_path},
+    lint::{get_fields, parse_definition},
+    operations::root_types,
+    state::{
+        AnalysisOptions, CompositionIssue, CytoscapeEdgeData, CytoscapeElement, CytoscapeElements,
+        CytoscapeGraph, CytoscapeNodeData, Data, DeadReference, Dependency, DocsCoverage, Edge,
+        Entity, Extraction, FileEdge, FileGraph, FileGraphSnapshot, FileSummary, GraphQL,
+        GraphQLType, GraphSnapshot, GraphvizEdge, GraphvizGraph, GraphvizObject, Implementation,
+        Import, ImportViolation, InputUsage, Interner, MergeConflict, MissingDefinitions,
+        MissingReport, Name, Node, NodeIndices, Ownership, PathStyle, PossibleType,
+        ScalarReference, ScalarUsage, ScatteredType, SchemaGraph, SnapshotEdge, SnapshotNode,
+        SortBy, State, Subgraph, SubgraphSchema, WalkSummary,
+    },
 };
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_std::{
     fs,
     future::Future,
@@ -13,95 +29,400 @@ use async_std::{
     prelude::*,
     sync::{Arc, Mutex},
 };
-use graphql_parser::{parse_schema, schema};
-use petgraph::{graph::NodeIndex, Direction};
-use std::{collections::HashMap, process::exit};
+use flate2::read::GzDecoder;
+use futures::stream::{self, TryStreamExt};
+use graphql_parser::{parse_query, parse_schema, schema};
+use petgraph::{
+    algo::{tarjan_scc, toposort},
+    dot::{self, Dot},
+    graph::NodeIndex,
+    visit::EdgeRef,
+    Direction,
+};
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    io::Read,
+};
+
+/// Index the nodes of a graph by id, keeping the first node of duplicate ids.
+pub fn index_nodes(graph: &SchemaGraph) -> NodeIndices {
+    let mut node_indices = NodeIndices::new();
+
+    for index in graph.node_indices() {
+        node_indices.entry(graph[index].id.clone()).or_insert(index);
+    }
 
-/// Check if a file extension is allowed.
-fn is_extension_allowed(extension: &str) -> bool {
-    ALLOWED_EXTENSIONS.to_vec().contains(&extension)
+    node_indices
 }
 
-/// Print missing definitions.
-pub async fn print_missing_definitions(
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-    missing_definitions: Arc<Mutex<HashMap<NodeIndex, Vec<String>>>>,
-) -> Result<()> {
-    let graph = graph.lock().await;
-    let missing_definitions = missing_definitions.lock().await;
+/// Return the ids shared by several nodes with all their indices.
+pub fn duplicate_ids(
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+) -> HashMap<String, Vec<NodeIndex>> {
+    let mut duplicate_ids: HashMap<String, Vec<NodeIndex>> = HashMap::new();
 
-    for (node_index, definitions) in missing_definitions.iter() {
-        println!(
-            "\n# {} {} not defined in:{}",
-            definitions.join(", "),
-            if definitions.len() == 1 { "is" } else { "are" },
-            graph.node_weight(*node_index).unwrap().entity,
-        );
+    for index in graph.node_indices() {
+        let id = &graph[index].id;
+
+        // Only the first node of an id is indexed.
+        if node_indices.get(id) != Some(&index) {
+            duplicate_ids
+                .entry(id.to_string())
+                .or_insert_with(|| vec![node_indices[id]])
+                .push(index);
+        }
     }
 
-    Ok(())
+    duplicate_ids
 }
 
-/// Find and return neighbors of a node.
-pub async fn find_neighbors(
+/// Return the schema definitions, sorted by location, more than one being
+/// invalid.
+pub fn schema_definitions(graph: &SchemaGraph) -> Vec<Entity> {
+    let mut schema_definitions = graph
+        .node_weights()
+        .filter(|node| node.entity.graphql == GraphQL::Schema)
+        .map(|node| node.entity.clone())
+        .collect::<Vec<Entity>>();
+    schema_definitions.sort_by(|a, b| (&a.path, a.position).cmp(&(&b.path, b.position)));
+
+    schema_definitions
+}
+
+/// Find a node by id.
+fn find_index(node: &str, node_indices: &NodeIndices) -> Option<NodeIndex> {
+    node_indices.get(node).copied()
+}
+
+/// Resolve the id of a node, optionally ignoring case. An exact match always
+/// wins, otherwise several case-insensitive matches are reported as ambiguous.
+pub fn resolve_node(
     node: &str,
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-    direction: Direction,
+    node_indices: &NodeIndices,
+    ignore_case: bool,
+) -> std::result::Result<String, Error> {
+    if find_index(node, node_indices).is_some() {
+        return Ok(node.to_owned());
+    }
+
+    if !ignore_case {
+        return Err(Error::NodeNotFound(node.to_owned()));
+    }
+
+    let mut candidates = node_indices
+        .keys()
+        .filter(|id| id.eq_ignore_ascii_case(node))
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    candidates.sort();
+
+    match candidates.len() {
+        0 => Err(Error::NodeNotFound(node.to_owned())),
+        1 => Ok(candidates.remove(0)),
+        _ => Err(Error::AmbiguousNode(node.to_owned(), candidates)),
+    }
+}
+
+/// Return the entity of a node.
+pub fn get_node(node: &str, graph: &SchemaGraph, node_indices: &NodeIndices) -> Option<Entity> {
+    find_index(node, node_indices).map(|index| graph[index].entity.clone())
+}
+
+/// Return the extensions of a type, in the nodes order.
+pub fn extensions_of(name: &str, graph: &SchemaGraph) -> Vec<Entity> {
+    graph
+        .node_weights()
+        .filter(|node| node.entity.extends.as_deref() == Some(name))
+        .map(|node| node.entity.clone())
+        .collect()
+}
+
+/// Return the entities of the nodes whose id matches a regex, sorted by id.
+pub fn nodes_matching(
+    pattern: &Regex,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
 ) -> Vec<Entity> {
-    let graph = graph.lock().await;
+    let mut ids = node_indices
+        .keys()
+        .filter(|id| pattern.is_match(id))
+        .collect::<Vec<&Name>>();
+
+    ids.sort();
+
+    ids.into_iter()
+        .map(|id| graph[node_indices[id]].entity.clone())
+        .collect()
+}
+
+/// Sort entities, or the items of a report about them, by name, then by id
+/// and path, for the reports to be stable.
+fn sort_entities<T: AsRef<Entity>>(entities: &mut [T]) {
+    entities.sort_by(|a, b| {
+        let (a, b) = (a.as_ref(), b.as_ref());
+
+        (&a.name, &a.id, &a.path).cmp(&(&b.name, &b.id, &b.path))
+    });
+}
+
+/// Keys of a listed item ordered by --sort-by, i.e. the node of the
+/// definition it is about, the GraphQL type and the location of the latter.
+#[derive(Debug)]
+pub struct SortKeys<'a> {
+    /// Id of the node, whose degree is looked up.
+    pub node: &'a str,
+    /// GraphQL type of the definition.
+    pub graphql: GraphQL,
+    /// Path and position of the definition.
+    pub location: (Cow<'a, str>, Option<(usize, usize)>),
+}
+
+/// Item of a list ordered by --sort-by.
+pub trait Sortable {
+    /// Return the keys the item is sorted by.
+    fn sort_keys(&self) -> SortKeys<'_>;
+}
+
+impl Sortable for Entity {
+    fn sort_keys(&self) -> SortKeys<'_> {
+        SortKeys {
+            node: &self.id,
+            graphql: self.graphql.clone(),
+            location: (self.path.to_string_lossy(), self.position),
+        }
+    }
+}
+
+impl Sortable for MissingReport {
+    fn sort_keys(&self) -> SortKeys<'_> {
+        self.entity.sort_keys()
+    }
+}
+
+// The implementor declares the implements clause.
+impl Sortable for Implementation {
+    fn sort_keys(&self) -> SortKeys<'_> {
+        SortKeys {
+            node: &self.implementor,
+            graphql: self.graphql.clone(),
+            location: (self.path.to_string_lossy(), self.position),
+        }
+    }
+}
+
+// The union declares its members, the object its interfaces.
+impl Sortable for PossibleType {
+    fn sort_keys(&self) -> SortKeys<'_> {
+        SortKeys {
+            node: match self.graphql.base_type() {
+                Some(GraphQLType::Union) => &self.abstract_type,
+                _ => &self.object,
+            },
+            graphql: self.graphql.clone(),
+            location: (self.path.to_string_lossy(), self.position),
+        }
+    }
+}
+
+impl Sortable for ScalarUsage {
+    fn sort_keys(&self) -> SortKeys<'_> {
+        SortKeys {
+            node: &self.scalar,
+            graphql: GraphQL::TypeDefinition(GraphQLType::Scalar),
+            location: (self.path.to_string_lossy(), self.position),
+        }
+    }
+}
+
+// The fields are only nodes with --granularity field, their type always is.
+impl Sortable for InputUsage {
+    fn sort_keys(&self) -> SortKeys<'_> {
+        SortKeys {
+            node: self
+                .field
+                .split_once('.')
+                .map_or(&self.field, |(parent, _)| parent),
+            graphql: GraphQL::Field,
+            location: (self.path.to_string_lossy(), self.position),
+        }
+    }
+}
+
+/// Sort listed items, already ordered by name. The degree of an item is the
+/// number of edges of its node, 0 if not in the graph.
+pub fn sort_listed_by<T: Sortable>(
+    items: &mut [T],
+    sort_by: SortBy,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+) {
+    // Stable, the ties keep the name order.
+    match sort_by {
+        SortBy::Name => {}
+        SortBy::Type => items.sort_by_cached_key(|item| item.sort_keys().graphql.as_str()),
+        SortBy::Path => items.sort_by_cached_key(|item| {
+            let (path, position) = item.sort_keys().location;
 
-    match graph.node_indices().find(|index| graph[*index].id == node) {
+            (path.into_owned(), position)
+        }),
+        SortBy::Degree => items.sort_by_cached_key(|item| {
+            std::cmp::Reverse(
+                find_index(item.sort_keys().node, node_indices).map_or(0, |index| {
+                    graph.edges_directed(index, Direction::Incoming).count()
+                        + graph.edges_directed(index, Direction::Outgoing).count()
+                }),
+            )
+        }),
+    }
+}
+
+/// Return the neighbors of a node, sorted by name.
+pub fn neighbors(
+    node: &str,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+    direction: Direction,
+) -> Vec<Entity> {
+    let mut neighbors = match find_index(node, node_indices) {
         Some(index) => graph
             .neighbors_directed(index, direction)
             .map(|index| &graph.node_weight(index).unwrap().entity)
             .cloned()
             .collect::<Vec<Entity>>(),
         None => vec![],
+    };
+
+    sort_entities(&mut neighbors);
+
+    neighbors
+}
+
+/// Return the transitive dependencies of a node, the node excluded unless it
+/// depends on itself through a cycle.
+fn transitive_dependencies(index: NodeIndex, graph: &SchemaGraph) -> HashSet<NodeIndex> {
+    let mut visited = HashSet::new();
+    let mut stack = vec![index];
+
+    while let Some(index) = stack.pop() {
+        // Dependencies are the sources of the incoming edges.
+        for dependency in graph.neighbors_directed(index, Direction::Incoming) {
+            if visited.insert(dependency) {
+                stack.push(dependency);
+            }
+        }
     }
+
+    visited
 }
 
-/// Print orphan nodes.
-pub async fn find_and_print_neighbors(
+/// Return the transitive dependencies of a node defined in another file than
+/// the node, the built-in ones excluded, sorted by name.
+pub fn external_dependencies(
     node: &str,
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-    direction: Direction,
-) -> Result<()> {
-    let graph_clone = graph.clone();
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+) -> Vec<Entity> {
+    let mut external_dependencies = match find_index(node, node_indices) {
+        Some(index) => transitive_dependencies(index, graph)
+            .into_iter()
+            .map(|dependency| &graph[dependency].entity)
+            .filter(|entity| !entity.is_built_in() && entity.path != graph[index].entity.path)
+            .cloned()
+            .collect::<Vec<Entity>>(),
+        None => vec![],
+    };
 
-    // Ensure that the node exists!
-    find_node(node, graph).await?;
+    sort_entities(&mut external_dependencies);
 
-    let dependencies = find_neighbors(node, graph_clone, direction).await;
+    external_dependencies
+}
 
-    if dependencies.is_empty() {
-        eprintln!("No dependencies found for node {}", node);
-        exit(1);
-    }
+/// Return the incoming and outgoing neighbors of a node, each one once: a
+/// neighbor on both sides, e.g. through a cycle, is only returned as incoming.
+pub fn neighborhood(
+    node: &str,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+) -> (Vec<Entity>, Vec<Entity>) {
+    let mut seen = HashSet::new();
+    let mut dedupe = |entities: Vec<Entity>| {
+        entities
+            .into_iter()
+            .filter(|entity| seen.insert(entity.id.clone()))
+            .collect::<Vec<Entity>>()
+    };
+    let incoming = dedupe(neighbors(node, graph, node_indices, Direction::Incoming));
+    let outgoing = dedupe(neighbors(node, graph, node_indices, Direction::Outgoing));
 
-    for dependency in dependencies {
-        println!("{}", dependency);
-    }
+    (incoming, outgoing)
+}
 
-    Ok(())
+/// Check if a node is the type or extension defining a field.
+fn is_parent_type(field: &Entity, entity: &Entity) -> bool {
+    entity.graphql.base_type().is_some()
+        && field
+            .name
+            .split_once('.')
+            .map_or(false, |(name, _)| name == entity.name)
 }
 
-/// Find and return orphan nodes.
-pub async fn find_orphans(
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-) -> Vec<Entity> {
-    let graph = &graph.lock().await;
-    let externals = graph.externals(Direction::Outgoing);
+/// Check if a node has no dependents. With the operations linked, a field
+/// only depended on by its type is an orphan, as no operation selects it.
+fn is_orphan(index: NodeIndex, graph: &SchemaGraph, has_operations: bool) -> bool {
+    let entity = &graph[index].entity;
+
+    graph
+        .neighbors_directed(index, Direction::Outgoing)
+        .all(|dependent| {
+            has_operations
+                && entity.graphql == GraphQL::Field
+                && is_parent_type(entity, &graph[dependent].entity)
+        })
+}
+
+/// Return the orphan nodes sorted by name, optionally only the ones of the
+/// provided GraphQL type(s). The directives are only returned when asked for,
+/// see unused_directives.
+pub fn orphans(graph: &SchemaGraph, only: &[GraphQL]) -> Vec<Entity> {
     let has_root_schema = graph
         .node_indices()
-        .any(|index| graph[index].id == "schema");
+        .any(|index| &*graph[index].id == "schema");
+    // Without --operations, nothing but their type depends on the fields.
+    let has_operations = graph
+        .node_weights()
+        .any(|node| matches!(node.entity.graphql, GraphQL::Operation | GraphQL::Fragment));
 
-    externals
+    let mut orphans = graph
+        .node_indices()
+        .filter(|index| is_orphan(*index, graph, has_operations))
         .filter_map(|index| {
             let entity = graph.node_weight(index).unwrap().entity.clone();
 
+            // Filter the reported orphans without altering the graph.
+            if !only.is_empty() && !only.contains(&entity.graphql) {
+                return None;
+            }
+
+            // The unused directives are reported on their own.
+            if only.is_empty() && entity.graphql == GraphQL::Directive {
+                return None;
+            }
+
+            // Skip the synthesized built-in scalars, only referenced by
+            // extensions when they don't have outgoing edges.
+            if entity.is_built_in() {
+                return None;
+            }
+
             match entity.graphql {
                 // Skip root schema has it can't have outgoing edges.
                 GraphQL::Schema => None,
+                // Skip the operations, entry points of the clients.
+                GraphQL::Operation => None,
                 // Skip Mutation, Query and Subscription if no root schema is defined
                 // as those nodes can't have outgoing edges.
                 GraphQL::TypeDefinition(GraphQLType::Object)
@@ -115,561 +436,5431 @@ pub async fn find_orphans(
                 _ => Some(entity),
             }
         })
-        .collect::<Vec<Entity>>()
+        .collect::<Vec<Entity>>();
+
+    sort_entities(&mut orphans);
+
+    orphans
 }
 
-/// Print orphan nodes.
-pub async fn find_and_print_orphans(
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-) -> Result<()> {
-    let orphans = find_orphans(graph).await;
+/// Return the declared directives which are never used, sorted by name.
+pub fn unused_directives(graph: &SchemaGraph) -> Vec<Entity> {
+    orphans(graph, &[GraphQL::Directive])
+}
 
-    if orphans.is_empty() {
-        eprintln!("No orphan node found");
-        exit(1);
-    }
+/// Return the missing definitions of each node, sorted by name.
+pub fn missing_definitions_report(
+    graph: &SchemaGraph,
+    missing_definitions: &HashMap<NodeIndex, Vec<Name>>,
+) -> Vec<MissingReport> {
+    let mut report = missing_definitions
+        .iter()
+        .map(|(node_index, definitions)| MissingReport {
+            entity: graph[*node_index].entity.clone(),
+            missing: definitions.clone(),
+        })
+        .collect::<Vec<MissingReport>>();
 
-    for orphan in orphans {
-        println!("{}", orphan);
-    }
+    sort_entities(&mut report);
 
-    Ok(())
+    report
 }
 
-/// Find a node by name, display it with syntax highlighting or exit.
-pub async fn find_node(
-    node: &str,
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-) -> Result<()> {
-    let graph = graph.lock().await;
+/// Return the serializable missing definitions of each node, sorted by node
+/// and path.
+pub fn missing_definitions_json(
+    graph: &SchemaGraph,
+    missing_definitions: &HashMap<NodeIndex, Vec<Name>>,
+) -> Vec<MissingDefinitions> {
+    let mut missing_definitions = missing_definitions_report(graph, missing_definitions)
+        .into_iter()
+        .map(|report| {
+            let mut missing = report
+                .missing
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>();
+            missing.sort();
 
-    match graph.node_indices().find(|index| graph[*index].id == node) {
-        Some(index) => {
-            let entity = &graph.node_weight(index).unwrap().entity;
+            MissingDefinitions {
+                node: report.entity.id,
+                path: report.entity.path.to_string_lossy().into_owned(),
+                missing,
+            }
+        })
+        .collect::<Vec<MissingDefinitions>>();
+    missing_definitions.sort_by(|a, b| (&a.node, &a.path).cmp(&(&b.node, &b.path)));
 
-            println!("{}", entity);
+    missing_definitions
+}
 
-            Ok(())
+/// Check if a missing definition of an entity is only referenced by @external
+/// fields. Relies on the raw SDL of the entity.
+fn is_external_definition(entity: &Entity, name: &str) -> bool {
+    let is_external = |fields: Vec<FieldDependencies>, type_dependencies: Vec<String>| {
+        let mut referencing = fields
+            .into_iter()
+            .filter(|field| {
+                field
+                    .dependencies
+                    .iter()
+                    .any(|dependency| dependency == name)
+            })
+            .peekable();
+
+        !type_dependencies
+            .iter()
+            .any(|dependency| dependency == name)
+            && referencing.peek().is_some()
+            && referencing.all(|field| {
+                field
+                    .dependencies
+                    .iter()
+                    .any(|dependency| dependency == "@external")
+            })
+    };
+
+    match entity.graphql {
+        GraphQL::Field => entity
+            .dependencies
+            .iter()
+            .any(|dependency| &**dependency == "@external"),
+        _ => match parse_definition(entity) {
+            Some(schema::Definition::TypeDefinition(type_definition)) => is_external(
+                type_definition.get_fields(),
+                type_definition.get_type_dependencies(),
+            ),
+            Some(schema::Definition::TypeExtension(type_extension)) => is_external(
+                type_extension.get_fields(),
+                type_extension.get_type_dependencies(),
+            ),
+            _ => false,
+        },
+    }
+}
+
+/// Split the missing definitions between the genuine ones and the ones owned
+/// by other federated subgraphs, returned in that order. The latter are the
+/// types only extended in the subgraph or only referenced by @external fields.
+pub fn split_external_definitions(
+    graph: &SchemaGraph,
+    missing_definitions: &HashMap<NodeIndex, Vec<Name>>,
+) -> (HashMap<NodeIndex, Vec<Name>>, HashMap<NodeIndex, Vec<Name>>) {
+    let extended = graph
+        .node_weights()
+        .filter_map(|node| node.entity.extends.as_deref())
+        .collect::<HashSet<&str>>();
+    let mut genuine = HashMap::new();
+    let mut external = HashMap::new();
+
+    for (node_index, definitions) in missing_definitions {
+        let (external_definitions, genuine_definitions): (Vec<Name>, Vec<Name>) =
+            definitions.iter().cloned().partition(|name| {
+                extended.contains(&**name)
+                    || is_external_definition(&graph[*node_index].entity, name)
+            });
+
+        if !genuine_definitions.is_empty() {
+            genuine.insert(*node_index, genuine_definitions);
         }
-        None => {
-            eprintln!("Node {} not found", node);
-            exit(1);
+
+        if !external_definitions.is_empty() {
+            external.insert(*node_index, external_definitions);
         }
     }
-}
 
-/// Recursively read directories and files for a given path.
-pub fn get_files(
-    path: PathBuf,
-    files: Arc<Mutex<HashMap<PathBuf, String>>>,
-) -> Pin<Box<dyn Future<Output = Result<()>>>> {
-    // Use a hack to get async recursive calls working.
-    Box::pin(async move {
-        let thread_safe_path = Arc::new(path);
-        let file_or_dir = fs::metadata(thread_safe_path.as_ref()).await?;
-        let file_type = file_or_dir.file_type();
-        let extension = match Path::new(thread_safe_path.as_ref()).extension() {
-            Some(extension) => extension.to_str().unwrap(),
-            None => "",
-        };
+    (genuine, external)
+}
 
-        if file_type.is_file() {
-            if is_extension_allowed(extension) {
-                let contents = fs::read_to_string(thread_safe_path.as_ref()).await?;
-                let mut files = files.lock().await;
+/// Split the missing definitions between the types and the applied
+/// directives without definition, returned in that order.
+pub fn split_unknown_directives(
+    missing_definitions: &HashMap<NodeIndex, Vec<Name>>,
+) -> (HashMap<NodeIndex, Vec<Name>>, HashMap<NodeIndex, Vec<Name>>) {
+    let mut types = HashMap::new();
+    let mut directives = HashMap::new();
 
-                files.insert(thread_safe_path.as_ref().clone(), contents);
-            }
+    for (node_index, definitions) in missing_definitions {
+        let (unknown_directives, type_definitions): (Vec<Name>, Vec<Name>) = definitions
+            .iter()
+            .cloned()
+            .partition(|name| name.starts_with('@'));
 
-            return Ok(());
+        if !type_definitions.is_empty() {
+            types.insert(*node_index, type_definitions);
         }
 
-        let mut dir = fs::read_dir(thread_safe_path.as_ref()).await?;
+        if !unknown_directives.is_empty() {
+            directives.insert(*node_index, unknown_directives);
+        }
+    }
 
-        while let Some(result) = dir.next().await {
-            let entry: fs::DirEntry = result?;
-            let inner_path = entry.path();
-            let inner_path_cloned = inner_path.clone();
-            let metadata = entry.clone().metadata().await?;
-            let is_dir = metadata.is_dir();
-            let extension = match &inner_path.extension() {
-                Some(extension) => extension.to_str().unwrap(),
-                None => "",
-            };
+    (types, directives)
+}
 
-            if !is_dir && is_extension_allowed(extension) {
-                let contents = fs::read_to_string(inner_path).await?;
-                let mut files = files.lock().await;
+/// Invert the missing definitions, returning for each missing name the
+/// entities referencing it, sorted by name.
+pub fn missing_definitions_by_name(
+    graph: &SchemaGraph,
+    missing_definitions: &HashMap<NodeIndex, Vec<Name>>,
+) -> HashMap<String, Vec<Entity>> {
+    let mut missing_definitions_by_name: HashMap<String, Vec<Entity>> = HashMap::new();
 
-                files.insert(inner_path_cloned, contents);
-            } else {
-                get_files(inner_path, files.clone()).await?;
-            }
+    for (node_index, definitions) in missing_definitions.iter() {
+        for definition in definitions {
+            missing_definitions_by_name
+                .entry(definition.to_string())
+                .or_default()
+                .push(graph[*node_index].entity.clone());
         }
+    }
 
-        Ok(())
-    })
+    for entities in missing_definitions_by_name.values_mut() {
+        sort_entities(entities);
+    }
+
+    missing_definitions_by_name
 }
 
-async fn add_node_and_dependencies(
-    entity: impl ExtendType,
-    filter: &[GraphQL],
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-    dependencies: Arc<Mutex<HashMap<NodeIndex, Vec<String>>>>,
-    file: &(PathBuf, String),
-) -> Result<()> {
-    // If a filter is provided and the mapped type of the entity is not part of
-    // this filter, skip it.
-    if !filter.is_empty() && !filter.to_vec().contains(&entity.get_mapped_type()) {
-        return Ok(());
+/// Return the name of the type wrapped in a list and/or non-null type.
+pub(crate) fn get_named_type<'a>(field_type: &'a schema::Type<'_, String>) -> &'a str {
+    match field_type {
+        schema::Type::NamedType(name) => name,
+        schema::Type::ListType(inner) | schema::Type::NonNullType(inner) => get_named_type(inner),
     }
+}
 
-    let mut graph = graph.lock().await;
+/// Names of the types reachable from the root operation types, following the
+/// dependencies of the nodes and of their extensions, and the implementations
+/// of the reachable interfaces.
+fn reachable_names(graph: &SchemaGraph) -> HashSet<String> {
+    let mut nodes_by_name: HashMap<&str, Vec<&Entity>> = HashMap::new();
+    let mut implementations: HashMap<String, Vec<&str>> = HashMap::new();
 
-    let entity_dependencies = entity.get_dependencies();
-    let (id, name) = entity.get_id_and_name();
-    let new_entity = Entity::new(
-        entity_dependencies.clone(),
-        entity.get_mapped_type(),
-        id,
-        name,
-        file.0.to_owned(),
-        entity.get_raw(),
-    );
-    let node_id = new_entity.id.clone();
-    let node_index = graph.add_node(Node::new(new_entity, node_id));
+    for node in graph.node_weights() {
+        let entity = &node.entity;
 
-    // Update dependencies.
-    let mut dependencies = dependencies.lock().await;
-    dependencies.insert(node_index, entity_dependencies);
+        nodes_by_name.entry(&entity.name).or_default().push(entity);
 
-    Ok(())
+        for interface in &entity.interfaces {
+            implementations
+                .entry(interface.clone())
+                .or_default()
+                .push(&entity.name);
+        }
+    }
+
+    // The operation types of the root schema, or the default ones.
+    let mut names = match graph
+        .node_weights()
+        .find(|node| node.entity.graphql == GraphQL::Schema)
+    {
+        Some(schema) => schema
+            .entity
+            .dependencies
+            .iter()
+            .map(|dependency| &**dependency)
+            .collect::<Vec<&str>>(),
+        None => vec!["Query", "Mutation", "Subscription"],
+    };
+    let mut reachable = HashSet::new();
+
+    while let Some(name) = names.pop() {
+        if !reachable.insert(name.to_owned()) {
+            continue;
+        }
+
+        for entity in nodes_by_name.get(name).into_iter().flatten() {
+            names.extend(entity.dependencies.iter().map(|dependency| &**dependency));
+        }
+
+        names.extend(implementations.get(name).into_iter().flatten().copied());
+    }
+
+    reachable
 }
 
-/// Parse the files, generate an AST and walk it to populate the graph.
-pub async fn populate_graph_from_ast(
-    dependencies: Arc<Mutex<HashMap<NodeIndex, Vec<String>>>>,
-    files: Arc<Mutex<HashMap<PathBuf, String>>>,
-    filter: &[GraphQL],
-    graph: Arc<Mutex<petgraph::Graph<Node, (NodeIndex, NodeIndex)>>>,
-    missing_definitions: Arc<Mutex<HashMap<NodeIndex, Vec<String>>>>,
-) -> Result<()> {
-    let files = files.lock().await;
+/// Return the bridges of the graph treated as undirected, i.e. the edges
+/// whose removal splits it into more components, as (source, target) in the
+/// edges order.
+pub fn bridges(graph: &SchemaGraph) -> Vec<(Entity, Entity)> {
+    let incident_edges = |node: NodeIndex| {
+        graph
+            .edges_directed(node, Direction::Outgoing)
+            .map(|edge| (edge.id(), edge.target()))
+            .chain(
+                graph
+                    .edges_directed(node, Direction::Incoming)
+                    .map(|edge| (edge.id(), edge.source())),
+            )
+            .collect::<Vec<_>>()
+            .into_iter()
+    };
+    let mut discovery = vec![usize::MAX; graph.node_count()];
+    let mut low = vec![0; graph.node_count()];
+    let mut is_bridge = vec![false; graph.edge_count()];
+    let mut time = 0;
+
+    // Iterative Tarjan's algorithm, the parent edge being skipped rather
+    // than the parent node to handle the edges in both directions.
+    for root in graph.node_indices() {
+        if discovery[root.index()] != usize::MAX {
+            continue;
+        }
 
-    // Populate the nodes first.
-    for file in files.clone() {
-        let ast = parse_schema::<String>(file.1.as_str())?;
+        discovery[root.index()] = time;
+        low[root.index()] = time;
+        time += 1;
 
-        // Reference: http://spec.graphql.org/draft/
-        for definition in ast.definitions {
-            let graph = graph.clone();
-            let dependencies = dependencies.clone();
+        let mut stack = vec![(root, None, incident_edges(root))];
 
-            match definition {
-                schema::Definition::TypeDefinition(type_definition) => {
-                    add_node_and_dependencies(type_definition, filter, graph, dependencies, &file)
-                        .await?
-                }
-                schema::Definition::TypeExtension(type_extension) => {
-                    add_node_and_dependencies(type_extension, filter, graph, dependencies, &file)
-                        .await?
+        while let Some((node, parent_edge, edges)) = stack.last_mut() {
+            let node = *node;
+
+            match edges.next() {
+                Some((edge, _)) if Some(edge) == *parent_edge => {}
+                Some((_, next)) if next == node => {}
+                Some((_, next)) if discovery[next.index()] != usize::MAX => {
+                    low[node.index()] = low[node.index()].min(discovery[next.index()]);
                 }
-                schema::Definition::SchemaDefinition(schema_definition) => {
-                    add_node_and_dependencies(schema_definition, filter, graph, dependencies, &file)
-                        .await?
+                Some((edge, next)) => {
+                    discovery[next.index()] = time;
+                    low[next.index()] = time;
+                    time += 1;
+                    stack.push((next, Some(edge), incident_edges(next)));
                 }
-                schema::Definition::DirectiveDefinition(directive_definition) => {
-                    add_node_and_dependencies(
-                        directive_definition,
-                        filter,
-                        graph,
-                        dependencies,
-                        &file,
-                    )
-                    .await?
+                None => {
+                    let parent_edge = *parent_edge;
+
+                    stack.pop();
+
+                    if let (Some((parent, ..)), Some(edge)) = (stack.last(), parent_edge) {
+                        low[parent.index()] = low[parent.index()].min(low[node.index()]);
+
+                        if low[node.index()] > discovery[parent.index()] {
+                            is_bridge[edge.index()] = true;
+                        }
+                    }
                 }
             }
         }
     }
 
-    // Populate the edges.
-    let dependencies = &*dependencies.lock().await;
+    graph
+        .edge_indices()
+        .filter(|edge| is_bridge[edge.index()])
+        .filter_map(|edge| graph.edge_endpoints(edge))
+        .map(|(source, target)| (graph[source].entity.clone(), graph[target].entity.clone()))
+        .collect()
+}
 
-    for (node_index, inner_dependencies) in dependencies {
-        let mut node_missing_definitions: Vec<String> = vec![];
+/// Return the objects, interfaces and input objects defined without fields,
+/// in the nodes order, unless an extension adds some.
+pub fn empty_types(graph: &SchemaGraph) -> Vec<Entity> {
+    let extended = graph
+        .node_weights()
+        .filter(|node| !node.entity.empty)
+        .filter_map(|node| node.entity.extends.as_deref())
+        .collect::<HashSet<&str>>();
 
-        for dependency in inner_dependencies {
-            let mut graph = graph.lock().await;
+    graph
+        .node_weights()
+        .map(|node| &node.entity)
+        .filter(|entity| {
+            matches!(entity.graphql, GraphQL::TypeDefinition(_))
+                && entity.empty
+                && !extended.contains(entity.name.as_str())
+        })
+        .cloned()
+        .collect()
+}
 
-            match graph
-                .node_indices()
-                .find(|index| graph[*index].id == *dependency)
-            {
-                Some(index) => match &graph[*node_index].entity.graphql {
-                    // Reverse edge for extension types.
-                    GraphQL::TypeExtension(GraphQLType::Enum)
-                    | GraphQL::TypeExtension(GraphQLType::InputObject)
-                    | GraphQL::TypeExtension(GraphQLType::Interface)
-                    | GraphQL::TypeExtension(GraphQLType::Object)
-                    | GraphQL::TypeExtension(GraphQLType::Scalar)
-                    | GraphQL::TypeExtension(GraphQLType::Union) => {
-                        graph.update_edge(*node_index, index, (*node_index, index));
-                    }
-                    _ => {
-                        graph.update_edge(index, *node_index, (index, *node_index));
-                    }
-                },
-                None => match dependency.as_str() {
-                    // Built-in Scalars, skip.
-                    "Boolean" | "Float" | "ID" | "Int" | "String" => {}
-                    // Keep track of possible missing definitions, should have been resolved at this point!
-                    _ => {
-                        node_missing_definitions.push(dependency.to_owned());
-                    }
-                },
-            }
-        }
+/// Return the types whose definition and extensions, grouped by the name of
+/// their base, span more distinct files than the threshold, sorted by name.
+pub fn scattered_types(graph: &SchemaGraph, threshold: usize) -> Vec<ScatteredType> {
+    let mut paths_by_name: BTreeMap<&str, BTreeSet<&PathBuf>> = BTreeMap::new();
 
-        if !node_missing_definitions.is_empty() {
-            missing_definitions
-                .lock()
-                .await
-                .insert(*node_index, node_missing_definitions);
-        }
+    for entity in graph.node_weights().map(|node| &node.entity) {
+        let name = match (&entity.graphql, &entity.extends) {
+            (GraphQL::TypeDefinition(_), _) => entity.name.as_str(),
+            (GraphQL::TypeExtension(_), Some(extends)) => extends.as_str(),
+            _ => continue,
+        };
+
+        paths_by_name.entry(name).or_default().insert(&entity.path);
     }
 
-    Ok(())
+    paths_by_name
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > threshold)
+        .map(|(name, paths)| ScatteredType {
+            name: name.to_owned(),
+            paths: paths
+                .into_iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+        })
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    use crate::state::{Data, GraphQL, GraphQLType, State};
+/// Return the implements clauses of the graph, extensions included, sorted
+/// by implementor, interface and location.
+fn implementations(graph: &SchemaGraph) -> Vec<Implementation> {
+    let mut implementations = graph
+        .node_weights()
+        .map(|node| &node.entity)
+        .flat_map(|entity| {
+            entity
+                .interfaces
+                .iter()
+                .map(move |interface| Implementation {
+                    implementor: entity.name.clone(),
+                    interface: interface.clone(),
+                    path: entity.path.clone(),
+                    position: entity.position,
+                    graphql: entity.graphql.clone(),
+                })
+        })
+        .collect::<Vec<Implementation>>();
+    implementations.sort_by(|a, b| {
+        (&a.implementor, &a.interface, &a.path, a.position).cmp(&(
+            &b.implementor,
+            &b.interface,
+            &b.path,
+            b.position,
+        ))
+    });
 
-    use async_std::task;
-    use petgraph::graph::NodeIndex;
+    implementations
+}
 
-    async fn scaffold(files: Vec<(PathBuf, String)>, filters: &[GraphQL]) -> Data {
-        let state = State::new();
-        let shared_data = state.shared;
-        let shared_data_for_populate = shared_data.clone();
+/// Return the objects and interfaces implementing an interface, through
+/// their definition or an extension.
+pub fn implementors(interface: &str, graph: &SchemaGraph) -> Vec<Implementation> {
+    implementations(graph)
+        .into_iter()
+        .filter(|implementation| implementation.interface == interface)
+        .collect()
+}
 
-        task::block_on(async {
-            let mut shared_files = shared_data.files.lock().await;
+/// Return the interfaces implemented by an object or interface, through its
+/// definition or an extension.
+pub fn interfaces_of(node: &str, graph: &SchemaGraph) -> Vec<Implementation> {
+    implementations(graph)
+        .into_iter()
+        .filter(|implementation| implementation.implementor == node)
+        .collect()
+}
 
-            for (path, contents) in files {
-                shared_files.insert(path, contents);
-            }
+/// Return the possible types of the graph, i.e. the objects implementing an
+/// interface and the members of the unions, extensions included, sorted by
+/// abstract type, object and location.
+fn all_possible_types(graph: &SchemaGraph) -> Vec<PossibleType> {
+    let objects = graph
+        .node_weights()
+        .filter(|node| node.entity.graphql == GraphQL::TypeDefinition(GraphQLType::Object))
+        .map(|node| node.entity.name.as_str())
+        .collect::<HashSet<&str>>();
+    let members = graph
+        .node_weights()
+        .map(|node| &node.entity)
+        .flat_map(|entity| {
+            entity.members.iter().map(move |member| PossibleType {
+                abstract_type: entity.name.clone(),
+                object: member.clone(),
+                path: entity.path.clone(),
+                position: entity.position,
+                graphql: entity.graphql.clone(),
+            })
         });
+    // The interfaces implementing an interface aren't possible types.
+    let implementors = implementations(graph)
+        .into_iter()
+        .filter(|implementation| objects.contains(implementation.implementor.as_str()))
+        .map(|implementation| PossibleType {
+            abstract_type: implementation.interface,
+            object: implementation.implementor,
+            path: implementation.path,
+            position: implementation.position,
+            graphql: implementation.graphql,
+        });
+    let mut possible_types = members.chain(implementors).collect::<Vec<PossibleType>>();
+    possible_types.sort_by(|a, b| {
+        (&a.abstract_type, &a.object, &a.path, a.position).cmp(&(
+            &b.abstract_type,
+            &b.object,
+            &b.path,
+            b.position,
+        ))
+    });
 
-        populate_graph_from_ast(
-            shared_data_for_populate.dependencies,
-            shared_data_for_populate.files,
-            filters,
-            shared_data_for_populate.graph,
-            shared_data_for_populate.missing_definitions,
-        )
-        .await
-        .unwrap();
+    possible_types
+}
 
-        shared_data
-    }
+/// Return the possible types of an interface or a union, through their
+/// definition or an extension.
+pub fn possible_types(abstract_type: &str, graph: &SchemaGraph) -> Vec<PossibleType> {
+    all_possible_types(graph)
+        .into_iter()
+        .filter(|possible_type| possible_type.abstract_type == abstract_type)
+        .collect()
+}
 
-    #[async_std::test]
-    async fn check_dependencies_and_graph() {
-        let house_contents = "type House { price: Int! rooms: Int! @test owner: Owner! }";
-        let house_dependencies = vec!["@test", "Int", "Owner"];
-        let house_name = "House";
-        let house_path = "some_path/House.gql";
+/// Return the interfaces and unions an object is a possible type of.
+pub fn member_of(object: &str, graph: &SchemaGraph) -> Vec<PossibleType> {
+    all_possible_types(graph)
+        .into_iter()
+        .filter(|possible_type| possible_type.object == object)
+        .collect()
+}
+
+/// Scan the graphql-import style comments of a source, e.g.
+/// # import Foo, Bar from "./foo.graphql", ignoring the malformed ones.
+pub fn parse_imports(contents: &str) -> Vec<Import> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let rest = line.trim_start().strip_prefix('#')?.trim_start();
+            let rest = rest.strip_prefix("import")?;
+
+            if !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+
+            let (names, from) = rest.rsplit_once(" from ")?;
+            let from = from.trim();
+            let from = from
+                .strip_prefix('"')
+                .and_then(|from| from.strip_suffix('"'))
+                .or_else(|| {
+                    from.strip_prefix('\'')
+                        .and_then(|from| from.strip_suffix('\''))
+                })?;
+            let names = names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect::<Vec<String>>();
+
+            if names.is_empty() || from.is_empty() {
+                return None;
+            }
+
+            Some(Import {
+                names,
+                from: from.to_owned(),
+                line: index + 1,
+            })
+        })
+        .collect()
+}
+
+/// Lexically normalize a path, i.e. drop the . and resolve the .. components.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(std::path::Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component.as_os_str()),
+        }
+    }
+
+    normalized
+}
+
+/// Check the graphql-import style comments of the files: the imported file
+/// has to be analyzed and define the imported names. A Foo.bar name only
+/// requires Foo to be defined. The violations are sorted by path and line.
+pub fn import_violations(
+    files: &HashMap<PathBuf, String>,
+    graph: &SchemaGraph,
+) -> Vec<ImportViolation> {
+    let mut names_by_path: HashMap<PathBuf, HashSet<&str>> = HashMap::new();
+
+    for node in graph.node_weights() {
+        names_by_path
+            .entry(normalize_path(&node.entity.path))
+            .or_default()
+            .insert(&node.entity.name);
+    }
+
+    let analyzed = files
+        .keys()
+        .map(|path| normalize_path(path))
+        .collect::<HashSet<PathBuf>>();
+    let mut paths = files.keys().collect::<Vec<&PathBuf>>();
+    let mut violations = vec![];
+
+    paths.sort();
+
+    for path in paths {
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+
+        for import in parse_imports(&files[path]) {
+            let mut report = |message: String| {
+                violations.push(ImportViolation {
+                    path: path.clone(),
+                    line: import.line,
+                    message,
+                })
+            };
+            let imported = normalize_path(&parent.join(&import.from));
+
+            if !analyzed.contains(&imported) {
+                report(format!("{} is not an analyzed file", import.from));
+
+                continue;
+            }
+
+            let defined = names_by_path.get(&imported);
+
+            for name in import.names.iter().filter(|name| *name != "*") {
+                let type_name = name.split('.').next().unwrap_or(name);
+
+                if !defined.map_or(false, |defined| defined.contains(type_name)) {
+                    report(format!("{} is not defined in {}", name, import.from));
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Return the fields referencing a defined type which is unreachable from the
+/// root operation types, in the nodes order. The fields are read back from
+/// the raw SDL of the entities.
+pub fn dead_references(graph: &SchemaGraph, node_indices: &NodeIndices) -> Vec<DeadReference> {
+    let reachable = reachable_names(graph);
+    let mut dead_references = vec![];
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+        let fields = match parse_definition(entity) {
+            Some(schema::Definition::TypeDefinition(schema::TypeDefinition::InputObject(
+                input_object,
+            ))) => input_object
+                .fields
+                .into_iter()
+                .map(|field| (field.name, field.value_type))
+                .collect::<Vec<_>>(),
+            Some(schema::Definition::TypeExtension(schema::TypeExtension::InputObject(
+                input_object,
+            ))) => input_object
+                .fields
+                .into_iter()
+                .map(|field| (field.name, field.value_type))
+                .collect(),
+            Some(definition) => get_fields(definition)
+                .into_iter()
+                .map(|field| (field.name, field.field_type))
+                .collect(),
+            None => continue,
+        };
+
+        for (field, field_type) in fields {
+            let target = get_named_type(&field_type);
+
+            if node_indices.contains_key(target) && !reachable.contains(target) {
+                dead_references.push(DeadReference {
+                    entity: entity.clone(),
+                    field: format!("{}.{}", entity.name, field),
+                    target: target.to_owned(),
+                });
+            }
+        }
+    }
+
+    dead_references
+}
+
+/// Return the usages of the custom scalars, or of the named one, sorted by
+/// scalar. The fields, arguments and input fields referencing them are the
+/// references recorded along the entities, lists and non-null types included.
+pub fn scalar_usage(name: Option<&str>, graph: &SchemaGraph) -> Vec<ScalarUsage> {
+    let mut usages = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        if entity.graphql == GraphQL::TypeDefinition(GraphQLType::Scalar)
+            && !entity.is_built_in()
+            && name.map_or(true, |name| name == entity.name)
+        {
+            usages
+                .entry(entity.name.clone())
+                .or_insert_with(|| ScalarUsage {
+                    scalar: entity.name.clone(),
+                    path: entity.path.clone(),
+                    position: entity.position,
+                    count: 0,
+                    types: 0,
+                    references: vec![],
+                });
+        }
+    }
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        for reference in &entity.references {
+            if let Some(usage) = usages.get_mut(reference.named_type()) {
+                usage.references.push(ScalarReference {
+                    coordinate: reference.coordinate(),
+                    type_name: entity.name.clone(),
+                    value_type: reference.value_type.clone(),
+                    path: entity.path.clone(),
+                    position: entity.position,
+                });
+            }
+        }
+    }
+
+    usages
+        .into_values()
+        .map(|mut usage| {
+            usage.references.sort_by(|a, b| {
+                (&a.coordinate, &a.path, a.position).cmp(&(&b.coordinate, &b.path, b.position))
+            });
+            usage.count = usage.references.len();
+            usage.types = usage
+                .references
+                .iter()
+                .map(|reference| &reference.type_name)
+                .collect::<BTreeSet<&String>>()
+                .len();
+
+            usage
+        })
+        .collect()
+}
+
+/// Return the input fields leading from the input objects nesting the given
+/// one to it, the shortest path being kept, extensions included.
+fn nesting_paths(input: &str, graph: &SchemaGraph) -> BTreeMap<String, Vec<String>> {
+    let mut nested_in: BTreeMap<&str, BTreeSet<(&str, &str)>> = BTreeMap::new();
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        if !matches!(
+            entity.graphql,
+            GraphQL::TypeDefinition(GraphQLType::InputObject)
+                | GraphQL::TypeExtension(GraphQLType::InputObject)
+        ) {
+            continue;
+        }
+
+        for reference in &entity.references {
+            nested_in
+                .entry(reference.named_type())
+                .or_default()
+                .insert((&entity.name, &reference.field));
+        }
+    }
+
+    let mut paths = BTreeMap::from([(input.to_owned(), vec![])]);
+    let mut queue = VecDeque::from([input.to_owned()]);
+
+    while let Some(nested) = queue.pop_front() {
+        let path = paths[&nested].clone();
+
+        for (parent, field) in nested_in.get(nested.as_str()).into_iter().flatten() {
+            if !paths.contains_key(*parent) {
+                let mut parent_path = vec![field.to_string()];
+
+                parent_path.extend(path.iter().cloned());
+                paths.insert(parent.to_string(), parent_path);
+                queue.push_back(parent.to_string());
+            }
+        }
+    }
+
+    paths
+}
+
+/// Return the arguments of the fields accepting an input object, directly or
+/// nested inside other input objects, sorted by field and argument.
+pub fn input_usage(input: &str, graph: &SchemaGraph) -> Vec<InputUsage> {
+    let is_input_object = graph.node_weights().any(|node| {
+        node.entity.name == input
+            && node.entity.graphql == GraphQL::TypeDefinition(GraphQLType::InputObject)
+    });
+
+    if !is_input_object {
+        return vec![];
+    }
+
+    let paths = nesting_paths(input, graph);
+    let mut usages = vec![];
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        // Directive definition arguments are left out, only the ones of the
+        // fields being kept.
+        if entity.graphql == GraphQL::Directive {
+            continue;
+        }
+
+        for reference in &entity.references {
+            let argument = match &reference.argument {
+                Some(argument) => argument,
+                None => continue,
+            };
+
+            if let Some(via) = paths.get(reference.named_type()) {
+                usages.push(InputUsage {
+                    field: reference.field.clone(),
+                    argument: argument.clone(),
+                    value_type: reference.value_type.clone(),
+                    path: entity.path.clone(),
+                    position: entity.position,
+                    indirect: !via.is_empty(),
+                    via: via.clone(),
+                });
+            }
+        }
+    }
+
+    usages.sort_by(|a, b| (&a.field, &a.argument).cmp(&(&b.field, &b.argument)));
+
+    usages
+}
+
+/// Return the documentation coverage of the type definitions and of the
+/// field nodes, the built-in scalars excluded.
+pub fn docs_coverage(graph: &SchemaGraph) -> DocsCoverage {
+    let mut coverage = DocsCoverage {
+        documented: 0,
+        undocumented: vec![],
+    };
+
+    for node in graph.node_weights() {
+        let entity = &node.entity;
+
+        if entity.is_built_in()
+            || !matches!(entity.graphql, GraphQL::TypeDefinition(_) | GraphQL::Field)
+        {
+            continue;
+        }
+
+        match entity.description {
+            Some(_) => coverage.documented += 1,
+            None => coverage.undocumented.push(entity.clone()),
+        }
+    }
+
+    coverage
+}
+
+/// Build the graph of a subgraph from its files, with the federation
+/// awareness enabled.
+pub async fn build_subgraph(
+    subgraph: &Subgraph,
+    options: &AnalysisOptions,
+) -> Result<SubgraphSchema> {
+    let state = State::with_options(options.clone().federation(true));
+    let shared_data = state.shared;
+
+    collect_files(subgraph.path.clone(), &shared_data).await?;
+
+    build_graph(&shared_data).await?;
+
+    let graph = std::mem::take(&mut *shared_data.graph.lock().await);
+    let missing_definitions = std::mem::take(&mut *shared_data.missing_definitions.lock().await);
+
+    Ok(SubgraphSchema {
+        name: subgraph.name.clone(),
+        graph,
+        missing_definitions,
+    })
+}
+
+/// Compare the subgraphs and return the issues preventing their composition:
+/// the object types defined in several subgraphs without @key nor @shareable,
+/// the root operation types excepted, then the definitions referenced by a
+/// subgraph and defined in none.
+pub fn composition_issues(subgraphs: &[SubgraphSchema]) -> Vec<CompositionIssue> {
+    let mut definitions: BTreeMap<&str, Vec<(String, Entity)>> = BTreeMap::new();
+    let mut defined = HashSet::new();
+
+    for subgraph in subgraphs {
+        for node in subgraph.graph.node_weights() {
+            defined.insert(&*node.id);
+
+            if node.entity.graphql == GraphQL::TypeDefinition(GraphQLType::Object)
+                && !matches!(&*node.id, "Query" | "Mutation" | "Subscription")
+            {
+                definitions
+                    .entry(&node.id)
+                    .or_default()
+                    .push((subgraph.name.clone(), node.entity.clone()));
+            }
+        }
+    }
+
+    let is_shared = |entity: &Entity| {
+        entity
+            .dependencies
+            .iter()
+            .any(|dependency| matches!(&**dependency, "@key" | "@shareable"))
+    };
+    let conflicts = definitions
+        .into_iter()
+        .filter(|(_, entities)| {
+            entities.len() > 1 && !entities.iter().all(|(_, entity)| is_shared(entity))
+        })
+        .map(|(name, entities)| CompositionIssue::Conflict(name.to_owned(), entities));
+    let mut unsatisfied = vec![];
+
+    for subgraph in subgraphs {
+        let mut missing_definitions = subgraph.missing_definitions.iter().collect::<Vec<_>>();
+
+        // Follow the nodes order.
+        missing_definitions.sort_by_key(|(node_index, _)| **node_index);
+
+        for (node_index, names) in missing_definitions {
+            for name in names.iter().filter(|name| !defined.contains(&***name)) {
+                unsatisfied.push(CompositionIssue::Unsatisfied(
+                    subgraph.name.clone(),
+                    Box::new(subgraph.graph[*node_index].entity.clone()),
+                    name.clone(),
+                ));
+            }
+        }
+    }
+
+    conflicts.chain(unsatisfied).collect()
+}
+
+/// Group the definitions by source file, in the paths order, and count the
+/// dependencies of each file defined in another one.
+pub fn summarize_by_file(graph: &SchemaGraph) -> Vec<FileSummary> {
+    let entities = graph
+        .node_weights()
+        .map(|node| (&*node.id, &node.entity))
+        .collect::<HashMap<&str, &Entity>>();
+    let mut summaries: BTreeMap<&PathBuf, FileSummary> = BTreeMap::new();
+
+    for node in graph
+        .node_weights()
+        .filter(|node| !node.entity.is_built_in())
+    {
+        let entity = &node.entity;
+        let summary = summaries
+            .entry(&entity.path)
+            .or_insert_with(|| FileSummary {
+                path: entity.path.clone(),
+                definitions: vec![],
+                cross_file_dependencies: 0,
+            });
+
+        if entity.graphql != GraphQL::Field {
+            summary.definitions.push(node.id.to_string());
+        }
+
+        summary.cross_file_dependencies += entity
+            .dependencies
+            .iter()
+            .filter_map(|dependency| entities.get(&**dependency))
+            .filter(|dependency| !dependency.is_built_in() && dependency.path != entity.path)
+            .count();
+    }
+
+    summaries.into_values().collect()
+}
+
+/// Derive the graph of the files from the one of the entities, the edges
+/// between entities of different files being aggregated into weighted edges
+/// between their files. The built-in entities and the edges within a file are
+/// left out, the files are sorted by path.
+pub fn file_graph(graph: &SchemaGraph) -> FileGraph {
+    let is_kept = |index: NodeIndex| !graph[index].entity.is_built_in();
+    let paths = graph
+        .node_indices()
+        .filter(|index| is_kept(*index))
+        .map(|index| graph[index].entity.path.to_string_lossy().into_owned())
+        .collect::<BTreeSet<String>>();
+    let mut file_graph = FileGraph::new();
+    let indices = paths
+        .into_iter()
+        .map(|path| (path.clone(), file_graph.add_node(path)))
+        .collect::<HashMap<String, NodeIndex>>();
+    let index_of = |index: NodeIndex| indices[&*graph[index].entity.path.to_string_lossy()];
+    let mut weights: BTreeMap<(NodeIndex, NodeIndex), usize> = BTreeMap::new();
+
+    for edge in graph
+        .edge_references()
+        .filter(|edge| is_kept(edge.source()) && is_kept(edge.target()))
+    {
+        let (source, target) = (index_of(edge.source()), index_of(edge.target()));
+
+        if source != target {
+            *weights.entry((source, target)).or_default() += 1;
+        }
+    }
+
+    for ((source, target), weight) in weights {
+        file_graph.add_edge(source, target, weight);
+    }
+
+    file_graph
+}
+
+/// Serializable representation of the file graph.
+pub fn file_graph_snapshot(file_graph: &FileGraph) -> FileGraphSnapshot {
+    FileGraphSnapshot {
+        nodes: file_graph.node_weights().cloned().collect(),
+        edges: file_graph
+            .edge_references()
+            .map(|edge| FileEdge {
+                source: file_graph[edge.source()].clone(),
+                target: file_graph[edge.target()].clone(),
+                weight: *edge.weight(),
+            })
+            .collect(),
+    }
+}
+
+/// Render the file graph as a graphviz .dot format, the weights as labels of
+/// the edges.
+pub fn render_file_dot(file_graph: &FileGraph) -> String {
+    format!("{}", Dot::new(file_graph))
+}
+
+/// Return the owner of a path, i.e. the first components of its directory
+/// relative to the root, "." for the files of the root.
+fn owner_of(path: &Path, root: &Path, depth: usize) -> String {
+    let directory = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let owner = directory
+        .components()
+        .take(depth)
+        .collect::<std::path::PathBuf>();
+
+    match owner.as_os_str().is_empty() {
+        true => String::from("."),
+        false => owner.to_string_lossy().into_owned(),
+    }
+}
+
+/// Group the definitions by owner, the directories made of the first depth
+/// components under the root, with the dependencies between the owners. The
+/// built-in entities are left out, the owners are sorted.
+pub fn ownership(graph: &SchemaGraph, root: &Path, depth: usize) -> Vec<Ownership> {
+    let is_kept = |index: NodeIndex| !graph[index].entity.is_built_in();
+    let owner = |index: NodeIndex| owner_of(&graph[index].entity.path, root, depth);
+    let mut owners: BTreeMap<String, Ownership> = BTreeMap::new();
+
+    for index in graph.node_indices().filter(|index| is_kept(*index)) {
+        let entity = &graph[index].entity;
+        let ownership = owners.entry(owner(index)).or_default();
+
+        if entity.graphql != GraphQL::Field && !entity.graphql.is_extension() {
+            ownership.types.insert(entity.name.clone());
+        }
+    }
+
+    for edge in graph
+        .edge_references()
+        .filter(|edge| is_kept(edge.source()) && is_kept(edge.target()))
+    {
+        // Edges go from the dependency to the dependent.
+        let (dependency, dependent) = (edge.source(), edge.target());
+        let (dependency_owner, dependent_owner) = (owner(dependency), owner(dependent));
+
+        if dependency_owner == dependent_owner {
+            continue;
+        }
+
+        let name = &graph[dependency].entity.name;
+
+        owners
+            .entry(dependent_owner.clone())
+            .or_default()
+            .dependencies
+            .entry(dependency_owner.clone())
+            .or_default()
+            .insert(name.clone());
+        owners
+            .entry(dependency_owner)
+            .or_default()
+            .dependents
+            .entry(dependent_owner)
+            .or_default()
+            .insert(name.clone());
+    }
+
+    owners
+        .into_iter()
+        .map(|(owner, ownership)| Ownership { owner, ..ownership })
+        .collect()
+}
+
+/// Walk of a dependency tree, cargo tree style.
+struct TreeWalk<'a> {
+    graph: &'a SchemaGraph,
+    /// Incoming for the dependencies, outgoing for the dependents.
+    direction: Direction,
+    /// Nodes already expanded.
+    visited: HashSet<NodeIndex>,
+    /// Nodes from the root to the current one.
+    ancestors: Vec<NodeIndex>,
+    tree: String,
+}
+
+impl TreeWalk<'_> {
+    /// Recursively append the neighbors of a node to the tree.
+    fn walk(&mut self, index: NodeIndex, prefix: &str, depth: Option<usize>) {
+        if depth == Some(0) {
+            return;
+        }
+
+        // Sort the neighbors by name to get a stable output.
+        let mut neighbors = self
+            .graph
+            .neighbors_directed(index, self.direction)
+            .collect::<Vec<NodeIndex>>();
+        neighbors.sort_by_key(|index| self.graph[*index].id.to_lowercase());
+
+        self.ancestors.push(index);
+
+        for (position, neighbor) in neighbors.iter().enumerate() {
+            let is_last = position == neighbors.len() - 1;
+            let entity = &self.graph[*neighbor].entity;
+            // Nodes repeating on their own ancestry are cycles, the other
+            // already expanded nodes are marked and not walked again. The
+            // nodes cut by the depth limit aren't expanded, thus not marked.
+            let is_cycle = self.ancestors.contains(neighbor);
+            let is_visited = match depth {
+                Some(1) => self.visited.contains(neighbor),
+                _ => !self.visited.insert(*neighbor),
+            };
+
+            self.tree.push_str(&format!(
+                "{}{} {} ({:?}){}\n",
+                prefix,
+                if is_last { "└──" } else { "├──" },
+                entity.name,
+                entity.graphql,
+                match (is_cycle, is_visited) {
+                    (true, _) => " (cycle)",
+                    (false, true) => " (*)",
+                    (false, false) => "",
+                }
+            ));
+
+            if !is_visited {
+                self.walk(
+                    *neighbor,
+                    &format!("{}{}", prefix, if is_last { "    " } else { "│   " }),
+                    depth.map(|depth| depth - 1),
+                );
+            }
+        }
+
+        self.ancestors.pop();
+    }
+}
+
+/// Return the dependencies of a node as an indented tree, with an optional
+/// depth limit, or its dependents with the outgoing direction.
+pub fn dependency_tree(
+    node: &str,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+    depth: Option<usize>,
+    direction: Direction,
+) -> Option<String> {
+    let index = find_index(node, node_indices)?;
+    let entity = &graph[index].entity;
+    let mut walk = TreeWalk {
+        graph,
+        direction,
+        visited: HashSet::from([index]),
+        ancestors: vec![],
+        tree: format!("{} ({:?})\n", entity.name, entity.graphql),
+    };
+
+    walk.walk(index, "", depth);
+
+    Some(walk.tree)
+}
+
+/// Return the depth of a strongly connected component, i.e. the number of
+/// levels of types on its longest path of dependencies, memoized.
+fn component_depth(
+    component: usize,
+    components: &[Vec<NodeIndex>],
+    component_of: &[usize],
+    graph: &SchemaGraph,
+    depths: &mut Vec<Option<usize>>,
+) -> usize {
+    if let Some(depth) = depths[component] {
+        return depth;
+    }
+
+    // The types of a cycle count as a single level, the extensions and the
+    // fields as none.
+    let level = components[component]
+        .iter()
+        .any(|index| matches!(graph[*index].entity.graphql, GraphQL::TypeDefinition(_)))
+        as usize;
+    let dependencies = components[component]
+        .iter()
+        .flat_map(|index| graph.neighbors_directed(*index, Direction::Incoming))
+        .filter(|index| graph[*index].entity.graphql != GraphQL::Directive)
+        .map(|index| component_of[index.index()])
+        .filter(|dependency| *dependency != component)
+        .collect::<HashSet<usize>>();
+    let depth = level
+        + dependencies
+            .into_iter()
+            .map(|dependency| component_depth(dependency, components, component_of, graph, depths))
+            .max()
+            .unwrap_or(0);
+
+    depths[component] = Some(depth);
+
+    depth
+}
+
+/// Return the depth of each root, i.e. the number of levels of types on the
+/// longest path of dependencies from it, the types of a cycle counting as a
+/// single level and the directives not being followed. The roots default to
+/// the root operation types, the missing ones being skipped.
+pub fn depth_metrics(
+    roots: &[String],
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+) -> Vec<(String, usize)> {
+    let components = tarjan_scc(graph);
+    let mut component_of = vec![0; graph.node_count()];
+    let mut depths = vec![None; components.len()];
+
+    for (component, indices) in components.iter().enumerate() {
+        for index in indices {
+            component_of[index.index()] = component;
+        }
+    }
+
+    let roots = match roots.is_empty() {
+        true => {
+            let (query, mutation, subscription) = root_types(graph);
+
+            vec![query, mutation, subscription]
+        }
+        false => roots.to_vec(),
+    };
+
+    roots
+        .into_iter()
+        .filter_map(|root| {
+            let index = find_index(&root, node_indices)?;
+            let component = component_of[index.index()];
+            // The level of the root itself isn't counted.
+            let depth = component_depth(component, &components, &component_of, graph, &mut depths)
+                .saturating_sub(1);
+
+            Some((root, depth))
+        })
+        .collect()
+}
+
+/// Build a copy of the graph where each extension node is folded into its
+/// base node, edges keep their direction and extensions without a base are
+/// left as is.
+pub fn collapse_extensions(graph: &SchemaGraph) -> SchemaGraph {
+    let mut collapsed = SchemaGraph::new();
+    let mut indices: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    let mut bases: HashMap<&str, NodeIndex> = HashMap::new();
+
+    // Add the non-extension nodes first.
+    for index in graph.node_indices() {
+        let node = &graph[index];
+
+        if !node.entity.graphql.is_extension() {
+            let new_index = collapsed.add_node(Node::new(node.entity.clone(), node.id.clone()));
+
+            indices.insert(index, new_index);
+            bases.entry(&node.id).or_insert(new_index);
+        }
+    }
+
+    // Fold the extensions and merge their dependencies.
+    for index in graph.node_indices() {
+        let node = &graph[index];
+
+        let extends = match node.entity.extends {
+            Some(ref extends) => extends.as_str(),
+            None => continue,
+        };
+        let new_index = match bases.get(extends) {
+            Some(base_index) => {
+                let base = &mut collapsed[*base_index].entity;
+
+                base.dependencies.extend(
+                    node.entity
+                        .dependencies
+                        .iter()
+                        .filter(|dependency| ***dependency != *node.entity.name)
+                        .cloned(),
+                );
+                base.dependencies =
+                    sort_and_dedupe_dependencies(std::mem::take(&mut base.dependencies));
+
+                *base_index
+            }
+            None => collapsed.add_node(Node::new(node.entity.clone(), node.id.clone())),
+        };
+
+        indices.insert(index, new_index);
+    }
+
+    for edge in graph.edge_references() {
+        let source = indices[&edge.source()];
+        let target = indices[&edge.target()];
+
+        // Drop the edges between an extension and its base.
+        if source != target {
+            collapsed.update_edge(source, target, edge.weight().clone());
+        }
+    }
+
+    collapsed
+}
+
+/// Append the members of an extension, returning the names of the ones
+/// already defined.
+fn merge_members<T>(
+    members: &mut Vec<T>,
+    extension: Vec<T>,
+    name: impl Fn(&T) -> &str,
+) -> Vec<String> {
+    let mut duplicates = vec![];
+
+    for member in extension {
+        if members
+            .iter()
+            .any(|existing| name(existing) == name(&member))
+        {
+            duplicates.push(name(&member).to_owned());
+        } else {
+            members.push(member);
+        }
+    }
+
+    duplicates
+}
+
+/// Merge an extension into its base definition, returning the coordinates of
+/// the members already defined.
+fn merge_definition<'a>(
+    definition: &mut schema::TypeDefinition<'a, String>,
+    extension: schema::TypeExtension<'a, String>,
+) -> Vec<String> {
+    use schema::{TypeDefinition, TypeExtension};
+
+    let (name, duplicates) = match (definition, extension) {
+        (TypeDefinition::Object(object), TypeExtension::Object(extension)) => {
+            merge_members(
+                &mut object.implements_interfaces,
+                extension.implements_interfaces,
+                String::as_str,
+            );
+            object.directives.extend(extension.directives);
+
+            (
+                &object.name,
+                merge_members(&mut object.fields, extension.fields, |field| &field.name),
+            )
+        }
+        (TypeDefinition::Interface(interface), TypeExtension::Interface(extension)) => {
+            merge_members(
+                &mut interface.implements_interfaces,
+                extension.implements_interfaces,
+                String::as_str,
+            );
+            interface.directives.extend(extension.directives);
+
+            (
+                &interface.name,
+                merge_members(&mut interface.fields, extension.fields, |field| &field.name),
+            )
+        }
+        (TypeDefinition::Union(union), TypeExtension::Union(extension)) => {
+            union.directives.extend(extension.directives);
+
+            (
+                &union.name,
+                merge_members(&mut union.types, extension.types, String::as_str),
+            )
+        }
+        (TypeDefinition::Enum(enum_type), TypeExtension::Enum(extension)) => {
+            enum_type.directives.extend(extension.directives);
+
+            (
+                &enum_type.name,
+                merge_members(&mut enum_type.values, extension.values, |value| &value.name),
+            )
+        }
+        (TypeDefinition::InputObject(input_object), TypeExtension::InputObject(extension)) => {
+            input_object.directives.extend(extension.directives);
+
+            (
+                &input_object.name,
+                merge_members(&mut input_object.fields, extension.fields, |field| {
+                    &field.name
+                }),
+            )
+        }
+        (TypeDefinition::Scalar(scalar), TypeExtension::Scalar(extension)) => {
+            scalar.directives.extend(extension.directives);
+
+            (&scalar.name, vec![])
+        }
+        _ => return vec![],
+    };
+
+    duplicates
+        .into_iter()
+        .map(|duplicate| format!("{}.{}", name, duplicate))
+        .collect()
+}
+
+/// Build a copy of the graph where the extensions are merged into their base
+/// definition, see collapse_extensions, the raw representation of the base
+/// being synthesized with all the members. The members already defined are
+/// reported and skipped. Relies on the raw SDL of the entities.
+pub fn merge_extensions(graph: &SchemaGraph) -> (SchemaGraph, Vec<MergeConflict>) {
+    let mut merged = collapse_extensions(graph);
+    let merged_indices = index_nodes(&merged);
+    let mut conflicts = vec![];
+
+    for index in graph.node_indices() {
+        let extension = &graph[index].entity;
+
+        let extends = match extension.extends {
+            Some(ref extends) => extends,
+            None => continue,
+        };
+        let base = match find_index(extends, &merged_indices) {
+            Some(base_index) => &mut merged[base_index].entity,
+            None => continue,
+        };
+        let (mut definition, extension_definition) =
+            match (parse_definition(base), parse_definition(extension)) {
+                (
+                    Some(schema::Definition::TypeDefinition(definition)),
+                    Some(schema::Definition::TypeExtension(extension_definition)),
+                ) => (definition, extension_definition),
+                _ => continue,
+            };
+
+        conflicts.extend(
+            merge_definition(&mut definition, extension_definition)
+                .into_iter()
+                .map(|coordinate| MergeConflict {
+                    path: extension.path.clone(),
+                    position: extension.position,
+                    coordinate,
+                }),
+        );
+
+        let empty = definition.is_empty();
+        let raw = schema::Definition::TypeDefinition(definition).to_string();
+
+        base.empty = empty;
+        base.raw = raw;
+    }
+
+    (merged, conflicts)
+}
+
+/// Compile a glob, e.g. User*, to a regex matching a whole name.
+pub fn glob_to_regex(glob: &str) -> Result<Regex> {
+    let pattern = regex::escape(glob).replace(r"\*", ".*").replace(r"\?", ".");
+
+    Regex::new(&format!("^{}$", pattern)).with_context(|| format!("Invalid glob {}", glob))
+}
+
+/// Return the subgraph induced by the nodes whose name matches one of the
+/// globs and their transitive dependencies, extensions included.
+pub fn select_nodes(graph: &SchemaGraph, globs: &[Regex]) -> SchemaGraph {
+    let mut nodes_by_name: HashMap<&str, Vec<NodeIndex>> = HashMap::new();
+
+    for index in graph.node_indices() {
+        nodes_by_name
+            .entry(&graph[index].entity.name)
+            .or_default()
+            .push(index);
+    }
+
+    let mut names = nodes_by_name
+        .keys()
+        .filter(|name| globs.iter().any(|glob| glob.is_match(name)))
+        .copied()
+        .collect::<Vec<&str>>();
+    let mut visited = HashSet::new();
+    let mut selected = HashSet::new();
+
+    while let Some(name) = names.pop() {
+        if !visited.insert(name) {
+            continue;
+        }
+
+        for index in nodes_by_name.get(name).into_iter().flatten() {
+            selected.insert(*index);
+            names.extend(
+                graph[*index]
+                    .entity
+                    .dependencies
+                    .iter()
+                    .filter_map(|dependency| nodes_by_name.get_key_value(&**dependency))
+                    .map(|(name, _)| *name),
+            );
+        }
+    }
+
+    induced_subgraph(graph, &selected)
+}
+
+/// Return the indices of the nodes reachable from a node in a direction, the
+/// node included.
+fn reachable_indices(
+    index: NodeIndex,
+    graph: &SchemaGraph,
+    direction: Direction,
+) -> HashSet<NodeIndex> {
+    let mut reachable = HashSet::new();
+    let mut stack = vec![index];
+
+    while let Some(index) = stack.pop() {
+        if reachable.insert(index) {
+            stack.extend(graph.neighbors_directed(index, direction));
+        }
+    }
+
+    reachable
+}
+
+/// Return the subgraph induced by the nodes lying on a path from a node to
+/// another one, following the dependencies, i.e. the transitive dependencies
+/// of the first which depend on the second, both included.
+pub fn subgraph_between(
+    from: &str,
+    to: &str,
+    graph: &SchemaGraph,
+    node_indices: &NodeIndices,
+) -> SchemaGraph {
+    let selected = match (find_index(from, node_indices), find_index(to, node_indices)) {
+        (Some(from), Some(to)) => {
+            let dependencies = reachable_indices(from, graph, Direction::Incoming);
+
+            reachable_indices(to, graph, Direction::Outgoing)
+                .into_iter()
+                .filter(|index| dependencies.contains(index))
+                .collect()
+        }
+        _ => HashSet::new(),
+    };
+
+    induced_subgraph(graph, &selected)
+}
+
+/// Return the subgraph induced by some nodes, with the edges between them.
+fn induced_subgraph(graph: &SchemaGraph, selected: &HashSet<NodeIndex>) -> SchemaGraph {
+    let mut subgraph = SchemaGraph::new();
+    let mut indices: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+
+    // Follow the nodes order to keep the rendering deterministic.
+    for index in graph
+        .node_indices()
+        .filter(|index| selected.contains(index))
+    {
+        let node = &graph[index];
+
+        indices.insert(
+            index,
+            subgraph.add_node(Node::new(node.entity.clone(), node.id.clone())),
+        );
+    }
+
+    for edge in graph.edge_references() {
+        if let (Some(source), Some(target)) =
+            (indices.get(&edge.source()), indices.get(&edge.target()))
+        {
+            subgraph.update_edge(*source, *target, edge.weight().clone());
+        }
+    }
+
+    subgraph
+}
+
+/// Build a serializable snapshot of the graph, edges referencing nodes by id.
+pub fn graph_snapshot(graph: &SchemaGraph) -> GraphSnapshot {
+    GraphSnapshot {
+        nodes: graph
+            .node_weights()
+            .map(|node| SnapshotNode {
+                dependencies: node
+                    .entity
+                    .dependencies
+                    .iter()
+                    .map(|dependency| dependency.to_string())
+                    .collect(),
+                description: node.entity.description.clone(),
+                graphql: node.entity.graphql.clone(),
+                id: node.id.to_string(),
+                name: node.entity.name.clone(),
+                path: node.entity.path.to_string_lossy().into_owned(),
+            })
+            .collect::<Vec<SnapshotNode>>(),
+        edges: graph
+            .edge_references()
+            .map(|edge| SnapshotEdge {
+                source: graph[edge.source()].id.to_string(),
+                target: graph[edge.target()].id.to_string(),
+            })
+            .collect::<Vec<SnapshotEdge>>(),
+    }
+}
+
+/// Escape a label of the graphviz .dot format, the lines being left-aligned.
+fn escape_dot(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+
+    for character in label.chars() {
+        match character {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(character);
+            }
+            '\n' => escaped.push_str("\\l"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Render the graph as graphviz .dot format.
+pub fn render_dot(graph: &SchemaGraph, options: &[dot::Config]) -> String {
+    render_dot_wrapped(graph, options, DEFAULT_WRAP_WIDTH)
+}
+
+/// Render the graph as graphviz .dot format, the dependencies of the labels
+/// being wrapped at a width, 0 disabling the wrapping.
+pub fn render_dot_wrapped(graph: &SchemaGraph, options: &[dot::Config], width: usize) -> String {
+    // The labels are rendered by the node attributes to apply the width.
+    let has_label = !options.contains(&dot::Config::NodeNoLabel)
+        && !options.contains(&dot::Config::NodeIndexLabel);
+    // dot::Config is neither Copy nor Clone.
+    let mut options = options
+        .iter()
+        .filter_map(|option| match option {
+            dot::Config::NodeIndexLabel => Some(dot::Config::NodeIndexLabel),
+            dot::Config::EdgeIndexLabel => Some(dot::Config::EdgeIndexLabel),
+            dot::Config::EdgeNoLabel => Some(dot::Config::EdgeNoLabel),
+            dot::Config::NodeNoLabel => Some(dot::Config::NodeNoLabel),
+            dot::Config::GraphContentOnly => Some(dot::Config::GraphContentOnly),
+            _ => None,
+        })
+        .collect::<Vec<dot::Config>>();
+
+    if has_label {
+        options.push(dot::Config::NodeNoLabel);
+    }
+
+    let node_attributes = |_, (_, node): (NodeIndex, &Node)| {
+        let label = match has_label {
+            true => format!("label = \"{}\" ", escape_dot(&node.entity.label(width))),
+            false => String::new(),
+        };
+
+        // Draw the synthetic built-in scalar nodes dashed.
+        match node.entity.is_built_in() {
+            true => format!("{}style = dashed ", label),
+            false => label,
+        }
+    };
+
+    format!(
+        "{:?}",
+        Dot::with_attr_getters(graph, &options, &|_, _| String::new(), &node_attributes)
+    )
+}
+
+/// Group the nodes in layers by dependency depth, the nodes without
+/// dependencies being at the depth 0. None for a cyclic graph.
+fn rank_layers(graph: &SchemaGraph) -> Option<Vec<Vec<NodeIndex>>> {
+    let order = toposort(graph, None).ok()?;
+    let mut depths: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut layers: Vec<Vec<NodeIndex>> = Vec::new();
+
+    // The dependencies come first in the topological order.
+    for index in order {
+        let depth = graph
+            .neighbors_directed(index, Direction::Incoming)
+            .map(|dependency| depths[&dependency] + 1)
+            .max()
+            .unwrap_or(0);
+
+        depths.insert(index, depth);
+
+        if layers.len() <= depth {
+            layers.resize(depth + 1, Vec::new());
+        }
+
+        layers[depth].push(index);
+    }
+
+    for layer in layers.iter_mut() {
+        layer.sort();
+    }
+
+    Some(layers)
+}
+
+/// Render the graph as graphviz .dot format with render_dot_wrapped, the
+/// nodes of a same dependency depth sharing a rank. The edges going from the
+/// dependencies to their dependents, the ranks are laid out from the bottom
+/// for the roots to be at the top. A cyclic graph is rendered unranked.
+pub fn render_dot_layered(graph: &SchemaGraph, options: &[dot::Config], width: usize) -> String {
+    let dot = render_dot_wrapped(graph, options, width);
+    let layers = match rank_layers(graph) {
+        Some(layers) => layers,
+        None => return dot,
+    };
+    let ranks = layers
+        .iter()
+        .map(|layer| {
+            let indices = layer
+                .iter()
+                .map(|index| format!("{}; ", index.index()))
+                .collect::<String>();
+
+            format!("    {{ rank=same; {}}}\n", indices)
+        })
+        .collect::<String>();
+    let ranks = format!("    rankdir=BT\n{}", ranks);
+
+    // The graph content only has no closing brace.
+    match dot.strip_suffix("}\n") {
+        Some(content) => format!("{}{}}}\n", content, ranks),
+        None => format!("{}{}", dot, ranks),
+    }
+}
+
+/// Escape the XML entities of a string.
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Escape a D2 key, quoting it when it holds other characters than
+/// alphanumerics and underscores, e.g. @deprecated or Foo.bar.
+fn escape_d2(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|char| char == '_' || char.is_alphanumeric())
+    {
+        return key.to_owned();
+    }
+
+    format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render the graph in the D2 diagram language.
+pub fn render_d2(graph: &SchemaGraph) -> String {
+    let mut d2 = String::new();
+
+    for node in graph.node_weights() {
+        d2.push_str(&format!("{}: {{ shape: class }}\n", escape_d2(&node.id)));
+    }
+
+    for edge in graph.edge_references() {
+        d2.push_str(&format!(
+            "{} -> {}\n",
+            escape_d2(&graph[edge.source()].id),
+            escape_d2(&graph[edge.target()].id)
+        ));
+    }
+
+    d2
+}
+
+/// Render the graph as GEXF 1.2 format.
+pub fn render_gexf(graph: &SchemaGraph) -> String {
+    let mut gexf = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://www.gexf.net/1.2draft" version="1.2">
+  <graph mode="static" defaultedgetype="directed">
+    <attributes class="node">
+      <attribute id="0" title="graphql" type="string"/>
+      <attribute id="1" title="path" type="string"/>
+    </attributes>
+    <nodes>
+"#,
+    );
+
+    for index in graph.node_indices() {
+        let node = &graph[index];
+
+        gexf.push_str(&format!(
+            r#"      <node id="{}" label="{}">
+        <attvalues>
+          <attvalue for="0" value="{}"/>
+          <attvalue for="1" value="{}"/>
+        </attvalues>
+      </node>
+"#,
+            index.index(),
+            escape_xml(&node.id),
+            node.entity.graphql,
+            escape_xml(&node.entity.path.to_string_lossy()),
+        ));
+    }
+
+    gexf.push_str("    </nodes>\n    <edges>\n");
+
+    for edge in graph.edge_references() {
+        gexf.push_str(&format!(
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\"/>\n",
+            edge.id().index(),
+            edge.source().index(),
+            edge.target().index(),
+        ));
+    }
+
+    gexf.push_str("    </edges>\n  </graph>\n</gexf>\n");
+
+    gexf
+}
+
+/// Render the graph as JSON.
+pub fn render_json(graph: &SchemaGraph) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&graph_snapshot(graph))?)
+}
+
+/// Render an entity as JSON, raw SDL included.
+pub fn render_entity_json(entity: &Entity) -> Result<String> {
+    Ok(serde_json::to_string_pretty(entity)?)
+}
+
+/// Render the graph as Cytoscape.js elements JSON.
+pub fn render_cytoscape(graph: &SchemaGraph) -> Result<String> {
+    let snapshot = graph_snapshot(graph);
+    let cytoscape = CytoscapeGraph {
+        elements: CytoscapeElements {
+            nodes: snapshot
+                .nodes
+                .into_iter()
+                .map(|node| CytoscapeElement {
+                    data: CytoscapeNodeData {
+                        id: node.id,
+                        name: node.name,
+                        graphql_type: node.graphql,
+                        path: node.path,
+                    },
+                })
+                .collect(),
+            edges: snapshot
+                .edges
+                .into_iter()
+                .enumerate()
+                .map(|(index, edge)| CytoscapeElement {
+                    data: CytoscapeEdgeData {
+                        id: format!("e{}", index),
+                        source: edge.source,
+                        target: edge.target,
+                    },
+                })
+                .collect(),
+        },
+    };
+
+    Ok(serde_json::to_string_pretty(&cytoscape)?)
+}
+
+/// Render the graph as Graphviz JSON, i.e. the shape of dot -Tjson without
+/// the layout, the nodes being referenced by their index.
+pub fn render_gvjson(graph: &SchemaGraph) -> Result<String> {
+    let graphviz = GraphvizGraph {
+        name: String::from("craftql"),
+        directed: true,
+        strict: false,
+        subgraph_cnt: 0,
+        objects: graph
+            .node_indices()
+            .map(|index| {
+                let entity = &graph[index].entity;
+
+                GraphvizObject {
+                    gvid: index.index(),
+                    name: entity.id.clone(),
+                    label: entity.name.clone(),
+                    graphql: entity.graphql.clone(),
+                }
+            })
+            .collect(),
+        edges: graph
+            .edge_references()
+            .enumerate()
+            .map(|(gvid, edge)| GraphvizEdge {
+                gvid,
+                tail: edge.source().index(),
+                head: edge.target().index(),
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&graphviz)?)
+}
+
+/// Line of the JSON Lines output, tagged by type.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonLine<'a> {
+    Node(&'a SnapshotNode),
+    Edge(&'a SnapshotEdge),
+}
+
+/// Render the graph as JSON Lines, the nodes first then the edges.
+pub fn render_jsonl(graph: &SchemaGraph) -> Result<String> {
+    let snapshot = graph_snapshot(graph);
+    let mut jsonl = String::new();
+
+    for line in snapshot
+        .nodes
+        .iter()
+        .map(JsonLine::Node)
+        .chain(snapshot.edges.iter().map(JsonLine::Edge))
+    {
+        jsonl.push_str(&serde_json::to_string(&line)?);
+        jsonl.push('\n');
+    }
+
+    Ok(jsonl)
+}
+
+/// Template of the HTML viewer.
+const VIEWER_TEMPLATE: &str = include_str!("viewer.html");
+
+/// Render the graph as a self-contained HTML viewer, the graph being
+/// embedded as JSON.
+pub fn render_html(graph: &SchemaGraph) -> Result<String> {
+    // Escape the closing tags so that the JSON can't end the script early.
+    let json = serde_json::to_string(&graph_snapshot(graph))?.replace("</", "<\\/");
+    let summary = format!(
+        "{} {}, {} {}",
+        graph.node_count(),
+        if graph.node_count() == 1 {
+            "node"
+        } else {
+            "nodes"
+        },
+        graph.edge_count(),
+        if graph.edge_count() == 1 {
+            "edge"
+        } else {
+            "edges"
+        }
+    );
+
+    Ok(VIEWER_TEMPLATE
+        .replace("__CRAFTQL_SUMMARY__", &summary)
+        .replace("__CRAFTQL_GRAPH__", &json))
+}
+
+/// Render the graph as YAML.
+pub fn render_yaml(graph: &SchemaGraph) -> Result<String> {
+    Ok(serde_yaml::to_string(&graph_snapshot(graph))?)
+}
+
+/// Print missing definitions.
+#[deprecated(since = "0.2.21", note = "use missing_definitions_report instead")]
+pub async fn print_missing_definitions(
+    graph: Arc<Mutex<SchemaGraph>>,
+    missing_definitions: Arc<Mutex<HashMap<NodeIndex, Vec<Name>>>>,
+) -> Result<()> {
+    let graph = graph.lock().await;
+    let missing_definitions = missing_definitions.lock().await;
+
+    for report in missing_definitions_report(&graph, &missing_definitions) {
+        println!(
+            "\n# {} {} not defined in:{}",
+            report.missing.join(", "),
+            if report.missing.len() == 1 {
+                "is"
+            } else {
+                "are"
+            },
+            report.entity,
+        );
+    }
+
+    Ok(())
+}
+
+/// Find and return neighbors of a node.
+#[deprecated(since = "0.2.21", note = "use neighbors instead")]
+pub async fn find_neighbors(
+    node: &str,
+    graph: Arc<Mutex<SchemaGraph>>,
+    direction: Direction,
+) -> Vec<Entity> {
+    let graph = graph.lock().await;
+
+    neighbors(node, &graph, &index_nodes(&graph), direction)
+}
+
+/// Print neighbors of a node.
+#[deprecated(since = "0.2.21", note = "use get_node and neighbors instead")]
+pub async fn find_and_print_neighbors(
+    node: &str,
+    graph: Arc<Mutex<SchemaGraph>>,
+    direction: Direction,
+) -> Result<()> {
+    let graph = graph.lock().await;
+    let node_indices = index_nodes(&graph);
+
+    // Ensure that the node exists!
+    match get_node(node, &graph, &node_indices) {
+        Some(entity) => println!("{}", entity),
+        None => return Err(Error::NodeNotFound(node.to_owned()).into()),
+    }
+
+    let dependencies = neighbors(node, &graph, &node_indices, direction);
+
+    if dependencies.is_empty() {
+        eprintln!("No dependencies found for node {}", node);
+    }
+
+    for dependency in dependencies {
+        println!("{}", dependency);
+    }
+
+    Ok(())
+}
+
+/// Find and return orphan nodes.
+#[deprecated(since = "0.2.21", note = "use orphans instead")]
+pub async fn find_orphans(graph: Arc<Mutex<SchemaGraph>>, only: &[GraphQL]) -> Vec<Entity> {
+    orphans(&*graph.lock().await, only)
+}
+
+/// Print orphan nodes.
+#[deprecated(since = "0.2.21", note = "use orphans instead")]
+pub async fn find_and_print_orphans(
+    graph: Arc<Mutex<SchemaGraph>>,
+    only: &[GraphQL],
+) -> Result<()> {
+    let graph = graph.lock().await;
+    let orphans = orphans(&graph, only);
+
+    if orphans.is_empty() {
+        eprintln!("No orphan node found");
+    }
+
+    for orphan in orphans {
+        println!("{}", orphan);
+    }
+
+    Ok(())
+}
+
+/// Find a node by name and display it, or fail with Error::NodeNotFound.
+#[deprecated(since = "0.2.21", note = "use get_node instead")]
+pub async fn find_node(node: &str, graph: Arc<Mutex<SchemaGraph>>) -> Result<()> {
+    let graph = graph.lock().await;
+
+    match get_node(node, &graph, &index_nodes(&graph)) {
+        Some(entity) => {
+            println!("{}", entity);
+
+            Ok(())
+        }
+        None => Err(Error::NodeNotFound(node.to_owned()).into()),
+    }
+}
+
+/// Recursively read directories and files for a given path, honoring the
+/// options of the state, and return the summary of the walk or fail with
+/// Error::EmptyInput when no GraphQL file is found.
+pub async fn collect_files(path: PathBuf, shared_data: &Data) -> Result<WalkSummary> {
+    walk_files(path, shared_data.files.clone(), shared_data.options.clone()).await
+}
+
+/// Recursively read directories and files for a given path.
+#[deprecated(since = "0.2.21", note = "use collect_files instead")]
+pub fn get_files(
+    path: PathBuf,
+    files: Arc<Mutex<HashMap<PathBuf, String>>>,
+) -> Pin<Box<dyn Future<Output = Result<()>>>> {
+    Box::pin(async move {
+        walk_files(path, files, Arc::new(AnalysisOptions::default()))
+            .await
+            .map(|_| ())
+    })
+}
+
+/// Recursively read directories and files for a given path, honoring the
+/// extensions and excluded paths of a configuration.
+#[deprecated(since = "0.2.21", note = "use collect_files instead")]
+pub fn get_files_with_config(
+    path: PathBuf,
+    files: Arc<Mutex<HashMap<PathBuf, String>>>,
+    config: Arc<Config>,
+) -> Pin<Box<dyn Future<Output = Result<()>>>> {
+    Box::pin(async move {
+        walk_files(path, files, Arc::new(AnalysisOptions::from(&*config)))
+            .await
+            .map(|_| ())
+    })
+}
+
+/// Collect the paths of the files first, then read them concurrently, or
+/// fail with Error::EmptyInput when no GraphQL file is found.
+pub(crate) fn walk_files(
+    path: PathBuf,
+    files: Arc<Mutex<HashMap<PathBuf, String>>>,
+    options: Arc<AnalysisOptions>,
+) -> Pin<Box<dyn Future<Output = Result<WalkSummary>>>> {
+    Box::pin(async move {
+        let (paths, mut skipped) = collect_paths(path.clone(), options.clone()).await?;
+        let read = paths.len();
+
+        if read == 0 {
+            return Err(Error::EmptyInput(path).into());
+        }
+
+        read_files(paths, files, &options).await?;
+        skipped.sort();
+
+        Ok(WalkSummary { read, skipped })
+    })
+}
+
+/// Return a path relative to a base, both being absolute.
+pub fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path_components = path.components().collect::<Vec<_>>();
+    let base_components = base.components().collect::<Vec<_>>();
+    let common = path_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let relative = base_components[common..]
+        .iter()
+        .map(|_| std::path::Component::ParentDir)
+        .chain(path_components[common..].iter().copied())
+        .collect::<PathBuf>();
+
+    match relative.as_os_str().is_empty() {
+        true => PathBuf::from("."),
+        false => relative,
+    }
+}
+
+/// Apply the path style of the options to the path of a walked file.
+pub async fn style_path(path: PathBuf, path_style: &PathStyle) -> Result<PathBuf> {
+    let canonicalize = |path: &Path| {
+        let path = path.to_path_buf();
+
+        async move {
+            path.canonicalize()
+                .await
+                .with_context(|| format!("Failed to read {}", path.to_string_lossy()))
+        }
+    };
+
+    Ok(match path_style {
+        PathStyle::AsProvided => path,
+        PathStyle::Absolute => canonicalize(&path).await?,
+        PathStyle::RelativeTo(base) => {
+            relative_path(&canonicalize(&path).await?, &canonicalize(base).await?)
+        }
+    })
+}
+
+/// Return the entries of a directory which aren't excluded, flagging the
+/// directories, symbolic links to directories included.
+async fn read_dir_entries(
+    path: PathBuf,
+    options: &AnalysisOptions,
+) -> Result<Vec<(PathBuf, bool)>> {
+    let mut entries = vec![];
+    let mut dir = fs::read_dir(&path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.to_string_lossy()))?;
+
+    while let Some(result) = dir.next().await {
+        let entry: fs::DirEntry = result?;
+        let inner_path = entry.path();
+
+        if options.is_path_excluded(&inner_path) {
+            continue;
+        }
+
+        let metadata = entry.metadata().await?;
+        let is_dir = metadata.is_dir()
+            || (metadata.file_type().is_symlink()
+                && fs::metadata(&inner_path)
+                    .await
+                    .with_context(|| format!("Failed to read {}", inner_path.to_string_lossy()))?
+                    .is_dir());
+
+        entries.push((inner_path, is_dir));
+    }
+
+    Ok(entries)
+}
+
+/// Check if a path is gzip-compressed, from its extension.
+pub(crate) fn is_gzip_path(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == GZIP_EXTENSION)
+}
+
+/// Return the extension of a path, peeling the gzip one, e.g. graphql for
+/// schema.graphql.gz. Empty if there is none.
+pub(crate) fn inner_extension(path: &Path) -> &str {
+    let path = match is_gzip_path(path) {
+        true => path.file_stem().map_or(Path::new(""), Path::new),
+        false => path,
+    };
+
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+}
+
+/// Collect the paths of the allowed files for a given path, and the ones
+/// skipped because of their extension, walking the directories level by
+/// level with a bounded concurrency.
+async fn collect_paths(
+    path: PathBuf,
+    options: Arc<AnalysisOptions>,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let is_allowed = |path: &Path| options.is_extension_allowed(inner_extension(path));
+
+    if options.is_path_excluded(&path) {
+        return Ok((vec![], vec![]));
+    }
+
+    let file_or_dir = fs::metadata(&path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.to_string_lossy()))?;
+
+    if !file_or_dir.is_dir() {
+        return Ok(if is_allowed(&path) || is_introspection_path(&path) {
+            (vec![path], vec![])
+        } else {
+            (vec![], vec![path])
+        });
+    }
+
+    let mut paths = vec![];
+    let mut skipped = vec![];
+    let mut dirs = vec![path];
+
+    while !dirs.is_empty() {
+        let entries = futures::StreamExt::buffered(
+            stream::iter(
+                dirs.drain(..)
+                    .map(|dir| read_dir_entries(dir, &options))
+                    .collect::<Vec<_>>(),
+            ),
+            options.concurrency(),
+        )
+        .try_collect::<Vec<Vec<(PathBuf, bool)>>>()
+        .await?;
+
+        for (inner_path, is_dir) in entries.into_iter().flatten() {
+            if is_dir {
+                dirs.push(inner_path);
+            } else if is_allowed(&inner_path) {
+                paths.push(inner_path);
+            } else {
+                skipped.push(inner_path);
+            }
+        }
+    }
+
+    Ok((paths, skipped))
+}
+
+/// Read the files with a bounded concurrency, inserting them as they arrive.
+async fn read_files(
+    paths: Vec<PathBuf>,
+    files: Arc<Mutex<HashMap<PathBuf, String>>>,
+    options: &AnalysisOptions,
+) -> Result<()> {
+    stream::iter(paths.into_iter().map(Ok))
+        .try_for_each_concurrent(options.concurrency(), |path| {
+            let files = files.clone();
+
+            async move {
+                let contents = read_file(&path)
+                    .await
+                    .with_context(|| format!("Failed to read {}", path.to_string_lossy()))?;
+                let path = style_path(path, options.stored_path_style()).await?;
+
+                files.lock().await.insert(path, contents);
+
+                Ok(())
+            }
+        })
+        .await
+}
+
+/// Read a file as a string, decompressing the gzip-compressed ones.
+async fn read_file(path: &Path) -> Result<String> {
+    if !is_gzip_path(path) {
+        return Ok(fs::read_to_string(path).await?);
+    }
+
+    let mut contents = String::new();
+
+    GzDecoder::new(fs::read(path).await?.as_slice()).read_to_string(&mut contents)?;
+
+    Ok(contents)
+}
+
+/// Check if a source is blank, i.e. only made of whitespaces and comments.
+pub fn is_blank_source(contents: &str) -> bool {
+    contents
+        .lines()
+        .map(str::trim)
+        .all(|line| line.is_empty() || line.starts_with('#'))
+}
+
+/// Build the entities of a definition, followed by the ones of its fields
+/// when they are added as nodes. The definition then depends on its fields
+/// rather than on their dependencies.
+fn to_entities(
+    entity: impl ExtendType,
+    path: &Path,
+    keep_raw: bool,
+    extraction: Extraction,
+) -> Vec<Entity> {
+    let (id, name) = entity.get_id_and_name();
+    let raw = |raw: String| match keep_raw {
+        true => raw,
+        false => String::new(),
+    };
+    let mut field_sets = match extraction.federation {
+        true => entity.get_field_set_dependencies(),
+        false => FieldSetDependencies::default(),
+    };
+
+    if !extraction.fields {
+        let dependencies = match extraction.federation {
+            true => sort_and_dedupe_dependencies(
+                entity
+                    .get_dependencies()
+                    .into_iter()
+                    .chain(field_sets.keys)
+                    .chain(field_sets.fields.into_values().flatten())
+                    .collect(),
+            ),
+            false => entity.get_dependencies(),
+        };
+
+        return vec![Entity::new(
+            dependencies,
+            entity.get_mapped_type(),
+            id,
+            name,
+            path,
+            Some(entity.get_position()),
+            raw(entity.get_raw()),
+        )
+        .with_description(entity.get_description())
+        .with_empty(entity.is_empty())
+        .with_interfaces(entity.get_interfaces())
+        .with_members(entity.get_members())
+        .with_argument_types(entity.get_argument_types())
+        .with_references(entity.get_references())];
+    }
+
+    let field_entities = entity
+        .get_fields()
+        .into_iter()
+        .map(|field| {
+            let dependencies = match field_sets.fields.remove(&field.name) {
+                Some(field_set_dependencies) => sort_and_dedupe_dependencies(
+                    field
+                        .dependencies
+                        .into_iter()
+                        .chain(field_set_dependencies)
+                        .collect(),
+                ),
+                None => field.dependencies,
+            };
+
+            Entity::new(
+                dependencies,
+                GraphQL::Field,
+                None,
+                format!("{}.{}", name, field.name),
+                path,
+                Some(field.position),
+                raw(field.raw),
+            )
+            .with_description(field.description)
+        })
+        .collect::<Vec<Entity>>();
+    let dependencies = sort_and_dedupe_dependencies(
+        entity
+            .get_type_dependencies()
+            .into_iter()
+            .chain(field_sets.keys)
+            .chain(field_entities.iter().map(|field| field.name.clone()))
+            .collect(),
+    );
+
+    std::iter::once(
+        Entity::new(
+            dependencies,
+            entity.get_mapped_type(),
+            id,
+            name,
+            path,
+            Some(entity.get_position()),
+            raw(entity.get_raw()),
+        )
+        .with_description(entity.get_description())
+        .with_empty(entity.is_empty())
+        .with_interfaces(entity.get_interfaces())
+        .with_members(entity.get_members())
+        .with_argument_types(entity.get_argument_types())
+        .with_references(entity.get_references()),
+    )
+    .chain(field_entities)
+    .collect()
+}
+
+/// Parse a source and return the entities of its definitions, with or
+/// without their raw SDL and their fields.
+fn parse_source(
+    path: &Path,
+    contents: &str,
+    keep_raw: bool,
+    extraction: Extraction,
+) -> Result<Vec<Entity>> {
+    // graphql_parser rejects a document without definitions.
+    if is_blank_source(contents) {
+        return Ok(vec![]);
+    }
+
+    let sdl;
+    let contents = match is_introspection_path(path) {
+        true => {
+            sdl = introspection_to_sdl(contents)
+                .with_context(|| format!("Failed to read {}", path.to_string_lossy()))?;
+            sdl.as_str()
+        }
+        false => contents,
+    };
+    let ast = match parse_schema::<String>(contents) {
+        Ok(ast) => ast,
+        // Executable documents living next to the schema, see link_operations.
+        Err(_) if extraction.operations && parse_query::<String>(contents).is_ok() => {
+            return Ok(vec![])
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    // Reference: http://spec.graphql.org/draft/
+    Ok(ast
+        .definitions
+        .into_iter()
+        .flat_map(|definition| match definition {
+            schema::Definition::TypeDefinition(type_definition) => {
+                to_entities(type_definition, path, keep_raw, extraction)
+            }
+            schema::Definition::TypeExtension(type_extension) => {
+                to_entities(type_extension, path, keep_raw, extraction)
+            }
+            schema::Definition::SchemaDefinition(schema_definition) => {
+                to_entities(schema_definition, path, keep_raw, extraction)
+            }
+            schema::Definition::DirectiveDefinition(directive_definition) => {
+                to_entities(directive_definition, path, keep_raw, extraction)
+            }
+        })
+        .collect())
+}
+
+/// Parse the sources without building the graph and return the errors of
+/// the ones failing to parse, sorted by path.
+pub fn validate_sources(files: &HashMap<PathBuf, String>) -> Vec<(PathBuf, String)> {
+    let mut errors = files
+        .iter()
+        .filter_map(|(path, contents)| {
+            // The executable documents parse too.
+            let extraction = Extraction {
+                operations: true,
+                ..Extraction::default()
+            };
+
+            parse_source(path, contents, false, extraction)
+                .err()
+                .map(|error| (path.clone(), format!("{:#}", error).trim_end().to_owned()))
+        })
+        .collect::<Vec<(PathBuf, String)>>();
+
+    errors.sort();
+
+    errors
+}
+
+/// Extract the entities of a source, from the cache if enabled.
+fn extract_source(path: &Path, contents: &str, options: &AnalysisOptions) -> Result<Vec<Entity>> {
+    let extraction = options.extraction();
+    let entities = match options.cache() {
+        // The cache always holds the raw SDL, dropped afterwards if not kept.
+        Some(cache) => cache.get_or_extract(path, contents, extraction, || {
+            parse_source(path, contents, true, extraction)
+        })?,
+        None => parse_source(path, contents, options.keeps_raw(), extraction)?,
+    };
+
+    Ok(entities
+        .into_iter()
+        .map(|mut entity| {
+            if !options.keeps_raw() {
+                entity.raw = String::new();
+            }
+
+            entity
+        })
+        .collect())
+}
+
+/// Keep the entities allowed by the options, the fields following their
+/// type.
+fn filter_entities(entities: Vec<Entity>, options: &AnalysisOptions) -> Vec<Entity> {
+    let mut is_type_allowed = false;
+
+    entities
+        .into_iter()
+        .filter(|entity| match entity.graphql {
+            GraphQL::Field => is_type_allowed,
+            ref graphql => {
+                is_type_allowed = options.is_graphql_allowed(graphql);
+                is_type_allowed
+            }
+        })
+        .collect()
+}
+
+/// Parse the sources concurrently, in chunks spread over the threads, and
+/// return their entities sorted by path.
+fn parse_sources(
+    mut sources: Vec<(&PathBuf, &str)>,
+    options: &AnalysisOptions,
+    threads: usize,
+) -> Result<Vec<Entity>> {
+    // Sort by path to keep the node insertion order deterministic.
+    sources.sort_by_key(|(path, _)| *path);
+
+    let chunk_size = ((sources.len() + threads - 1) / threads.max(1)).max(1);
+
+    std::thread::scope(|scope| {
+        let handles = sources
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(path, contents)| extract_source(path, contents, options))
+                        .collect::<Result<Vec<Vec<Entity>>>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Join in order, the chunks follow the sorted sources.
+        let mut entities = vec![];
+
+        for handle in handles {
+            let chunk = handle
+                .join()
+                .map_err(|_| anyhow::anyhow!("A parsing thread panicked"))??;
+
+            entities.extend(chunk.into_iter().flatten());
+        }
+
+        Ok(entities)
+    })
+}
+
+/// Number the extensions of each type in the sources order, for several
+/// extensions of a type to get unique ids.
+fn number_extensions(entities: &mut [Entity]) {
+    let mut ordinals: HashMap<String, usize> = HashMap::new();
+
+    for entity in entities.iter_mut() {
+        if let Some(ref extends) = entity.extends {
+            let ordinal = ordinals.entry(extends.clone()).or_default();
+
+            *ordinal += 1;
+            entity.id = get_extended_id(extends, *ordinal);
+        }
+    }
+}
+
+/// Resolve the directive argument coordinates found in the dependencies to
+/// the named types of the arguments, the unknown directives being dropped.
+fn resolve_directive_arguments(entities: &mut [Entity]) {
+    let argument_types = entities
+        .iter()
+        .flat_map(|entity| entity.argument_types.clone())
+        .collect::<HashMap<String, String>>();
+
+    for entity in entities.iter_mut() {
+        if !entity
+            .dependencies
+            .iter()
+            .any(|dependency| is_directive_argument_coordinate(dependency))
+        {
+            continue;
+        }
+
+        let (coordinates, dependencies): (Vec<Name>, Vec<Name>) =
+            std::mem::take(&mut entity.dependencies)
+                .into_iter()
+                .partition(|dependency| is_directive_argument_coordinate(dependency));
+
+        entity.directive_dependencies = coordinates
+            .into_iter()
+            .filter_map(|coordinate| {
+                argument_types.get(&*coordinate).map(|name| Dependency {
+                    name: name.clone(),
+                    via: coordinate.to_string(),
+                })
+            })
+            // A plain reference is the reason of the dependency.
+            .filter(|dependency| !dependencies.iter().any(|name| **name == dependency.name))
+            .collect();
+        entity.dependencies = sort_and_dedupe_dependencies(
+            dependencies
+                .into_iter()
+                .chain(
+                    entity
+                        .directive_dependencies
+                        .iter()
+                        .map(|dependency| Name::from(dependency.name.as_str())),
+                )
+                .collect(),
+        );
+    }
+}
+
+pub(crate) fn add_node_and_dependencies(
+    mut entity: Entity,
+    graph: &mut SchemaGraph,
+    dependencies: &mut HashMap<NodeIndex, Vec<Name>>,
+    node_indices: &mut NodeIndices,
+    interner: &mut Interner,
+) {
+    // Share a single allocation per name across the nodes and dependencies.
+    entity.dependencies = entity
+        .dependencies
+        .iter()
+        .map(|dependency| interner.intern(dependency))
+        .collect();
+
+    let entity_dependencies = entity.dependencies.clone();
+    let node_id = interner.intern(&entity.id);
+    let node_index = graph.add_node(Node::new(entity, node_id.clone()));
+
+    // Index the node, duplicate ids don't overwrite the first node.
+    node_indices.entry(node_id).or_insert(node_index);
+
+    // Update dependencies.
+    dependencies.insert(node_index, entity_dependencies);
+}
+
+/// Parse the sources, generate an AST and walk it to populate the graph
+/// synchronously, the parsing being spread over the available threads.
+pub(crate) fn populate_graph<'a>(
+    sources: impl IntoIterator<Item = (&'a PathBuf, &'a str)>,
+    options: &AnalysisOptions,
+    graph: &mut SchemaGraph,
+    dependencies: &mut HashMap<NodeIndex, Vec<Name>>,
+    missing_definitions: &mut HashMap<NodeIndex, Vec<Name>>,
+    node_indices: &mut NodeIndices,
+) -> Result<()> {
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+
+    populate_graph_with_threads(
+        sources,
+        options,
+        graph,
+        dependencies,
+        missing_definitions,
+        node_indices,
+        threads,
+    )
+}
+
+fn populate_graph_with_threads<'a>(
+    sources: impl IntoIterator<Item = (&'a PathBuf, &'a str)>,
+    options: &AnalysisOptions,
+    graph: &mut SchemaGraph,
+    dependencies: &mut HashMap<NodeIndex, Vec<Name>>,
+    missing_definitions: &mut HashMap<NodeIndex, Vec<Name>>,
+    node_indices: &mut NodeIndices,
+    threads: usize,
+) -> Result<()> {
+    let mut interner = Interner::default();
+
+    let mut entities = parse_sources(sources.into_iter().collect(), options, threads)?;
+
+    number_extensions(&mut entities);
+    // Resolve before filtering, the directive definitions may be filtered out.
+    resolve_directive_arguments(&mut entities);
+
+    // Populate the nodes first, in a single-threaded phase.
+    for entity in filter_entities(entities, options) {
+        add_node_and_dependencies(entity, graph, dependencies, node_indices, &mut interner);
+    }
+
+    // Add the referenced built-in scalars which are not overridden.
+    if options.builtins_as_nodes()
+        && options.is_graphql_allowed(&GraphQL::TypeDefinition(GraphQLType::Scalar))
+    {
+        let referenced = dependencies
+            .values()
+            .flatten()
+            .map(|dependency| &**dependency)
+            .collect::<HashSet<&str>>();
+        let built_in_scalars = BUILT_IN_SCALARS
+            .into_iter()
+            .filter(|name| referenced.contains(name) && !node_indices.contains_key(*name))
+            .collect::<Vec<&str>>();
+
+        for name in built_in_scalars {
+            add_node_and_dependencies(
+                Entity::new(
+                    vec![],
+                    GraphQL::TypeDefinition(GraphQLType::Scalar),
+                    None,
+                    name.to_string(),
+                    Path::new(BUILT_IN_SCALARS_PATH),
+                    None,
+                    format!("scalar {}\n", name),
+                ),
+                graph,
+                dependencies,
+                node_indices,
+                &mut interner,
+            );
+        }
+    }
+
+    // Populate the edges in a single pass, then record the missing
+    // definitions at once.
+    missing_definitions.extend(wire_edges(graph, dependencies, node_indices, options));
+
+    Ok(())
+}
+
+/// Wire the edges of the graph from the dependencies of the nodes and return
+/// the missing definitions of each node.
+pub(crate) fn wire_edges(
+    graph: &mut SchemaGraph,
+    dependencies: &HashMap<NodeIndex, Vec<Name>>,
+    node_indices: &NodeIndices,
+    options: &AnalysisOptions,
+) -> HashMap<NodeIndex, Vec<Name>> {
+    let mut missing_definitions = HashMap::new();
+    // The normalized ids are only matched when the dependency isn't an id.
+    let normalized_indices = options.id_normalizer().map(|normalizer| {
+        let mut indices = node_indices.iter().collect::<Vec<_>>();
+        let mut normalized_indices = HashMap::new();
+
+        indices.sort_by_key(|(_, index)| **index);
+
+        for (id, index) in indices {
+            normalized_indices
+                .entry(normalizer.normalize(id))
+                .or_insert(*index);
+        }
+
+        (normalizer, normalized_indices)
+    });
+    let find_index = |dependency: &str| {
+        find_index(dependency, node_indices).or_else(|| {
+            normalized_indices
+                .as_ref()
+                .and_then(|(normalizer, normalized_indices)| {
+                    normalized_indices
+                        .get(&normalizer.normalize(dependency))
+                        .copied()
+                })
+        })
+    };
+    // Follow the nodes order to keep the edge indices deterministic.
+    let mut nodes_dependencies = dependencies.iter().collect::<Vec<_>>();
+
+    nodes_dependencies.sort_by_key(|(node_index, _)| **node_index);
+
+    for (node_index, inner_dependencies) in nodes_dependencies {
+        // Reverse edge for extension types, except with their own fields.
+        let is_extension = graph[*node_index].entity.graphql.is_extension();
+        let mut node_missing_definitions: Vec<Name> = vec![];
+
+        for dependency in inner_dependencies {
+            // The dependencies resolved from a directive argument keep it.
+            let edge = match graph[*node_index]
+                .entity
+                .directive_dependencies
+                .iter()
+                .find(|directive_dependency| directive_dependency.name == **dependency)
+            {
+                Some(directive_dependency) => {
+                    Edge::new(dependency.clone()).with_reason(directive_dependency.via.clone())
+                }
+                None => Edge::new(dependency.clone()),
+            };
+
+            match find_index(dependency) {
+                Some(index) if is_extension && graph[index].entity.graphql != GraphQL::Field => {
+                    graph.update_edge(*node_index, index, edge);
+                }
+                Some(index) => {
+                    graph.update_edge(index, *node_index, edge);
+                }
+                // Built-in Scalars, skip.
+                None if BUILT_IN_SCALARS.contains(&&**dependency) => {}
+                // Built-in and known directives, skip.
+                None if options.is_known_directive(dependency) => {}
+                // Defined elsewhere, e.g. stitched at runtime, skip.
+                None if options.is_missing_ignored(dependency) => {}
+                // Keep track of possible missing definitions, should have been resolved at this point!
+                None => {
+                    node_missing_definitions.push(dependency.clone());
+                }
+            }
+        }
+
+        if !node_missing_definitions.is_empty() {
+            missing_definitions.insert(*node_index, node_missing_definitions);
+        }
+    }
+
+    missing_definitions
+}
+
+/// Parse the collected files, generate an AST and walk it to populate the
+/// graph, honoring the options of the state.
+pub async fn build_graph(shared_data: &Data) -> Result<()> {
+    let files = shared_data.files.lock().await;
+
+    populate_graph(
+        files
+            .iter()
+            .map(|(path, contents)| (path, contents.as_str())),
+        &shared_data.options,
+        &mut *shared_data.graph.lock().await,
+        &mut *shared_data.dependencies.lock().await,
+        &mut *shared_data.missing_definitions.lock().await,
+        &mut *shared_data.node_indices.lock().await,
+    )
+}
+
+/// Parse the files, generate an AST and walk it to populate the graph.
+#[deprecated(since = "0.2.21", note = "use build_graph instead")]
+pub async fn populate_graph_from_ast(
+    dependencies: Arc<Mutex<HashMap<NodeIndex, Vec<Name>>>>,
+    files: Arc<Mutex<HashMap<PathBuf, String>>>,
+    filter: &[GraphQL],
+    exclude: &[GraphQL],
+    graph: Arc<Mutex<SchemaGraph>>,
+    missing_definitions: Arc<Mutex<HashMap<NodeIndex, Vec<Name>>>>,
+    node_indices: Arc<Mutex<NodeIndices>>,
+) -> Result<()> {
+    let files = files.lock().await;
+
+    populate_graph(
+        files
+            .iter()
+            .map(|(path, contents)| (path, contents.as_str())),
+        &AnalysisOptions::new()
+            .filter(filter.iter().cloned())
+            .exclude_types(exclude.iter().cloned()),
+        &mut *graph.lock().await,
+        &mut *dependencies.lock().await,
+        &mut *missing_definitions.lock().await,
+        &mut *node_indices.lock().await,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::{Data, Granularity, GraphQL, GraphQLType, ScalarUsage, SortBy, State};
+
+    use async_std::task;
+    use petgraph::graph::NodeIndex;
+
+    async fn scaffold(files: Vec<(PathBuf, String)>, filters: &[GraphQL]) -> Data {
+        scaffold_with_exclude(files, filters, &[]).await
+    }
+
+    async fn scaffold_with_exclude(
+        files: Vec<(PathBuf, String)>,
+        filters: &[GraphQL],
+        excludes: &[GraphQL],
+    ) -> Data {
+        let state = State::with_options(
+            AnalysisOptions::new()
+                .filter(filters.iter().cloned())
+                .exclude_types(excludes.iter().cloned()),
+        );
+        let shared_data = state.shared;
+
+        task::block_on(async {
+            let mut shared_files = shared_data.files.lock().await;
+
+            for (path, contents) in files {
+                shared_files.insert(path, contents);
+            }
+        });
+
+        build_graph(&shared_data).await.unwrap();
+
+        shared_data
+    }
+
+    #[async_std::test]
+    async fn check_dependencies_and_graph() {
+        let house_contents = "type House { price: Int! rooms: Int! @test owner: Owner! }";
+        let house_dependencies = vec![Name::from("@test"), Name::from("Int"), Name::from("Owner")];
+        let house_name = "House";
+        let house_path = "some_path/House.gql";
 
         let owner_contents = "type Owner { name: String! }";
-        let owner_dependencies = vec!["String"];
+        let owner_dependencies = vec![Name::from("String")];
         let owner_name = "Owner";
         let owner_path = "some_path/Owner.graphql";
 
         let shared_data = scaffold(
             vec![
-                (PathBuf::from(house_path), String::from(house_contents)),
-                (PathBuf::from(owner_path), String::from(owner_contents)),
+                (PathBuf::from(house_path), String::from(house_contents)),
+                (PathBuf::from(owner_path), String::from(owner_contents)),
+            ],
+            &[],
+        )
+        .await;
+
+        let dependencies = shared_data.dependencies.lock().await;
+
+        assert_eq!(dependencies.len(), 2);
+
+        // The nodes follow the order of the paths.
+        let house_node_index = NodeIndex::new(0);
+        let owner_node_index = NodeIndex::new(1);
+
+        // List of dependencies should match.
+        assert_eq!(
+            dependencies.get(&owner_node_index).unwrap(),
+            &owner_dependencies
+        );
+        assert_eq!(
+            dependencies.get(&house_node_index).unwrap(),
+            &house_dependencies
+        );
+
+        // Graph should contains 2 nodes and 1 edge.
+        let graph = shared_data.graph.lock().await;
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+
+        // Check house.
+        let house = graph.node_weight(house_node_index).unwrap();
+        assert_eq!(&*house.id, house_name);
+        assert_eq!(house.entity.dependencies, house_dependencies);
+        assert_eq!(
+            house.entity.graphql,
+            GraphQL::TypeDefinition(GraphQLType::Object)
+        );
+        assert_eq!(house.entity.id, String::from(house_name));
+        assert_eq!(house.entity.name, String::from(house_name));
+        assert_eq!(house.entity.path, PathBuf::from(house_path));
+        // We need to parse and format the AST in order to get the same output!
+        assert_eq!(
+            house.entity.raw.to_string(),
+            format!(
+                "{}",
+                parse_schema::<String>(house_contents).unwrap().to_owned()
+            )
+        );
+
+        // Check owner.
+        let owner = graph.node_weight(owner_node_index).unwrap();
+        assert_eq!(&*owner.id, owner_name);
+        assert_eq!(owner.entity.dependencies, owner_dependencies);
+        assert_eq!(
+            owner.entity.graphql,
+            GraphQL::TypeDefinition(GraphQLType::Object)
+        );
+        assert_eq!(owner.entity.id, String::from(owner_name));
+        assert_eq!(owner.entity.name, String::from(owner_name));
+        assert_eq!(owner.entity.path, PathBuf::from(owner_path));
+        // We need to parse and format the AST in order to get the same output!
+        assert_eq!(
+            owner.entity.raw.to_string(),
+            format!(
+                "{}",
+                parse_schema::<String>(owner_contents).unwrap().to_owned()
+            )
+        );
+
+        // Check the edges. Owner should be directed to House, not the other
+        // way around!
+        assert!(graph.contains_edge(owner_node_index, house_node_index));
+        assert!(!graph.contains_edge(house_node_index, owner_node_index));
+    }
+
+    #[async_std::test]
+    async fn check_resolve_node() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/User.gql"),
+                    String::from("type User { id: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Role.gql"),
+                    String::from("enum Role { ADMIN }"),
+                ),
+                (
+                    PathBuf::from("some_path/ROLE.gql"),
+                    String::from("enum ROLE { USER }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let node_indices = shared_data.node_indices.lock().await;
+
+        assert_eq!(resolve_node("User", &node_indices, false).unwrap(), "User");
+        assert!(matches!(
+            resolve_node("user", &node_indices, false),
+            Err(Error::NodeNotFound(_))
+        ));
+        assert_eq!(resolve_node("user", &node_indices, true).unwrap(), "User");
+        // An exact match wins.
+        assert_eq!(resolve_node("Role", &node_indices, true).unwrap(), "Role");
+        match resolve_node("role", &node_indices, true) {
+            Err(Error::AmbiguousNode(node, candidates)) => {
+                assert_eq!(node, "role");
+                assert_eq!(candidates, vec!["ROLE", "Role"]);
+            }
+            _ => panic!("role should be ambiguous"),
+        }
+    }
+
+    #[async_std::test]
+    async fn check_node_indices() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { bar: Bar }"),
+                ),
+                (
+                    PathBuf::from("some_path/FooExtension.gql"),
+                    String::from("extend type Foo { id: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("type Bar { id: ID! }"),
+                ),
+                (
+                    PathBuf::from("other_path/Bar.gql"),
+                    String::from("type Bar { name: String! }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+
+        // Extensions are indexed with their own id.
+        assert_eq!(node_indices.len(), 3);
+        assert_eq!(
+            graph[node_indices["Foo"]].entity.graphql,
+            GraphQL::TypeDefinition(GraphQLType::Object)
+        );
+        assert_eq!(
+            graph[node_indices["Foo__1"]].entity.graphql,
+            GraphQL::TypeExtension(GraphQLType::Object)
+        );
+        assert_eq!(get_node("Foo", &graph, &node_indices).unwrap().name, "Foo");
+        assert_eq!(
+            get_node("Foo__1", &graph, &node_indices).unwrap().name,
+            "Foo"
+        );
+        assert_eq!(
+            neighbors("Foo", &graph, &node_indices, Direction::Incoming).len(),
+            2
+        );
+        assert_eq!(index_nodes(&graph), *node_indices);
+
+        // The duplicate Bar is detected and the first one is kept.
+        let duplicate_ids = duplicate_ids(&graph, &node_indices);
+        assert_eq!(duplicate_ids.len(), 1);
+
+        let bars = duplicate_ids.get("Bar").unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars.first(), node_indices.get("Bar"));
+    }
+
+    #[test]
+    fn check_parallel_parsing() {
+        let sources = (0..500)
+            .map(|index| {
+                (
+                    PathBuf::from(format!("some_path/T{}.gql", index)),
+                    format!(
+                        "type T{} {{ next: T{} }} extend type T{} {{ id: ID! }}",
+                        index,
+                        index + 1,
+                        index
+                    ),
+                )
+            })
+            .collect::<Vec<(PathBuf, String)>>();
+        let populate = |threads: usize| {
+            let mut graph = SchemaGraph::new();
+
+            populate_graph_with_threads(
+                sources
+                    .iter()
+                    .map(|(path, contents)| (path, contents.as_str())),
+                &AnalysisOptions::default(),
+                &mut graph,
+                &mut HashMap::new(),
+                &mut HashMap::new(),
+                &mut HashMap::new(),
+                threads,
+            )
+            .unwrap();
+
+            graph
+        };
+        let sequential = populate(1);
+        let parallel = populate(8);
+
+        assert_eq!(sequential.node_count(), 1000);
+        assert_eq!(graph_snapshot(&parallel), graph_snapshot(&sequential));
+        assert_eq!(render_dot(&parallel, &[]), render_dot(&sequential, &[]));
+    }
+
+    #[test]
+    fn check_keep_raw() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let sources = [(
+            PathBuf::from("some_path/schema.gql"),
+            String::from("type Foo { bar: Bar } type Bar { id: ID! } extend type Bar { foo: Foo }"),
+        )];
+        let populate = |options: AnalysisOptions| {
+            let mut graph = SchemaGraph::new();
+
+            populate_graph(
+                sources
+                    .iter()
+                    .map(|(path, contents)| (path, contents.as_str())),
+                &options,
+                &mut graph,
+                &mut HashMap::new(),
+                &mut HashMap::new(),
+                &mut HashMap::new(),
+            )
+            .unwrap();
+
+            graph
+        };
+        let with_raw = populate(AnalysisOptions::new());
+        let without_raw = populate(AnalysisOptions::new().keep_raw(false));
+        // The cache keeps the raw SDL for the later runs needing it.
+        let cached_without_raw = populate(
+            AnalysisOptions::new()
+                .cache_dir(cache_dir.path())
+                .keep_raw(false),
+        );
+        let cached_with_raw = populate(AnalysisOptions::new().cache_dir(cache_dir.path()));
+
+        assert!(AnalysisOptions::new().keeps_raw());
+        assert!(with_raw
+            .node_weights()
+            .all(|node| !node.entity.raw.is_empty()));
+        assert!(without_raw
+            .node_weights()
+            .chain(cached_without_raw.node_weights())
+            .all(|node| node.entity.raw.is_empty()));
+        assert_eq!(graph_snapshot(&without_raw), graph_snapshot(&with_raw));
+        assert_eq!(render_dot(&without_raw, &[]), render_dot(&with_raw, &[]));
+        assert_eq!(
+            graph_snapshot(&cached_without_raw),
+            graph_snapshot(&with_raw)
+        );
+        assert!(cached_with_raw
+            .node_weights()
+            .zip(with_raw.node_weights())
+            .all(|(cached, node)| cached.entity.raw == node.entity.raw));
+    }
+
+    #[test]
+    fn check_interned_names() {
+        let sources = (0..5000)
+            .map(|index| {
+                (
+                    PathBuf::from(format!("some_path/T{}.gql", index)),
+                    format!(
+                        "type T{} {{ next: T{} shared: Shared missing: Missing }}",
+                        index,
+                        index + 1
+                    ),
+                )
+            })
+            .chain(std::iter::once((
+                PathBuf::from("some_path/Shared.gql"),
+                String::from("type Shared { id: ID! }"),
+            )))
+            .collect::<Vec<(PathBuf, String)>>();
+        let mut graph = SchemaGraph::new();
+        let mut dependencies = HashMap::new();
+        let mut missing_definitions = HashMap::new();
+        let mut node_indices = HashMap::new();
+
+        populate_graph_with_threads(
+            sources
+                .iter()
+                .map(|(path, contents)| (path, contents.as_str())),
+            &AnalysisOptions::default(),
+            &mut graph,
+            &mut dependencies,
+            &mut missing_definitions,
+            &mut node_indices,
+            4,
+        )
+        .unwrap();
+
+        let (shared_id, shared_index) = node_indices.get_key_value("Shared").unwrap();
+        let missing = &missing_definitions[&node_indices["T0"]][0];
+
+        assert_eq!(graph.node_count(), 5001);
+        assert!(Arc::ptr_eq(shared_id, &graph[*shared_index].id));
+        // A single allocation per name, whatever the number of references.
+        assert!(graph
+            .node_weights()
+            .flat_map(|node| node.entity.dependencies.iter())
+            .chain(dependencies.values().flatten())
+            .filter(|dependency| &***dependency == "Shared")
+            .all(|dependency| Arc::ptr_eq(dependency, shared_id)));
+        assert_eq!(missing_definitions.len(), 5000);
+        assert!(missing_definitions
+            .values()
+            .flatten()
+            .filter(|dependency| &***dependency == "Missing")
+            .all(|dependency| Arc::ptr_eq(dependency, missing)));
+        // Sort order is still the one of the strings.
+        assert_eq!(
+            graph[node_indices["T1"]].entity.dependencies,
+            vec![
+                Name::from("Missing"),
+                Name::from("Shared"),
+                Name::from("T2")
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn check_known_directives() {
+        let files = vec![(
+            PathBuf::from("some_path/Foo.gql"),
+            String::from(
+                "type Foo @key(fields: \"id\") @tag(name: \"foo\") { id: ID! @deprecated bar: Int @skip(if: true) }",
+            ),
+        )];
+        let missing = |options: AnalysisOptions| async {
+            let shared_data = State::with_options(options).shared;
+
+            shared_data.files.lock().await.extend(files.clone());
+            build_graph(&shared_data).await.unwrap();
+
+            let missing_definitions = shared_data.missing_definitions.lock().await;
+
+            missing_definitions
+                .values()
+                .flatten()
+                .map(|name| name.to_string())
+                .collect::<Vec<String>>()
+        };
+
+        // The built-in directives are never reported.
+        assert_eq!(missing(AnalysisOptions::new()).await, vec!["@key", "@tag"]);
+        assert_eq!(
+            missing(AnalysisOptions::new().known_directives(["key", "@tag"])).await,
+            Vec::<String>::new()
+        );
+    }
+
+    #[async_std::test]
+    async fn check_schema_definitions() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/b.gql"),
+                    String::from("type Query { id: ID } schema { query: Query }"),
+                ),
+                (
+                    PathBuf::from("some_path/a.gql"),
+                    String::from("schema { query: Query }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let locations = schema_definitions(&graph)
+            .into_iter()
+            .map(|entity| (entity.path, entity.position))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            locations,
+            vec![
+                (PathBuf::from("some_path/a.gql"), Some((1, 1))),
+                (PathBuf::from("some_path/b.gql"), Some((1, 23))),
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn check_ignore_missing() {
+        let files = vec![(
+            PathBuf::from("some_path/Foo.gql"),
+            String::from("type Query { foo: Foo bar: Bar baz: [Baz!] }"),
+        )];
+        let missing = |options: AnalysisOptions| async {
+            let shared_data = State::with_options(options).shared;
+
+            shared_data.files.lock().await.extend(files.clone());
+            build_graph(&shared_data).await.unwrap();
+
+            let missing_definitions = shared_data.missing_definitions.lock().await;
+
+            missing_definitions
+                .values()
+                .flatten()
+                .map(|name| name.to_string())
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            missing(AnalysisOptions::new()).await,
+            vec!["Bar", "Baz", "Foo"]
+        );
+        assert_eq!(
+            missing(AnalysisOptions::new().ignore_missing(["Foo", "Baz"])).await,
+            vec!["Bar"]
+        );
+    }
+
+    #[async_std::test]
+    async fn check_normalizer() {
+        let files = vec![(
+            PathBuf::from("some_path/schema.gql"),
+            String::from(
+                "type Query { user: User post: Post } type User_v1 { id: ID! } type Post { id: ID! } type Post_v1 { id: ID! }",
+            ),
+        )];
+        let edges = |options: AnalysisOptions| async {
+            let shared_data = State::with_options(options).shared;
+
+            shared_data.files.lock().await.extend(files.clone());
+            build_graph(&shared_data).await.unwrap();
+
+            let graph = shared_data.graph.lock().await;
+            let missing_definitions = shared_data.missing_definitions.lock().await;
+
+            (
+                graph
+                    .edge_references()
+                    .map(|edge| {
+                        (
+                            graph[edge.source()].id.to_string(),
+                            graph[edge.target()].id.to_string(),
+                        )
+                    })
+                    .collect::<Vec<(String, String)>>(),
+                missing_definitions.values().flatten().count(),
+            )
+        };
+        let edge = |source: &str, target: &str| (String::from(source), String::from(target));
+
+        assert_eq!(
+            edges(AnalysisOptions::new()).await,
+            (vec![edge("Post", "Query")], 1)
+        );
+        // User_v1 is linked, Post is still matched as is.
+        assert_eq!(
+            edges(
+                AnalysisOptions::new()
+                    .normalizer(|id| id.strip_suffix("_v1").unwrap_or(id).to_owned())
+            )
+            .await,
+            (vec![edge("Post", "Query"), edge("User_v1", "Query")], 0)
+        );
+    }
+
+    #[async_std::test]
+    async fn check_blank_sources() {
+        assert!(is_blank_source(""));
+        assert!(is_blank_source("\n  # Comment.\n\t\n"));
+        assert!(!is_blank_source("# Comment.\ntype Foo { id: ID! }"));
+
+        let shared_data = scaffold(
+            vec![
+                (PathBuf::from("some_path/empty.gql"), String::new()),
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("# Comment.\ntype Foo { id: ID! }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        assert_eq!(shared_data.graph.lock().await.node_count(), 1);
+    }
+
+    #[async_std::test]
+    async fn check_orphans() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/Peer.gql"),
+                String::from("type Peer { id: String! }"),
+            )],
+            &[],
+        )
+        .await;
+
+        task::block_on(async {
+            let graph = shared_data.graph.lock().await;
+            assert_eq!(graph.node_count(), 1);
+            assert_eq!(graph.edge_count(), 0);
+        });
+
+        assert_eq!(orphans(&*shared_data.graph.lock().await, &[]).len(), 1);
+    }
+
+    #[async_std::test]
+    async fn check_unused_directives() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.gql"),
+                String::from(
+                    "directive @used on FIELD_DEFINITION directive @unused on OBJECT type Peer { id: String! @used }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let names = |entities: Vec<Entity>| {
+            entities
+                .into_iter()
+                .map(|entity| entity.id)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(names(unused_directives(&graph)), ["@unused"]);
+        assert_eq!(names(orphans(&graph, &[])), ["Peer"]);
+    }
+
+    #[async_std::test]
+    async fn check_orphans_with_schema() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/Schema.gql"),
+                String::from("schema { query: Query }"),
+            )],
+            &[],
+        )
+        .await;
+
+        task::block_on(async {
+            let graph = shared_data.graph.lock().await;
+            assert_eq!(graph.node_count(), 1);
+            assert_eq!(graph.edge_count(), 0);
+        });
+
+        assert_eq!(orphans(&*shared_data.graph.lock().await, &[]).len(), 0);
+    }
+
+    #[async_std::test]
+    async fn check_orphans_without_schema_but_with_query_mutation_subscription() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Query.gql"),
+                    String::from("type Query { foo(bar: String): String }"),
+                ),
+                (
+                    PathBuf::from("some_path/Mutation.gql"),
+                    String::from("type Mutation { foo(bar: String): String }"),
+                ),
+                (
+                    PathBuf::from("some_path/Subscription.gql"),
+                    String::from("type Subscription { foo(bar: String): String }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        task::block_on(async {
+            let graph = shared_data.graph.lock().await;
+            assert_eq!(graph.node_count(), 3);
+            assert_eq!(graph.edge_count(), 0);
+        });
+
+        assert_eq!(orphans(&*shared_data.graph.lock().await, &[]).len(), 0);
+    }
+
+    #[async_std::test]
+    async fn check_orphans_only() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Peer.gql"),
+                    String::from("type Peer { id: String! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Color.gql"),
+                    String::from("enum Color { RED }"),
+                ),
+                (
+                    PathBuf::from("some_path/Node.gql"),
+                    String::from("interface Node { id: ID! }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        assert_eq!(orphans(&*shared_data.graph.lock().await, &[]).len(), 3);
+
+        let orphans = orphans(
+            &*shared_data.graph.lock().await,
+            &[GraphQL::TypeDefinition(GraphQLType::Object)],
+        );
+
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans.first().unwrap().name, "Peer");
+    }
+
+    #[async_std::test]
+    async fn check_orphans_with_builtins() {
+        let state = State::with_options(AnalysisOptions::new().treat_builtins_as_nodes(true));
+        let shared_data = state.shared;
+
+        shared_data.files.lock().await.extend([
+            (
+                PathBuf::from("some_path/Peer.gql"),
+                String::from("type Peer { id: ID! }"),
+            ),
+            (
+                PathBuf::from("some_path/PeerExtension.gql"),
+                String::from("extend type Peer { age: Int }"),
+            ),
+        ]);
+
+        build_graph(&shared_data).await.unwrap();
+
+        let graph = shared_data.graph.lock().await;
+
+        assert!(graph.node_weights().any(|node| &*node.id == "Int"));
+        assert!(orphans(&graph, &[])
+            .iter()
+            .all(|entity| !entity.is_built_in()));
+    }
+
+    #[async_std::test]
+    async fn check_subgraph_between() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("schema.gql"),
+                String::from(
+                    "type Query { user: User post: Post } type User { profile: Profile role: Role } type Post { author: Profile } type Profile { avatar: Image } type Image { url: String } enum Role { ADMIN }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let between = |from: &str, to: &str| {
+            graph_snapshot(&subgraph_between(from, to, &graph, &node_indices))
+                .nodes
+                .into_iter()
+                .map(|node| node.id)
+                .collect::<Vec<String>>()
+        };
+
+        // Both paths through User and Post are kept, Role is unrelated.
+        assert_eq!(
+            between("Query", "Image"),
+            ["Query", "User", "Post", "Profile", "Image"]
+        );
+        assert_eq!(between("User", "Role"), ["User", "Role"]);
+        assert!(between("Image", "Query").is_empty());
+        assert!(between("Query", "Unknown").is_empty());
+        assert_eq!(
+            graph_snapshot(&subgraph_between("Query", "Image", &graph, &node_indices))
+                .edges
+                .len(),
+            5
+        );
+    }
+
+    #[async_std::test]
+    async fn check_select_nodes() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("schema.gql"),
+                String::from(
+                    "type User { profile: UserProfile } type UserProfile { avatar: Image } type Image { url: String } extend type Image { alt: String } type Unrelated { user: User }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let select = |globs: &[&str]| {
+            let globs = globs
+                .iter()
+                .map(|glob| glob_to_regex(glob).unwrap())
+                .collect::<Vec<Regex>>();
+
+            graph_snapshot(&select_nodes(&graph, &globs))
+        };
+        let selected = select(&["User*"]);
+
+        // The dependencies and their extensions are kept, not the dependents.
+        assert_eq!(
+            selected
+                .nodes
+                .iter()
+                .map(|node| node.id.as_str())
+                .collect::<Vec<&str>>(),
+            ["User", "UserProfile", "Image", "Image__1"]
+        );
+        assert_eq!(selected.edges.len(), 3);
+        assert_eq!(select(&["Use?"]).nodes.len(), 4);
+        assert!(select(&["Nothing*"]).nodes.is_empty());
+        assert!(glob_to_regex("User*").unwrap().is_match("UserProfile"));
+        assert!(!glob_to_regex("User").unwrap().is_match("UserProfile"));
+    }
+
+    #[async_std::test]
+    async fn check_empty_types() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("schema.gql"),
+                String::from(
+                    "type Foo type Bar extend type Bar { id: ID! } input Baz { id: ID! } enum Qux { A }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+
+        assert_eq!(
+            empty_types(&graph)
+                .into_iter()
+                .map(|entity| entity.id)
+                .collect::<Vec<String>>(),
+            vec!["Foo"]
+        );
+        assert!(get_node("Bar", &graph, &node_indices).unwrap().empty);
+        assert!(!get_node("Qux", &graph, &node_indices).unwrap().empty);
+    }
+
+    #[async_std::test]
+    async fn check_scattered_types() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("a.gql"),
+                    String::from("type Foo { id: ID! } type Bar { id: ID! } extend type Baz { a: ID }"),
+                ),
+                (
+                    PathBuf::from("b.gql"),
+                    String::from("extend type Foo { b: ID } extend type Bar { b: ID } extend type Baz { b: ID }"),
+                ),
+                (
+                    PathBuf::from("c.gql"),
+                    String::from("extend type Foo { c: ID } extend type Foo { d: ID }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let names = |threshold| {
+            scattered_types(&graph, threshold)
+                .into_iter()
+                .map(|scattered_type| (scattered_type.name, scattered_type.paths.len()))
+                .collect::<Vec<(String, usize)>>()
+        };
+
+        // The extensions without a base are grouped as well.
+        assert_eq!(
+            names(1),
+            [
+                (String::from("Bar"), 2),
+                (String::from("Baz"), 2),
+                (String::from("Foo"), 3)
+            ]
+        );
+        assert_eq!(names(2), [(String::from("Foo"), 3)]);
+        assert!(names(3).is_empty());
+    }
+
+    #[test]
+    fn check_parse_imports() {
+        let contents = r#"
+            # import User, Query.users from "./user.graphql"
+            #import * from '../schema.graphql'
+            # important: not an import
+            # import Post from ./post.graphql
+            type Query { users: [User] }
+            "#;
+
+        assert_eq!(
+            parse_imports(contents),
+            vec![
+                Import {
+                    names: vec![String::from("User"), String::from("Query.users")],
+                    from: String::from("./user.graphql"),
+                    line: 2,
+                },
+                Import {
+                    names: vec![String::from("*")],
+                    from: String::from("../schema.graphql"),
+                    line: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn check_normalize_path() {
+        assert_eq!(
+            normalize_path(Path::new("./foo/./bar/../baz.graphql")),
+            PathBuf::from("foo/baz.graphql")
+        );
+        assert_eq!(
+            normalize_path(Path::new("../foo.graphql")),
+            PathBuf::from("../foo.graphql")
+        );
+    }
+
+    #[async_std::test]
+    async fn check_bridges() {
+        let bridges_of = |schema: &'static str| async move {
+            let shared_data = scaffold(
+                vec![(PathBuf::from("schema.gql"), String::from(schema))],
+                &[],
+            )
+            .await;
+            let bridges = bridges(&*shared_data.graph.lock().await);
+
+            bridges
+                .into_iter()
+                .map(|(source, target)| format!("{} -- {}", source.id, target.id))
+                .collect::<Vec<String>>()
+        };
+
+        // Every edge of a chain is a bridge, none of a cycle.
+        assert_eq!(
+            bridges_of("type A { b: B } type B { c: C } type C { id: ID }").await,
+            ["B -- A", "C -- B"]
+        );
+        assert!(
+            bridges_of("type A { b: B } type B { c: C } type C { a: A }")
+                .await
+                .is_empty()
+        );
+        // Two types depending on each other are linked by two edges.
+        assert!(bridges_of("type A { b: B } type B { a: A }")
+            .await
+            .is_empty());
+    }
+
+    #[async_std::test]
+    async fn check_docs_coverage() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Documented.gql"),
+                    String::from(
+                        r#""A peer." type Peer { id: ID! } "A color." enum Color { RED }"#,
+                    ),
+                ),
+                (
+                    PathBuf::from("some_path/Undocumented.gql"),
+                    String::from("interface Node { id: ID! } extend type Peer { age: Int }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let coverage = docs_coverage(&*shared_data.graph.lock().await);
+
+        // Type extensions can't be described, hence not counted.
+        assert_eq!(coverage.documented, 2);
+        assert_eq!(coverage.total(), 3);
+        assert_eq!(coverage.undocumented[0].id, "Node");
+        assert_eq!(format!("{:.2}", coverage.percentage()), "66.67");
+
+        let empty = scaffold(vec![], &[]).await;
+
+        assert_eq!(
+            docs_coverage(&*empty.graph.lock().await).percentage(),
+            100.0
+        );
+    }
+
+    #[async_std::test]
+    async fn check_nodes_matching() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/User.gql"),
+                    String::from("type User { friends: UserConnection posts: PostConnection }"),
+                ),
+                (
+                    PathBuf::from("some_path/UserConnection.gql"),
+                    String::from("type UserConnection { nodes: [User] }"),
+                ),
+                (
+                    PathBuf::from("some_path/PostConnection.gql"),
+                    String::from("type PostConnection { count: Int }"),
+                ),
+                (
+                    PathBuf::from("some_path/ConnectionInput.gql"),
+                    String::from("input ConnectionInput { first: Int }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let connections =
+            nodes_matching(&Regex::new(".*Connection$").unwrap(), &graph, &node_indices);
+
+        assert_eq!(
+            connections
+                .iter()
+                .map(|entity| entity.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["PostConnection", "UserConnection"]
+        );
+        assert!(nodes_matching(&Regex::new("^Comment").unwrap(), &graph, &node_indices).is_empty());
+    }
+
+    #[async_std::test]
+    async fn check_neighbors() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { field: Bar }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("interface Bar { id: ID!}"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        task::block_on(async {
+            let graph = shared_data.graph.lock().await;
+            assert_eq!(graph.node_count(), 2);
+            assert_eq!(graph.edge_count(), 1);
+        });
+
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+
+        // Foo depends on Bar but is not a dependency.
+        let incoming = neighbors("Foo", &graph, &node_indices, Direction::Incoming);
+        let outgoing = neighbors("Foo", &graph, &node_indices, Direction::Outgoing);
+
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming.first().unwrap().name, "Bar");
+        assert_eq!(outgoing.len(), 0);
+
+        // Bar depends on nothing but is a dependency of Foo.
+        let incoming = neighbors("Bar", &graph, &node_indices, Direction::Incoming);
+        let outgoing = neighbors("Bar", &graph, &node_indices, Direction::Outgoing);
+
+        assert_eq!(incoming.len(), 0);
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing.first().unwrap().name, "Foo");
+    }
+
+    #[async_std::test]
+    async fn check_neighborhood() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.graphql"),
+                String::from(
+                    "type Query { bar: Bar } type Bar { foo: Foo baz: Baz } type Foo { bar: Bar } type Baz { id: ID }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+
+        let (incoming, outgoing) = neighborhood("Bar", &graph, &node_indices);
+        let names = |entities: &[Entity]| {
+            entities
+                .iter()
+                .map(|entity| entity.name.clone())
+                .collect::<Vec<String>>()
+        };
+
+        // Foo is on both sides because of the cycle, it is only incoming.
+        assert_eq!(names(&incoming), ["Baz", "Foo"]);
+        assert_eq!(names(&outgoing), ["Query"]);
+    }
+
+    #[async_std::test]
+    async fn check_sort_listed_by() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/b.graphql"),
+                    String::from("type Query { bar: Bar } type Bar { baz: Baz }"),
+                ),
+                (
+                    PathBuf::from("some_path/a.graphql"),
+                    String::from("enum Baz { A }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let sorted = |sort_by: SortBy| {
+            let mut entities = graph
+                .node_weights()
+                .map(|node| node.entity.clone())
+                .collect::<Vec<Entity>>();
+
+            sort_entities(&mut entities);
+            sort_listed_by(&mut entities, sort_by, &graph, &node_indices);
+
+            entities
+                .into_iter()
+                .map(|entity| entity.name)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(sorted(SortBy::Name), ["Bar", "Baz", "Query"]);
+        assert_eq!(sorted(SortBy::Path), ["Baz", "Query", "Bar"]);
+        assert_eq!(sorted(SortBy::Type), ["Baz", "Bar", "Query"]);
+        // Bar has two edges, the ties are sorted by name.
+        assert_eq!(sorted(SortBy::Degree), ["Bar", "Baz", "Query"]);
+    }
+
+    #[async_std::test]
+    async fn check_scalar_usage() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.graphql"),
+                String::from(
+                    "scalar Date scalar Unused directive @cache(until: Date) on FIELD_DEFINITION type Query { posts(since: Date!): [Post] } type Post { dates: [Date!]! } extend type Post { edited: Date } input Filter { before: Date }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let usages = scalar_usage(None, &graph);
+        fn coordinates(usage: &ScalarUsage) -> Vec<(&str, &str)> {
+            usage
+                .references
+                .iter()
+                .map(|reference| (reference.coordinate.as_str(), reference.value_type.as_str()))
+                .collect()
+        }
+
+        // Through arguments, a list, an extension and an input object.
+        assert_eq!(usages.len(), 2);
+        assert_eq!(
+            coordinates(&usages[0]),
+            [
+                ("@cache(until:)", "Date"),
+                ("Filter.before", "Date"),
+                ("Post.dates", "[Date!]!"),
+                ("Post.edited", "Date"),
+                ("Query.posts(since:)", "Date!")
+            ]
+        );
+        assert_eq!((usages[0].count, usages[0].types), (5, 4));
+        assert_eq!((usages[1].scalar.as_str(), usages[1].count), ("Unused", 0));
+        assert_eq!(scalar_usage(Some("Unused"), &graph).len(), 1);
+        assert!(scalar_usage(Some("Post"), &graph).is_empty());
+    }
+
+    #[async_std::test]
+    async fn check_input_usage() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.graphql"),
+                String::from(
+                    "type Query { a(filter: Filter): ID b(search: [Search!]): ID c(outer: Outer): ID } interface Node { d(filter: Filter!): ID } input Filter { id: ID } input Search { text: String } extend input Search { filter: Filter } input Outer { search: Search self: Outer }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let usages = input_usage("Filter", &graph)
+            .into_iter()
+            .map(|usage| (format!("{}({}:)", usage.field, usage.argument), usage.via))
+            .collect::<Vec<(String, Vec<String>)>>();
+
+        // Nested through an extension and two levels of input objects.
+        assert_eq!(
+            usages,
+            [
+                (String::from("Node.d(filter:)"), vec![]),
+                (String::from("Query.a(filter:)"), vec![]),
+                (
+                    String::from("Query.b(search:)"),
+                    vec![String::from("Search.filter")]
+                ),
+                (
+                    String::from("Query.c(outer:)"),
+                    vec![String::from("Outer.search"), String::from("Search.filter")]
+                ),
+            ]
+        );
+        assert!(input_usage("Query", &graph).is_empty());
+    }
+
+    #[async_std::test]
+    async fn check_missing_definitions() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { field: Woot, otherField: Why }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("interface Bar { id: What!}"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+
+        let missing_definitions = shared_data.missing_definitions.lock().await;
+
+        let (bar_missing_dependencies, foo_missing_dependencies) =
+            if missing_definitions.get(&NodeIndex::new(0)).unwrap().len() == 2 {
+                (
+                    missing_definitions.get(&NodeIndex::new(1)).unwrap(),
+                    missing_definitions.get(&NodeIndex::new(0)).unwrap(),
+                )
+            } else {
+                (
+                    missing_definitions.get(&NodeIndex::new(0)).unwrap(),
+                    missing_definitions.get(&NodeIndex::new(1)).unwrap(),
+                )
+            };
+
+        assert_eq!(
+            *foo_missing_dependencies,
+            vec![Name::from("Why"), Name::from("Woot")]
+        );
+        assert_eq!(*bar_missing_dependencies, vec![Name::from("What")]);
+    }
+
+    #[async_std::test]
+    async fn check_duplicate_dependencies_edges() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.gql"),
+                String::from(
+                    r#"
+                    type Bar { id: ID! }
+                    type Foo { a: Bar b: [Bar] c(bar: BarInput, baz: BarInput): Bar! }
+                    input BarInput { bar: Bar baz: Baz qux: Baz }
+                    extend type Foo { d: Bar e: Bar f: Baz }
+                    extend type Foo { g: Bar h: Baz }
+                    "#,
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let missing_definitions = shared_data.missing_definitions.lock().await;
+        let mut edges = graph
+            .edge_references()
+            .map(|edge| (&*graph[edge.source()].id, &*graph[edge.target()].id))
+            .collect::<Vec<(&str, &str)>>();
+
+        edges.sort_unstable();
+
+        // A single edge per pair of nodes, reversed for the extensions.
+        assert_eq!(
+            edges,
+            vec![
+                ("Bar", "BarInput"),
+                ("Bar", "Foo"),
+                ("BarInput", "Foo"),
+                ("Foo__1", "Bar"),
+                ("Foo__1", "Foo"),
+                ("Foo__2", "Bar"),
+                ("Foo__2", "Foo"),
+            ]
+        );
+        // A missing definition is reported once per node.
+        assert_eq!(missing_definitions.len(), 3);
+        assert!(missing_definitions
+            .values()
+            .all(|missing| missing == &vec![Name::from("Baz")]));
+    }
+
+    #[test]
+    fn check_field_granularity() {
+        let sources = [(
+            PathBuf::from("some_path/schema.gql"),
+            String::from(
+                "type Order { id: ID! address: Address } type Address { city: String } \
+                 enum Unit { KM } extend type Address { unit: Unit }",
+            ),
+        )];
+        let populate = |options: AnalysisOptions| {
+            let mut graph = SchemaGraph::new();
+            let mut missing_definitions = HashMap::new();
+
+            populate_graph(
+                sources
+                    .iter()
+                    .map(|(path, contents)| (path, contents.as_str())),
+                &options,
+                &mut graph,
+                &mut HashMap::new(),
+                &mut missing_definitions,
+                &mut HashMap::new(),
+            )
+            .unwrap();
+
+            let mut edges = graph
+                .edge_references()
+                .map(|edge| format!("{} -> {}", graph[edge.source()].id, graph[edge.target()].id))
+                .collect::<Vec<String>>();
+
+            edges.sort_unstable();
+
+            (graph, edges, missing_definitions)
+        };
+        let (graph, edges, missing_definitions) =
+            populate(AnalysisOptions::new().granularity(Granularity::Field));
+
+        assert!(missing_definitions.is_empty());
+
+        assert_eq!(
+            graph
+                .node_weights()
+                .filter(|node| node.entity.graphql == GraphQL::Field)
+                .map(|node| &*node.id)
+                .collect::<Vec<&str>>(),
+            vec!["Order.id", "Order.address", "Address.city", "Address.unit"]
+        );
+        // Fields sit between their type and the types they reference, the
+        // ones of an extension keeping the usual direction.
+        assert_eq!(
+            edges,
+            vec![
+                "Address -> Order.address",
+                "Address.city -> Address",
+                "Address.unit -> Address__1",
+                "Address__1 -> Address",
+                "Order.address -> Order",
+                "Order.id -> Order",
+                "Unit -> Address.unit",
+            ]
+        );
+
+        // The fields follow the filter of their type.
+        let (graph, edges, _) = populate(
+            AnalysisOptions::new()
+                .granularity(Granularity::Field)
+                .filter([
+                    GraphQL::TypeDefinition(GraphQLType::Enum),
+                    GraphQL::TypeExtension(GraphQLType::Object),
+                    GraphQL::Field,
+                ]),
+        );
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(
+            edges,
+            vec!["Address.unit -> Address__1", "Unit -> Address.unit"]
+        );
+
+        // Both granularities share a cache without mixing up their entities.
+        let cache_dir = tempfile::tempdir().unwrap();
+        let (_, type_edges, _) = populate(AnalysisOptions::new());
+
+        for _ in 0..2 {
+            let (_, cached_edges, _) = populate(
+                AnalysisOptions::new()
+                    .granularity(Granularity::Field)
+                    .cache_dir(cache_dir.path()),
+            );
+            let (_, cached_type_edges, _) =
+                populate(AnalysisOptions::new().cache_dir(cache_dir.path()));
+
+            assert_eq!(cached_edges.len(), 7);
+            assert_eq!(cached_type_edges, type_edges);
+        }
+    }
+
+    #[test]
+    fn check_federation() {
+        let sources = [(
+            PathBuf::from("some_path/schema.gql"),
+            String::from(
+                r#"
+                type Review @key(fields: "id product { upc }") {
+                  id: ID!
+                  rating: Rating
+                  author: User
+                  product: Product
+                  shipping: Shipping @requires(fields: "product { upc }")
+                }
+                type Shipping { cost: Int }
+                extend type Product @key(fields: "upc") {
+                  upc: String! @external
+                  manufacturer: Manufacturer @external
+                }
+                type User @key(fields: "id") { id: ID! @external }
+                "#,
+            ),
+        )];
+        let populate = |options: AnalysisOptions| {
+            let mut graph = SchemaGraph::new();
+            let mut missing_definitions = HashMap::new();
+
+            populate_graph(
+                sources
+                    .iter()
+                    .map(|(path, contents)| (path, contents.as_str())),
+                &options,
+                &mut graph,
+                &mut HashMap::new(),
+                &mut missing_definitions,
+                &mut HashMap::new(),
+            )
+            .unwrap();
+
+            (graph, missing_definitions)
+        };
+        let missing_names = |graph: &SchemaGraph, missing: &HashMap<NodeIndex, Vec<Name>>| {
+            let mut names = missing
+                .iter()
+                .flat_map(|(index, names)| {
+                    names
+                        .iter()
+                        .map(move |name| format!("{} {}", graph[*index].id, name))
+                })
+                .collect::<Vec<String>>();
+
+            names.sort_unstable();
+            names
+        };
+
+        let (graph, missing_definitions) = populate(AnalysisOptions::new());
+
+        assert!(missing_names(&graph, &missing_definitions).contains(&String::from("Review @key")));
+
+        let (graph, missing_definitions) = populate(AnalysisOptions::new().federation(true));
+        let (genuine, external) = split_external_definitions(&graph, &missing_definitions);
+
+        // The federation directives are known, the types only extended or
+        // only referenced by @external fields are owned by other subgraphs.
+        assert_eq!(missing_names(&graph, &genuine), vec!["Review Rating"]);
+        assert_eq!(
+            missing_names(&graph, &external),
+            vec![
+                "Product__1 Manufacturer",
+                "Product__1 Product",
+                "Review Product"
+            ]
+        );
+        assert!(graph.node_weights().all(|node| node.entity.is_federated()
+            == matches!(&*node.id, "Review" | "Product__1" | "User")));
+
+        // The field sets are dependencies, the @requires one of the field
+        // node with the field granularity.
+        let shipping_dependencies = |options: AnalysisOptions| {
+            let (graph, _) = populate(options.granularity(Granularity::Field));
+
+            graph
+                .node_weights()
+                .find(|node| &*node.id == "Review.shipping")
+                .unwrap()
+                .entity
+                .dependencies
+                .clone()
+        };
+
+        assert_eq!(
+            shipping_dependencies(AnalysisOptions::new().federation(true)),
+            vec![
+                Name::from("@requires"),
+                Name::from("Product"),
+                Name::from("Shipping")
+            ]
+        );
+
+        // Both variants share a cache without mixing up their entities.
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                shipping_dependencies(AnalysisOptions::new().cache_dir(cache_dir.path())),
+                vec![Name::from("@requires"), Name::from("Shipping")]
+            );
+            assert!(shipping_dependencies(
+                AnalysisOptions::new()
+                    .federation(true)
+                    .cache_dir(cache_dir.path())
+            )
+            .contains(&Name::from("Product")));
+        }
+    }
+
+    #[async_std::test]
+    async fn check_composition_issues() {
+        let subgraph = |name: &str, contents: &str| {
+            let name = name.to_owned();
+            let contents = contents.to_owned();
+
+            async move {
+                let state = State::with_options(AnalysisOptions::new().federation(true));
+                let shared_data = state.shared;
+
+                shared_data
+                    .files
+                    .lock()
+                    .await
+                    .insert(PathBuf::from(format!("{}/schema.gql", name)), contents);
+                build_graph(&shared_data).await.unwrap();
+
+                let graph = std::mem::take(&mut *shared_data.graph.lock().await);
+                let missing_definitions =
+                    std::mem::take(&mut *shared_data.missing_definitions.lock().await);
+
+                SubgraphSchema {
+                    name,
+                    graph,
+                    missing_definitions,
+                }
+            }
+        };
+        let subgraphs = vec![
+            subgraph(
+                "a",
+                "type Query { me: User } type User @key(fields: \"id\") { id: ID! } \
+                 type Money @shareable { amount: Int } type Address { city: String }",
+            )
+            .await,
+            subgraph(
+                "b",
+                "type Query { invoice: Invoice } type User @key(fields: \"id\") { id: ID! } \
+                 type Money @shareable { amount: Int } type Address { city: String } \
+                 type Invoice { user: User total: Money tax: Tax } extend type Address { zip: Zip }",
+            )
+            .await,
+        ];
+        let issues = composition_issues(&subgraphs)
+            .into_iter()
+            .map(|issue| match issue {
+                CompositionIssue::Conflict(name, entities) => format!(
+                    "{} in {}",
+                    name,
+                    entities
+                        .into_iter()
+                        .map(|(subgraph, _)| subgraph)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                CompositionIssue::Unsatisfied(subgraph, entity, name) => {
+                    format!("{} {} -> {}", subgraph, entity.id, name)
+                }
+            })
+            .collect::<Vec<String>>();
+
+        // The root operation types, the entities and the shareable types can
+        // be defined in several subgraphs.
+        assert_eq!(
+            issues,
+            vec!["Address in a, b", "b Invoice -> Tax", "b Address__1 -> Zip"]
+        );
+        assert!(composition_issues(&subgraphs[..1]).is_empty());
+    }
+
+    #[async_std::test]
+    async fn check_dead_references() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.gql"),
+                String::from(
+                    r#"
+                    schema { query: Root }
+                    type Root { shop: Shop }
+                    type Shop { orders(filter: OrderFilter): [Order!] }
+                    type Order { id: ID! }
+                    input OrderFilter { status: Status }
+                    enum Status { OPEN }
+                    type Query { legacy: Legacy }
+                    type Legacy { shop: Shop order: Order! }
+                    "#,
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let dead_references = dead_references(&graph, &node_indices)
+            .into_iter()
+            .map(|dead_reference| (dead_reference.field, dead_reference.target))
+            .collect::<Vec<(String, String)>>();
+
+        // The root schema takes precedence over the default Query type.
+        assert_eq!(
+            dead_references,
+            vec![(String::from("Query.legacy"), String::from("Legacy"))]
+        );
+    }
+
+    #[async_std::test]
+    async fn check_missing_definitions_by_name() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { field: Woot, otherField: Why }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("interface Bar { id: What!}"),
+                ),
+                (
+                    PathBuf::from("some_path/Baz.gql"),
+                    String::from("type Baz { field: Woot }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let missing_definitions_by_name = missing_definitions_by_name(
+            &*shared_data.graph.lock().await,
+            &*shared_data.missing_definitions.lock().await,
+        );
+
+        assert_eq!(missing_definitions_by_name.len(), 3);
+
+        let woot = missing_definitions_by_name
+            .get("Woot")
+            .unwrap()
+            .iter()
+            .map(|entity| entity.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(woot, vec!["Baz", "Foo"]);
+
+        let what = missing_definitions_by_name.get("What").unwrap();
+        assert_eq!(what.len(), 1);
+        assert_eq!(what.first().unwrap().name, "Bar");
+        assert_eq!(
+            what.first().unwrap().path,
+            PathBuf::from("some_path/Bar.gql")
+        );
+
+        let why = missing_definitions_by_name.get("Why").unwrap();
+        assert_eq!(why.len(), 1);
+        assert_eq!(why.first().unwrap().name, "Foo");
+    }
+
+    #[async_std::test]
+    async fn check_filtering() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { a: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("interface Bar { b: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Cow.gql"),
+                    String::from("input Cow { c: ID! }"),
+                ),
+            ],
+            &[
+                GraphQL::TypeDefinition(GraphQLType::Object),
+                GraphQL::TypeDefinition(GraphQLType::InputObject),
+            ],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+
+        // The nodes follow the order of the paths.
+        let selected_entities = graph
+            .node_indices()
+            .map(|index| &*graph.node_weight(index).unwrap().id)
+            .collect::<Vec<&str>>();
+        assert_eq!(selected_entities, vec!["Cow", "Foo"]);
+    }
+
+    #[async_std::test]
+    async fn check_depth_metrics() {
+        async fn metrics(schema: &str, roots: &[&str]) -> Vec<(String, usize)> {
+            let shared_data = scaffold(
+                vec![(PathBuf::from("some_path/schema.gql"), String::from(schema))],
+                &[],
+            )
+            .await;
+            let graph = shared_data.graph.lock().await;
+            let node_indices = shared_data.node_indices.lock().await;
+
+            depth_metrics(
+                &roots
+                    .iter()
+                    .map(|root| root.to_string())
+                    .collect::<Vec<String>>(),
+                &graph,
+                &node_indices,
+            )
+        }
+
+        assert_eq!(
+            metrics(
+                "type Query { a: A } type Mutation { c: C } type A { b: B } type B { c: C } enum C { X }",
+                &[]
+            )
+            .await,
+            [(String::from("Query"), 3), (String::from("Mutation"), 1)]
+        );
+        // The types of a cycle count as a single level.
+        assert_eq!(
+            metrics(
+                "type Query { a: A } type A { b: B } type B { a: A c: C } enum C { X }",
+                &["Query", "A"]
+            )
+            .await,
+            [(String::from("Query"), 2), (String::from("A"), 1)]
+        );
+        // The root operation types of the schema are used.
+        assert_eq!(
+            metrics(
+                "schema { query: Root } type Root { root: Root id: ID }",
+                &[]
+            )
+            .await,
+            [(String::from("Root"), 0)]
+        );
+    }
+
+    #[async_std::test]
+    async fn check_dependency_tree() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/User.gql"),
+                    String::from("type User { address: Address! friend: User }"),
+                ),
+                (
+                    PathBuf::from("some_path/Address.gql"),
+                    String::from("type Address { country: Country! owner: User }"),
+                ),
+                (
+                    PathBuf::from("some_path/Country.gql"),
+                    String::from("enum Country { FR JP }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+
+        assert_eq!(
+            dependency_tree("User", &graph, &node_indices, None, Direction::Incoming).unwrap(),
+            "User (Object)\n\
+             ├── Address (Object)\n\
+             │   ├── Country (Enum)\n\
+             │   └── User (Object) (cycle)\n\
+             └── User (Object) (cycle)\n"
+        );
+        assert_eq!(
+            dependency_tree("User", &graph, &node_indices, Some(1), Direction::Incoming).unwrap(),
+            "User (Object)\n\
+             ├── Address (Object)\n\
+             └── User (Object) (cycle)\n"
+        );
+        assert_eq!(
+            dependency_tree("Country", &graph, &node_indices, None, Direction::Outgoing).unwrap(),
+            "Country (Enum)\n\
+             └── Address (Object)\n    \
+             └── User (Object)\n        \
+             ├── Address (Object) (cycle)\n        \
+             └── User (Object) (cycle)\n"
+        );
+        assert!(
+            dependency_tree("Woot", &graph, &node_indices, None, Direction::Incoming).is_none()
+        );
+    }
+
+    #[test]
+    fn check_split_unknown_directives() {
+        let missing_definitions = HashMap::from([
+            (
+                NodeIndex::new(0),
+                vec![Name::from("@cached"), Name::from("Role")],
+            ),
+            (NodeIndex::new(1), vec![Name::from("@entity")]),
+            (NodeIndex::new(2), vec![Name::from("Post")]),
+        ]);
+        let (types, directives) = split_unknown_directives(&missing_definitions);
+
+        assert_eq!(
+            types,
+            HashMap::from([
+                (NodeIndex::new(0), vec![Name::from("Role")]),
+                (NodeIndex::new(2), vec![Name::from("Post")]),
+            ])
+        );
+        assert_eq!(
+            directives,
+            HashMap::from([
+                (NodeIndex::new(0), vec![Name::from("@cached")]),
+                (NodeIndex::new(1), vec![Name::from("@entity")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn check_validate_sources() {
+        let files = HashMap::from([
+            (PathBuf::from("b.gql"), String::from("type B { a A }")),
+            (PathBuf::from("a.gql"), String::from("type A {")),
+            (PathBuf::from("c.gql"), String::from("type C { id: ID }")),
+            (PathBuf::from("d.gql"), String::from("# blank")),
+            (PathBuf::from("e.gql"), String::from("query { c { id } }")),
+        ]);
+        let errors = validate_sources(&files);
+
+        // All the errors are reported, not only the first one.
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(path, _)| path.to_string_lossy().into_owned())
+                .collect::<Vec<String>>(),
+            ["a.gql", "b.gql"]
+        );
+        assert!(errors[1].1.contains("Parse error at 1:12"));
+    }
+
+    #[async_std::test]
+    async fn check_edge_weights() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("schema.gql"),
+                String::from(
+                    "type User { address: Address } type Address { id: ID } extend type User { role: Role } enum Role { A }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let edges = graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    graph[edge.source()].id.to_string(),
+                    graph[edge.target()].id.to_string(),
+                    edge.weight().dependency.to_string(),
+                )
+            })
+            .collect::<Vec<(String, String, String)>>();
+
+        // The edges of the extension are reversed, they keep the dependency.
+        assert_eq!(
+            edges,
+            [
+                (
+                    String::from("Address"),
+                    String::from("User"),
+                    String::from("Address")
+                ),
+                (
+                    String::from("User__1"),
+                    String::from("Role"),
+                    String::from("Role")
+                ),
+                (
+                    String::from("User__1"),
+                    String::from("User"),
+                    String::from("User")
+                ),
+            ]
+        );
+        assert_eq!(
+            graph
+                .find_edge(node_indices["Address"], node_indices["User"])
+                .map(|edge| graph[edge].clone()),
+            Some(Edge::new("Address"))
+        );
+    }
+
+    #[async_std::test]
+    async fn check_edge_reasons() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("schema.gql"),
+                String::from(
+                    "directive @auth(requires: Role) on OBJECT enum Role { A } type User @auth(requires: A) { id: ID } type Post @auth(requires: A) { role: Role }",
+                ),
+            )],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let edge = |from: &str, to: &str| {
+            graph
+                .find_edge(node_indices[from], node_indices[to])
+                .map(|edge| graph[edge].clone())
+        };
+
+        // The directive argument is the reason of the edge, labelling it.
+        assert_eq!(
+            edge("Role", "User"),
+            Some(Edge::new("Role").with_reason(String::from("@auth(requires:)")))
+        );
+        assert_eq!(
+            format!("{:?}", edge("Role", "User").unwrap()),
+            "Role via @auth(requires:)"
+        );
+        assert_eq!(edge("@auth", "User"), Some(Edge::new("@auth")));
+        // A field also referencing the dependency makes it a plain reference.
+        assert_eq!(edge("Role", "Post"), Some(Edge::new("Role")));
+    }
+
+    #[async_std::test]
+    async fn check_ownership() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("root/a/User.gql"),
+                    String::from("type User { role: Role } enum Role { ADMIN }"),
+                ),
+                (
+                    PathBuf::from("root/b/c/Post.gql"),
+                    String::from("type Post { author: User }"),
+                ),
+                (
+                    PathBuf::from("root/schema.gql"),
+                    String::from("type Query { posts: [Post] }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let owners = |depth| {
+            ownership(&graph, Path::new("root"), depth)
+                .into_iter()
+                .map(|ownership| ownership.owner)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(owners(1), [".", "a", "b"]);
+        assert_eq!(owners(2), [".", "a", "b/c"]);
+
+        let ownership = ownership(&graph, Path::new("root"), 1);
+
+        // The dependencies within an owner are left out.
+        assert_eq!(
+            ownership[1],
+            Ownership {
+                owner: String::from("a"),
+                types: BTreeSet::from([String::from("Role"), String::from("User")]),
+                dependencies: BTreeMap::new(),
+                dependents: BTreeMap::from([(
+                    String::from("b"),
+                    BTreeSet::from([String::from("User")])
+                )]),
+            }
+        );
+        assert_eq!(
+            ownership[0].dependencies,
+            BTreeMap::from([(String::from("b"), BTreeSet::from([String::from("Post")]))])
+        );
+    }
+
+    #[async_std::test]
+    async fn check_external_dependencies() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/User.gql"),
+                    String::from("type User { address: Address! role: Role } enum Role { ADMIN }"),
+                ),
+                (
+                    PathBuf::from("some_path/Address.gql"),
+                    String::from("type Address { country: Country! owner: User }"),
+                ),
+                (
+                    PathBuf::from("some_path/Country.gql"),
+                    String::from("enum Country { FR JP }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+        let graph = shared_data.graph.lock().await;
+        let node_indices = shared_data.node_indices.lock().await;
+        let names = |node| {
+            external_dependencies(node, &graph, &node_indices)
+                .into_iter()
+                .map(|entity| entity.name)
+                .collect::<Vec<String>>()
+        };
+
+        // Role is defined in the same file, Country is reached through Address.
+        assert_eq!(names("User"), ["Address", "Country"]);
+        // User and Role are reached back through the cycle.
+        assert_eq!(names("Address"), ["Country", "Role", "User"]);
+        assert!(names("Country").is_empty());
+        assert!(names("Woot").is_empty());
+    }
+
+    #[async_std::test]
+    async fn check_exclusion() {
+        let shared_data = scaffold_with_exclude(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { a: ID! @bar }"),
+                ),
+                (
+                    PathBuf::from("some_path/bar.gql"),
+                    String::from("directive @bar on FIELD_DEFINITION"),
+                ),
+                (
+                    PathBuf::from("some_path/Cow.gql"),
+                    String::from("input Cow { c: ID! }"),
+                ),
+            ],
+            &[
+                GraphQL::Directive,
+                GraphQL::TypeDefinition(GraphQLType::Object),
+            ],
+            &[GraphQL::Directive],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(graph
+            .node_weights()
+            .all(|node| node.entity.graphql != GraphQL::Directive));
+        assert_eq!(&*graph.node_weights().next().unwrap().id, "Foo");
+    }
+
+    #[async_std::test]
+    async fn check_parallel_walk() {
+        fn walk_sequentially(path: &std::path::Path, paths: &mut Vec<PathBuf>) {
+            for entry in std::fs::read_dir(path).unwrap() {
+                let inner_path = entry.unwrap().path();
+
+                if inner_path.is_dir() {
+                    walk_sequentially(&inner_path, paths);
+                } else if AnalysisOptions::new()
+                    .is_extension_allowed(inner_extension(Path::new(&inner_path)))
+                {
+                    paths.push(PathBuf::from(inner_path));
+                }
+            }
+        }
+
+        let mut expected = vec![];
+        walk_sequentially(std::path::Path::new("tests"), &mut expected);
+        expected.sort();
+        assert!(!expected.is_empty());
+
+        for jobs in [1, 2, 64] {
+            let (mut paths, _) = collect_paths(
+                PathBuf::from("tests"),
+                Arc::new(AnalysisOptions::new().jobs(jobs)),
+            )
+            .await
+            .unwrap();
+            paths.sort();
+
+            assert_eq!(paths, expected);
+        }
+    }
+
+    #[async_std::test]
+    async fn check_yaml_rendering() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { field: Bar }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("interface Bar { id: ID! }"),
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        let graph = shared_data.graph.lock().await;
+        let yaml = render_yaml(&graph).unwrap();
+        let value = serde_yaml::from_str::<serde_yaml::Value>(&yaml).unwrap();
+
+        assert_eq!(value["nodes"].as_sequence().unwrap().len(), 2);
+        assert_eq!(value["edges"].as_sequence().unwrap().len(), 1);
+        assert_eq!(value["edges"][0]["source"], "Bar");
+        assert_eq!(value["edges"][0]["target"], "Foo");
+
+        // JSON and YAML share the same representation.
+        let json = render_json(&graph).unwrap();
+        let json_value = serde_json::from_str::<serde_yaml::Value>(&json).unwrap();
+
+        assert_eq!(json_value, value);
+    }
+
+    #[async_std::test]
+    async fn check_collapse_extensions() {
+        let shared_data = scaffold(
+            vec![
+                (
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { qux: Qux }"),
+                ),
+                (
+                    PathBuf::from("some_path/FooExtension.gql"),
+                    String::from("extend type Foo { bar: Bar }"),
+                ),
+                (
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("type Bar { id: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Baz.gql"),
+                    String::from("type Baz { foo: Foo }"),
+                ),
+                (
+                    PathBuf::from("some_path/Qux.gql"),
+                    String::from("type Qux { id: ID! }"),
+                ),
             ],
             &[],
         )
         .await;
 
-        let dependencies = shared_data.dependencies.lock().await;
-
-        assert_eq!(dependencies.len(), 2);
-
-        // There no determined insertion order, assign dependencies lists based
-        // on respective assumed lengths.
-        let (current_owner_dependencies, current_house_dependencies, is_same_order) =
-            if dependencies.get(&NodeIndex::new(0)).unwrap().len() == house_dependencies.len() {
-                (
-                    dependencies.get(&NodeIndex::new(1)).unwrap(),
-                    dependencies.get(&NodeIndex::new(0)).unwrap(),
-                    true,
-                )
-            } else {
-                (
-                    dependencies.get(&NodeIndex::new(0)).unwrap(),
-                    dependencies.get(&NodeIndex::new(1)).unwrap(),
-                    false,
-                )
-            };
+        let graph = shared_data.graph.lock().await;
+        let collapsed = collapse_extensions(&graph);
 
-        // List of dependencies should match.
-        assert_eq!(current_owner_dependencies, &owner_dependencies);
-        assert_eq!(current_house_dependencies, &house_dependencies);
+        // The stored graph is left untouched.
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 4);
 
-        // Graph should contains 2 nodes and 1 edge.
-        let graph = shared_data.graph.lock().await;
-        assert_eq!(graph.node_count(), 2);
-        assert_eq!(graph.edge_count(), 1);
+        let foos = collapsed
+            .node_weights()
+            .filter(|node| node.entity.name == "Foo")
+            .collect::<Vec<&Node>>();
 
-        // Check house.
-        let house_node_index = if is_same_order {
-            NodeIndex::new(0)
-        } else {
-            NodeIndex::new(1)
-        };
-        let house = graph.node_weight(house_node_index).unwrap();
-        assert_eq!(house.id, String::from(house_name));
-        assert_eq!(house.entity.dependencies, house_dependencies);
+        assert_eq!(collapsed.node_count(), 4);
+        assert_eq!(foos.len(), 1);
         assert_eq!(
-            house.entity.graphql,
+            foos[0].entity.graphql,
             GraphQL::TypeDefinition(GraphQLType::Object)
         );
-        assert_eq!(house.entity.id, String::from(house_name));
-        assert_eq!(house.entity.name, String::from(house_name));
-        assert_eq!(house.entity.path, PathBuf::from(house_path));
-        // We need to parse and format the AST in order to get the same output!
         assert_eq!(
-            house.entity.raw.to_string(),
-            format!(
-                "{}",
-                parse_schema::<String>(house_contents).unwrap().to_owned()
-            )
+            foos[0].entity.dependencies,
+            vec![Name::from("Bar"), Name::from("Qux")]
         );
 
-        // Check owner.
-        let owner_node_index = if is_same_order {
-            NodeIndex::new(1)
-        } else {
-            NodeIndex::new(0)
-        };
-        let owner = graph.node_weight(owner_node_index).unwrap();
-        assert_eq!(owner.id, String::from(owner_name));
-        assert_eq!(owner.entity.dependencies, owner_dependencies);
+        // The union of the edges, the extension one keeping its direction.
+        let mut edges = collapsed
+            .edge_references()
+            .map(|edge| (&*collapsed[edge.source()].id, &*collapsed[edge.target()].id))
+            .collect::<Vec<(&str, &str)>>();
+        edges.sort_unstable();
+
+        assert_eq!(edges, vec![("Foo", "Bar"), ("Foo", "Baz"), ("Qux", "Foo")]);
         assert_eq!(
-            owner.entity.graphql,
-            GraphQL::TypeDefinition(GraphQLType::Object)
+            render_dot(&collapsed, &[dot::Config::EdgeNoLabel])
+                .matches("Foo (")
+                .count(),
+            1
         );
-        assert_eq!(owner.entity.id, String::from(owner_name));
-        assert_eq!(owner.entity.name, String::from(owner_name));
-        assert_eq!(owner.entity.path, PathBuf::from(owner_path));
-        // We need to parse and format the AST in order to get the same output!
+    }
+
+    #[test]
+    fn check_relative_path() {
         assert_eq!(
-            owner.entity.raw.to_string(),
-            format!(
-                "{}",
-                parse_schema::<String>(owner_contents).unwrap().to_owned()
-            )
+            relative_path(Path::new("/a/b/c.gql"), Path::new("/a")),
+            PathBuf::from("b/c.gql")
+        );
+        assert_eq!(
+            relative_path(Path::new("/a/b/c.gql"), Path::new("/a/d/e")),
+            PathBuf::from("../../b/c.gql")
+        );
+        assert_eq!(
+            relative_path(Path::new("/a"), Path::new("/a")),
+            PathBuf::from(".")
         );
-
-        // Check the edges. Owner should be directed to House, not the other
-        // way around!
-        assert!(graph.contains_edge(owner_node_index, house_node_index));
-        assert!(!graph.contains_edge(house_node_index, owner_node_index));
     }
 
-    #[async_std::test]
-    async fn check_orphans() {
-        let shared_data = scaffold(
-            vec![(
-                PathBuf::from("some_path/Peer.gql"),
-                String::from("type Peer { id: String! }"),
-            )],
-            &[],
-        )
-        .await;
-
-        task::block_on(async {
-            let graph = shared_data.graph.lock().await;
-            assert_eq!(graph.node_count(), 1);
-            assert_eq!(graph.edge_count(), 0);
-        });
-
-        assert_eq!(find_orphans(shared_data.graph).await.len(), 1);
+    #[test]
+    fn check_inner_extension() {
+        assert_eq!(inner_extension(Path::new("a/b.graphql")), "graphql");
+        assert_eq!(inner_extension(Path::new("a/b.gql.gz")), "gql");
+        assert_eq!(inner_extension(Path::new("a/b.gz")), "");
+        assert_eq!(inner_extension(Path::new("a/b")), "");
     }
 
     #[async_std::test]
-    async fn check_orphans_with_schema() {
+    async fn check_merge_extensions() {
         let shared_data = scaffold(
-            vec![(
-                PathBuf::from("some_path/Schema.gql"),
-                String::from("schema { query: Query }"),
-            )],
+            vec![
+                (
+                    PathBuf::from("some_path/User.gql"),
+                    String::from("type User { id: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/UserExtension.gql"),
+                    String::from("extend type User implements Node { name: String }"),
+                ),
+                (
+                    PathBuf::from("some_path/UserOtherExtension.gql"),
+                    String::from("extend type User @cached { name: String role: Role }"),
+                ),
+                (
+                    PathBuf::from("some_path/Role.gql"),
+                    String::from("enum Role { ADMIN } extend enum Role { VIEWER }"),
+                ),
+            ],
             &[],
         )
         .await;
 
-        task::block_on(async {
-            let graph = shared_data.graph.lock().await;
-            assert_eq!(graph.node_count(), 1);
-            assert_eq!(graph.edge_count(), 0);
-        });
+        let graph = shared_data.graph.lock().await;
+        let (merged, conflicts) = merge_extensions(&graph);
+        let merged_indices = index_nodes(&merged);
 
-        assert_eq!(find_orphans(shared_data.graph).await.len(), 0);
+        // The stored graph is left untouched.
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(merged.node_count(), 2);
+        assert_eq!(
+            get_node("User", &merged, &merged_indices).unwrap().raw,
+            "type User implements Node @cached {\n  id: ID!\n  name: String\n  role: Role\n}\n"
+        );
+        assert_eq!(
+            get_node("Role", &merged, &merged_indices).unwrap().raw,
+            "enum Role {\n  ADMIN\n  VIEWER\n}\n"
+        );
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict {
+                path: PathBuf::from("some_path/UserOtherExtension.gql"),
+                position: Some((1, 8)),
+                coordinate: String::from("User.name"),
+            }]
+        );
     }
 
     #[async_std::test]
-    async fn check_orphans_without_schema_but_with_query_mutation_subscription() {
+    async fn check_cytoscape_rendering() {
         let shared_data = scaffold(
             vec![
                 (
-                    PathBuf::from("some_path/Query.gql"),
-                    String::from("type Query { foo(bar: String): String }"),
+                    PathBuf::from("some_path/Foo.gql"),
+                    String::from("type Foo { field: Bar baz: Baz }"),
                 ),
                 (
-                    PathBuf::from("some_path/Mutation.gql"),
-                    String::from("type Mutation { foo(bar: String): String }"),
+                    PathBuf::from("some_path/Bar.gql"),
+                    String::from("interface Bar { id: ID! }"),
                 ),
                 (
-                    PathBuf::from("some_path/Subscription.gql"),
-                    String::from("type Subscription { foo(bar: String): String }"),
+                    PathBuf::from("some_path/Baz.gql"),
+                    String::from("enum Baz { A }"),
                 ),
             ],
             &[],
         )
         .await;
 
-        task::block_on(async {
-            let graph = shared_data.graph.lock().await;
-            assert_eq!(graph.node_count(), 3);
-            assert_eq!(graph.edge_count(), 0);
-        });
+        let cytoscape = render_cytoscape(&*shared_data.graph.lock().await).unwrap();
+        let value = serde_json::from_str::<serde_json::Value>(&cytoscape).unwrap();
+        let nodes = value["elements"]["nodes"].as_array().unwrap();
+        let edges = value["elements"]["edges"].as_array().unwrap();
 
-        assert_eq!(find_orphans(shared_data.graph).await.len(), 0);
+        assert_eq!(value.as_object().unwrap().len(), 1);
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 2);
+        assert!(nodes.iter().any(|node| node["data"]
+            == serde_json::json!({
+                "id": "Baz",
+                "name": "Baz",
+                "graphqlType": "enum",
+                "path": "some_path/Baz.gql",
+            })));
+        assert_eq!(edges[0]["data"]["id"], "e0");
+        assert_eq!(edges[1]["data"]["id"], "e1");
+        assert!(edges.iter().all(|edge| edge["data"]["target"] == "Foo"));
     }
 
     #[async_std::test]
-    async fn check_neighbors() {
+    async fn check_jsonl_rendering() {
         let shared_data = scaffold(
             vec![
                 (
                     PathBuf::from("some_path/Foo.gql"),
-                    String::from("type Foo { field: Bar }"),
+                    String::from("type Foo { field: Bar baz: Baz }"),
                 ),
                 (
                     PathBuf::from("some_path/Bar.gql"),
-                    String::from("interface Bar { id: ID!}"),
+                    String::from("interface Bar { id: ID! }"),
+                ),
+                (
+                    PathBuf::from("some_path/Baz.gql"),
+                    String::from("enum Baz { A }"),
                 ),
             ],
             &[],
         )
         .await;
 
-        task::block_on(async {
-            let graph = shared_data.graph.lock().await;
-            assert_eq!(graph.node_count(), 2);
-            assert_eq!(graph.edge_count(), 1);
-        });
+        let jsonl = render_jsonl(&*shared_data.graph.lock().await).unwrap();
+        let lines = jsonl
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<serde_json::Value>>();
 
-        // Foo depends on Bar but is not a dependency.
-        let incoming = find_neighbors("Foo", shared_data.graph.clone(), Direction::Incoming).await;
-        let outgoing = find_neighbors("Foo", shared_data.graph.clone(), Direction::Outgoing).await;
+        // One line per node and per edge.
+        assert_eq!(lines.len(), 3 + 2);
+        assert!(lines[..3].iter().all(|line| line["type"] == "node"));
+        assert!(lines[3..].iter().all(|line| line["type"] == "edge"));
+        assert!(lines[3..].iter().all(|line| line["target"] == "Foo"));
+        assert!(jsonl.ends_with('\n'));
+    }
 
-        assert_eq!(incoming.len(), 1);
-        assert_eq!(incoming.first().unwrap().name, "Bar");
-        assert_eq!(outgoing.len(), 0);
+    /// Check that the tags of an XML document are balanced.
+    fn is_well_formed(xml: &str) -> bool {
+        let mut stack = vec![];
 
-        // Bar depends on nothing but is a dependency of Foo.
-        let incoming = find_neighbors("Bar", shared_data.graph.clone(), Direction::Incoming).await;
-        let outgoing = find_neighbors("Bar", shared_data.graph.clone(), Direction::Outgoing).await;
+        for tag in xml.split('<').skip(1) {
+            let tag = &tag[..tag.find('>').unwrap()];
 
-        assert_eq!(incoming.len(), 0);
-        assert_eq!(outgoing.len(), 1);
-        assert_eq!(outgoing.first().unwrap().name, "Foo");
+            if tag.starts_with('?') || tag.ends_with('/') {
+                continue;
+            }
+
+            match tag.strip_prefix('/') {
+                Some(name) => {
+                    if stack.pop() != Some(name) {
+                        return false;
+                    }
+                }
+                None => stack.push(tag.split_whitespace().next().unwrap()),
+            }
+        }
+
+        stack.is_empty()
     }
 
     #[async_std::test]
-    async fn check_missing_definitions() {
+    async fn check_render_d2() {
         let shared_data = scaffold(
-            vec![
-                (
-                    PathBuf::from("some_path/Foo.gql"),
-                    String::from("type Foo { field: Woot, otherField: Why }"),
-                ),
-                (
-                    PathBuf::from("some_path/Bar.gql"),
-                    String::from("interface Bar { id: What!}"),
+            vec![(
+                PathBuf::from("some_path/schema.gql"),
+                String::from(
+                    "type Foo { field: Bar } interface Bar { id: ID! @foo } directive @foo on FIELD_DEFINITION",
                 ),
-            ],
+            )],
+            &[],
+        )
+        .await;
+
+        let d2 = render_d2(&*shared_data.graph.lock().await);
+
+        assert!(d2.contains("Foo: { shape: class }\n"));
+        assert!(d2.contains("\"@foo\": { shape: class }\n"));
+        assert!(d2.contains("Bar -> Foo\n"));
+        assert!(d2.contains("\"@foo\" -> Bar\n"));
+        assert_eq!(escape_d2(r#"a"b\c"#), r#""a\"b\\c""#);
+    }
+
+    #[async_std::test]
+    async fn check_render_gvjson() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.gql"),
+                String::from("type Foo { field: Bar } interface Bar { id: ID! }"),
+            )],
             &[],
         )
         .await;
 
+        let gvjson = serde_json::from_str::<serde_json::Value>(
+            &render_gvjson(&*shared_data.graph.lock().await).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(gvjson["directed"], true);
+        assert_eq!(
+            gvjson["objects"],
+            serde_json::json!([
+                { "_gvid": 0, "name": "Foo", "label": "Foo", "graphql": "object" },
+                { "_gvid": 1, "name": "Bar", "label": "Bar", "graphql": "interface" },
+            ])
+        );
+        assert_eq!(
+            gvjson["edges"],
+            serde_json::json!([{ "_gvid": 0, "tail": 1, "head": 0 }])
+        );
+    }
+
+    #[async_std::test]
+    async fn check_render_dot_wrapped() {
+        let fields = (0..12)
+            .map(|index| format!("f{}: Dependency{}", index, index))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/schema.gql"),
+                format!("type Foo {{ {} }}", fields),
+            )],
+            &[],
+        )
+        .await;
         let graph = shared_data.graph.lock().await;
-        assert_eq!(graph.node_count(), 2);
-        assert_eq!(graph.edge_count(), 0);
+        let label = |dot: String| {
+            dot.lines()
+                .find(|line| line.contains("Foo (Object)"))
+                .unwrap()
+                .trim()
+                .to_owned()
+        };
 
-        let missing_definitions = shared_data.missing_definitions.lock().await;
+        assert_eq!(
+            label(render_dot_wrapped(&graph, &[], 80)),
+            "0 [ label = \"Foo (Object)\\l\\l[Dependency0, Dependency1, Dependency10, Dependency11, Dependency2,\\l \
+             Dependency3, Dependency4, Dependency5, Dependency6, Dependency7, Dependency8,\\l \
+             Dependency9]\" ]"
+        );
+        assert_eq!(
+            label(render_dot_wrapped(&graph, &[], 0)),
+            "0 [ label = \"Foo (Object)\\l\\l[Dependency0, Dependency1, Dependency10, Dependency11, \
+             Dependency2, Dependency3, Dependency4, Dependency5, Dependency6, Dependency7, \
+             Dependency8, Dependency9]\" ]"
+        );
+        // The default width is the one of the Debug implementation.
+        assert_eq!(
+            render_dot(&graph, &[]),
+            format!("{:?}", Dot::with_config(&*graph, &[]))
+        );
+    }
 
-        let (bar_missing_dependencies, foo_missing_dependencies) =
-            if missing_definitions.get(&NodeIndex::new(0)).unwrap().len() == 2 {
-                (
-                    missing_definitions.get(&NodeIndex::new(1)).unwrap(),
-                    missing_definitions.get(&NodeIndex::new(0)).unwrap(),
-                )
-            } else {
-                (
-                    missing_definitions.get(&NodeIndex::new(0)).unwrap(),
-                    missing_definitions.get(&NodeIndex::new(1)).unwrap(),
-                )
-            };
+    #[async_std::test]
+    async fn check_render_dot_layered() {
+        let render = |schema: &'static str| async move {
+            let shared_data = scaffold(
+                vec![(PathBuf::from("some_path/schema.gql"), String::from(schema))],
+                &[],
+            )
+            .await;
+            let graph = shared_data.graph.lock().await;
+
+            (
+                render_dot_layered(&graph, &[dot::Config::EdgeNoLabel], 80),
+                render_dot_wrapped(&graph, &[dot::Config::EdgeNoLabel], 80),
+            )
+        };
+
+        // Foo depends on Bar and Baz, Bar on Baz, Qux on nothing.
+        let (layered, _) = render(
+            "type Foo { bar: Bar baz: Baz } type Bar { baz: Baz } enum Baz { A } enum Qux { B }",
+        )
+        .await;
+        let ranks = layered
+            .lines()
+            .filter(|line| line.contains("rank=same"))
+            .map(str::trim)
+            .collect::<Vec<&str>>();
 
         assert_eq!(
-            *foo_missing_dependencies,
-            vec![String::from("Why"), String::from("Woot")]
+            ranks,
+            vec![
+                "{ rank=same; 2; 3; }",
+                "{ rank=same; 1; }",
+                "{ rank=same; 0; }"
+            ]
         );
-        assert_eq!(*bar_missing_dependencies, vec![String::from("What")]);
+        assert!(layered.ends_with("}\n"));
+        // The dependencies are at the bottom.
+        assert!(layered.contains("\n    rankdir=BT\n"));
+
+        // A cyclic graph is rendered unranked.
+        let (layered, unranked) = render("type Foo { bar: Bar } type Bar { foo: Foo }").await;
+        assert_eq!(layered, unranked);
     }
 
     #[async_std::test]
-    async fn check_filtering() {
+    async fn check_render_html() {
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/</script>.gql"),
+                String::from("type Foo { field: Bar } interface Bar { id: ID! }"),
+            )],
+            &[],
+        )
+        .await;
+
+        let html = render_html(&*shared_data.graph.lock().await).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("2 nodes, 1 edge<"));
+        assert!(html.contains(r#""id":"Foo""#));
+        assert!(!html.contains("__CRAFTQL_"));
+        assert_eq!(html.matches("</script>").count(), 1);
+
+        let shared_data = scaffold(
+            vec![(
+                PathBuf::from("some_path/Foo.gql"),
+                String::from("type Foo { id: ID! }"),
+            )],
+            &[],
+        )
+        .await;
+        let html = render_html(&*shared_data.graph.lock().await).unwrap();
+
+        assert!(html.contains("1 node, 0 edges<"));
+    }
+
+    #[async_std::test]
+    async fn check_gexf_rendering() {
         let shared_data = scaffold(
             vec![
                 (
                     PathBuf::from("some_path/Foo.gql"),
-                    String::from("type Foo { a: ID! }"),
+                    String::from("type Foo { field: Bar }"),
                 ),
                 (
                     PathBuf::from("some_path/Bar.gql"),
-                    String::from("interface Bar { b: ID! }"),
+                    String::from("interface Bar { id: ID! }"),
                 ),
                 (
-                    PathBuf::from("some_path/Cow.gql"),
-                    String::from("input Cow { c: ID! }"),
+                    PathBuf::from("some_path/Foo&Bar.gql"),
+                    String::from("extend type Foo { id: ID! }"),
                 ),
             ],
-            &[
-                GraphQL::TypeDefinition(GraphQLType::Object),
-                GraphQL::TypeDefinition(GraphQLType::InputObject),
-            ],
+            &[],
         )
         .await;
 
-        let graph = shared_data.graph.lock().await;
-        assert_eq!(graph.node_count(), 2);
-        assert_eq!(graph.edge_count(), 0);
+        let gexf = render_gexf(&*shared_data.graph.lock().await);
 
-        let mut selected_entities = graph
-            .node_indices()
-            .map(|index| &graph.node_weight(index).unwrap().id)
-            .collect::<Vec<&String>>();
-        // Sort the entities as the order is not as there no determined insertion order.
-        selected_entities.sort();
-        assert_eq!(selected_entities, vec!["Cow", "Foo"]);
+        assert!(is_well_formed(&gexf));
+        assert_eq!(gexf.matches("<node ").count(), 3);
+        assert_eq!(gexf.matches("<edge ").count(), 2);
+        assert!(gexf.contains(r#"defaultedgetype="directed""#));
+        assert!(gexf.contains(r#"<attvalue for="0" value="object_extension"/>"#));
+        assert!(gexf.contains(r#"<attvalue for="1" value="some_path/Foo&amp;Bar.gql"/>"#));
+        assert_eq!(
+            escape_xml(r#"<"a" & 'b'>"#),
+            "&lt;&quot;a&quot; &amp; &apos;b&apos;&gt;"
+        );
     }
 }
@@ -2,15 +2,29 @@ extern crate craftql;
 
 use anyhow::Result;
 use async_std::{fs, path::PathBuf};
-use craftql::{state::State, utils::get_files};
+use craftql::{
+    config::BUILT_IN_SCALARS_PATH,
+    error::Error,
+    schema::{Schema, SchemaOptions},
+    state::{
+        AnalysisOptions, Data, Entity, FileEdge, FileSummary, GraphQL, GraphQLType, Name, State,
+        WalkSummary,
+    },
+    utils::{
+        build_graph, collect_files, file_graph, file_graph_snapshot, get_node, graph_snapshot,
+        missing_definitions_report, orphans, render_dot, summarize_by_file,
+    },
+};
+use petgraph::{dot, Direction};
 
 #[async_std::test]
+#[allow(deprecated)]
 async fn check_get_files() -> Result<()> {
     let state = State::default();
     let shared_data = state.shared;
     let shared_data_cloned = shared_data.clone();
 
-    get_files(PathBuf::from("./tests/fixtures"), shared_data.files).await?;
+    craftql::utils::get_files(PathBuf::from("./tests/fixtures"), shared_data.files).await?;
 
     let files = shared_data_cloned.files.lock().await;
 
@@ -25,3 +39,2841 @@ async fn check_get_files() -> Result<()> {
 
     Ok(())
 }
+
+#[async_std::test]
+async fn check_collect_files() -> Result<()> {
+    let state = State::default();
+    let shared_data = state.shared;
+
+    let summary = collect_files(PathBuf::from("./tests/fixtures"), &shared_data).await?;
+
+    let files = shared_data.files.lock().await;
+
+    assert_eq!(files.len(), 20);
+    assert_eq!(
+        summary,
+        WalkSummary {
+            read: 20,
+            skipped: vec![PathBuf::from("./tests/fixtures/c.txt")],
+        }
+    );
+
+    let contents = fs::read_to_string("./tests/fixtures/Types/Enums/Episode.gql").await?;
+
+    assert_eq!(
+        files.get(&PathBuf::from("./tests/fixtures/Types/Enums/Episode.gql")),
+        Some(&contents)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn check_walk_summary() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["./tests/fixtures", "-v", "--count-only"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Read 20 file(s), skipped 1\nSkipped ./tests/fixtures/c.txt\n"
+    );
+    // --verbose only applies to the missing definitions.
+    assert_eq!(run_craftql(&["./tests/fixtures", "--verbose"]).1, Some(1));
+}
+
+#[async_std::test]
+#[allow(deprecated)]
+async fn check_collect_empty_directory() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = PathBuf::from(dir.path());
+    let is_empty_input = |result: Result<()>| {
+        matches!(
+            result.unwrap_err().downcast_ref::<Error>(),
+            Some(Error::EmptyInput(empty)) if *empty == path
+        )
+    };
+
+    // Any mode walking the files reports the empty input.
+    assert!(is_empty_input(
+        collect_files(path.clone(), &State::default().shared)
+            .await
+            .map(|_| ())
+    ));
+    assert!(is_empty_input(
+        craftql::utils::get_files(path.clone(), State::default().shared.files).await
+    ));
+    assert!(is_empty_input(
+        Schema::from_path(path.clone(), &SchemaOptions::new())
+            .await
+            .map(|_| ())
+    ));
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_schema_from_path() -> Result<()> {
+    let schema = Schema::from_path("tests/fixtures", &SchemaOptions::new()).await?;
+
+    assert_eq!(schema.entities().count(), 28);
+    assert_eq!(
+        schema.dependencies_of("Character"),
+        Some(vec![
+            "@deprecated",
+            "@test",
+            "Boolean",
+            "Character",
+            "Color",
+            "Episode",
+            "FriendsConnection",
+            "ID",
+            "Int",
+            "String"
+        ])
+    );
+
+    let schema = Schema::from_path(
+        "tests/fixtures",
+        &SchemaOptions::new().filter([GraphQL::TypeDefinition(GraphQLType::Enum)]),
+    )
+    .await?;
+
+    assert!(schema
+        .entities()
+        .all(|entity| entity.graphql == GraphQL::TypeDefinition(GraphQLType::Enum)));
+    assert!(
+        Schema::from_path("tests/fixtures", &SchemaOptions::new().extensions(["txt"]))
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_analysis_options() -> Result<()> {
+    let options = AnalysisOptions::new()
+        .extensions(["gql"])
+        .exclude_paths(["Enums"])
+        .filter([
+            GraphQL::TypeDefinition(GraphQLType::Object),
+            GraphQL::TypeDefinition(GraphQLType::Scalar),
+        ])
+        .treat_builtins_as_nodes(true);
+    let shared_data = State::with_options(options).shared;
+
+    collect_files(PathBuf::from("./tests/fixtures"), &shared_data).await?;
+
+    // Only the .gql files outside of the Enums directory are collected.
+    let mut paths = shared_data
+        .files
+        .lock()
+        .await
+        .keys()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<Vec<String>>();
+    paths.sort_unstable();
+    assert!(!paths.is_empty());
+    assert!(paths.iter().all(|path| path.ends_with(".gql")));
+    assert!(!paths.iter().any(|path| path.contains("/Enums/")));
+
+    build_graph(&shared_data).await?;
+
+    let graph = shared_data.graph.lock().await;
+
+    // Objects and the referenced built-in scalars are kept.
+    assert!(graph.node_weights().all(|node| matches!(
+        (
+            &node.entity.graphql,
+            node.entity.path.to_string_lossy().as_ref()
+        ),
+        (GraphQL::TypeDefinition(GraphQLType::Object), _)
+            | (
+                GraphQL::TypeDefinition(GraphQLType::Scalar),
+                BUILT_IN_SCALARS_PATH
+            )
+    )));
+    assert!(graph.node_weights().any(|node| &*node.id == "Orphan"));
+
+    let id = get_node("ID", &graph, &*shared_data.node_indices.lock().await).unwrap();
+    assert_eq!(id.path, PathBuf::from(BUILT_IN_SCALARS_PATH));
+    assert!(orphans(&graph, &[])
+        .iter()
+        .all(|entity| entity.graphql != GraphQL::TypeDefinition(GraphQLType::Scalar)));
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_bounded_file_reading() -> Result<()> {
+    let collect = |options: AnalysisOptions| async move {
+        let shared_data = State::with_options(options).shared;
+
+        collect_files(PathBuf::from("./tests/fixtures"), &shared_data).await?;
+
+        let files = shared_data.files.lock().await.clone();
+
+        Ok::<_, anyhow::Error>(files)
+    };
+    let files = collect(AnalysisOptions::new()).await?;
+
+    // Reading the nested files one by one gives the same map.
+    assert_eq!(files.len(), 20);
+    assert_eq!(collect(AnalysisOptions::new().jobs(1)).await?, files);
+
+    // Errors name the file.
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("invalid.gql");
+
+    std::fs::write(&path, [0xff, 0xfe])?;
+
+    let error = collect_files(PathBuf::from(dir.path()), &State::new().shared)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        format!("Failed to read {}", path.to_string_lossy())
+    );
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_cache() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let cache_dir = tempfile::tempdir()?;
+
+    std::fs::write(dir.path().join("a.gql"), "type Foo { bar: Bar }")?;
+    std::fs::write(dir.path().join("b.gql"), "type Bar { id: ID! }")?;
+    std::fs::write(dir.path().join("c.gql"), "enum Baz { A }")?;
+
+    // Return the (hits, misses) of a run and its graph snapshot.
+    let run = |options: AnalysisOptions| async {
+        let shared_data = State::with_options(options.cache_dir(cache_dir.path())).shared;
+
+        collect_files(PathBuf::from(dir.path()), &shared_data).await?;
+        build_graph(&shared_data).await?;
+
+        let cache = shared_data.options.cache().unwrap();
+        let snapshot = graph_snapshot(&*shared_data.graph.lock().await);
+
+        Ok::<_, anyhow::Error>(((cache.hits(), cache.misses()), snapshot))
+    };
+
+    let (stats, first) = run(AnalysisOptions::new()).await?;
+    assert_eq!(stats, (0, 3));
+
+    let (stats, second) = run(AnalysisOptions::new()).await?;
+    assert_eq!(stats, (3, 0));
+    assert_eq!(second, first);
+
+    // The cache doesn't depend on the filter.
+    let (stats, filtered) =
+        run(AnalysisOptions::new().filter([GraphQL::TypeDefinition(GraphQLType::Enum)])).await?;
+    assert_eq!(stats, (3, 0));
+    assert_eq!(filtered.nodes.len(), 1);
+
+    // Only the mutated file is extracted again.
+    std::fs::write(dir.path().join("c.gql"), "enum Baz { A B }")?;
+
+    let (stats, _) = run(AnalysisOptions::new()).await?;
+    assert_eq!(stats, (2, 1));
+
+    // Invalid entries are extracted again.
+    for entry in std::fs::read_dir(cache_dir.path())? {
+        std::fs::write(entry?.path(), "{}")?;
+    }
+
+    let (stats, third) = run(AnalysisOptions::new()).await?;
+    assert_eq!(stats, (0, 3));
+    assert_eq!(third.nodes.len(), 3);
+
+    Ok(())
+}
+
+async fn populate_fixtures() -> Result<Data> {
+    let state = State::default();
+    let shared_data = state.shared;
+
+    collect_files(PathBuf::from("./tests/fixtures"), &shared_data).await?;
+    build_graph(&shared_data).await?;
+
+    Ok(shared_data)
+}
+
+#[async_std::test]
+async fn check_identical_graphs() -> Result<()> {
+    let shared_data = populate_fixtures().await?;
+    let sources = shared_data
+        .files
+        .lock()
+        .await
+        .iter()
+        .map(|(path, contents)| (path.clone(), contents.clone()))
+        .collect::<Vec<(PathBuf, String)>>();
+    let schema = Schema::from_sources(sources)?;
+
+    // The insertion order follows the sorted paths, whatever the files order.
+    assert_eq!(
+        graph_snapshot(&*shared_data.graph.lock().await),
+        graph_snapshot(schema.graph())
+    );
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_pure_functions() -> Result<()> {
+    let shared_data = populate_fixtures().await?;
+    let graph = shared_data.graph.lock().await;
+    let missing_definitions = shared_data.missing_definitions.lock().await;
+    let node_indices = shared_data.node_indices.lock().await;
+
+    let orphans = orphans(&graph, &[]);
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans.first().unwrap().name, "Orphan");
+
+    let mut reports = missing_definitions_report(&graph, &missing_definitions)
+        .into_iter()
+        .map(|report| {
+            (
+                report.entity.name,
+                report.missing.iter().map(|name| name.to_string()).collect(),
+            )
+        })
+        .collect::<Vec<(String, Vec<String>)>>();
+    reports.sort();
+    assert_eq!(
+        reports,
+        vec![
+            (String::from("Character"), vec![String::from("Color")]),
+            (
+                String::from("SearchResult"),
+                vec![String::from("Ewok"), String::from("Gungan")]
+            ),
+        ]
+    );
+
+    let character = get_node("Character", &graph, &node_indices).unwrap();
+    assert_eq!(
+        character.path,
+        PathBuf::from("./tests/fixtures/Types/Interfaces/Character.graphql")
+    );
+    // The leading comment is skipped.
+    assert_eq!(character.position, Some((2, 1)));
+    assert!(character
+        .to_string()
+        .starts_with("\n# ./tests/fixtures/Types/Interfaces/Character.graphql:2:1\n"));
+    assert!(get_node("Woot", &graph, &node_indices).is_none());
+
+    let dot = render_dot(&graph, &[dot::Config::EdgeNoLabel]);
+    assert!(dot.starts_with("digraph {"));
+    assert_eq!(dot.matches("label = ").count(), graph.node_count());
+
+    Ok(())
+}
+
+fn run_craftql(args: &[&str]) -> (String, Option<i32>) {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(args)
+        .output()
+        .unwrap();
+
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        output.status.code(),
+    )
+}
+
+#[test]
+fn check_count_only() {
+    let count_only =
+        |args: &[&str]| run_craftql(&[&["./tests/fixtures"][..], args, &["--count-only"]].concat());
+
+    assert_eq!(count_only(&["--orphans"]), (String::from("1\n"), Some(2)));
+    assert_eq!(
+        count_only(&["--jobs", "1"]),
+        (String::from("28\n"), Some(0))
+    );
+    assert_eq!(
+        count_only(&["--filter", "object"]),
+        (String::from("11\n"), Some(0))
+    );
+    assert_eq!(
+        count_only(&["--missing-definitions"]),
+        (String::from("2\n"), Some(2))
+    );
+}
+
+#[test]
+fn check_raw_dependent_modes() {
+    let craftql = |args: &[&str]| run_craftql(args).0;
+
+    // The raw-free flags, e.g. --count-only or --format, don't strip the raw
+    // SDL read by the modes.
+    for (args, expected) in [
+        (
+            &["tests/fixtures", "--node", "Orphan", "--raw"][..],
+            "type Orphan {\n  id: ID!\n}\n",
+        ),
+        (
+            &["tests/fixtures", "--orphans", "--format", "json"],
+            "type Orphan {",
+        ),
+        (
+            &["tests/fixtures", "--nodes-matching", "^Orph"],
+            "type Orphan {",
+        ),
+        (
+            &["tests/fixtures", "--entity-json", "Orphan"],
+            "type Orphan {",
+        ),
+        (&["tests/enum_values", "--lint", "--count-only"], "4\n"),
+        (&["tests/budget", "--budget", "--count-only"], "5\n"),
+        (
+            &["tests/dead_references", "--dead-references", "--count-only"],
+            "3\n",
+        ),
+        (
+            &[
+                "tests/scalar_usage",
+                "--scalar-usage",
+                "JSON",
+                "--format",
+                "json",
+            ],
+            "Post.metadata",
+        ),
+        (
+            &["tests/input_usage", "--input-usage", "OrderFilterInput"],
+            "Query.orders(filter:)",
+        ),
+        (
+            &["tests/extensions", "--merge-extensions", "--node", "User"],
+            "email: String",
+        ),
+        (
+            &[
+                "tests/operations",
+                "--operations",
+                "tests/operations",
+                "--unused-fields",
+                "--count-only",
+            ],
+            "1\n",
+        ),
+        (
+            &[
+                "tests/unused_directives",
+                "--unused-directives",
+                "--wrap",
+                "0",
+            ],
+            "directive @unused on OBJECT",
+        ),
+    ] {
+        assert!(
+            craftql(args).contains(expected),
+            "{:?} doesn't output {:?}",
+            args,
+            expected
+        );
+    }
+}
+
+#[test]
+fn check_possible_types() {
+    let craftql = |args: &[&str]| run_craftql(&[&["tests/possible_types"][..], args].concat());
+
+    // Comment is added to the union by an extension.
+    assert_eq!(
+        craftql(&["--possible-types", "SearchResult"]),
+        (
+            String::from(
+                "tests/possible_types/comment.graphql:7:8 union_extension SearchResult = Comment\n\
+                 tests/possible_types/search.graphql:9:1 union SearchResult = Post\n\
+                 tests/possible_types/search.graphql:9:1 union SearchResult = User\n"
+            ),
+            Some(0)
+        )
+    );
+    // The Entity interface isn't a possible type of Node.
+    assert_eq!(
+        craftql(&["--possible-types", "Node"]),
+        (
+            String::from(
+                "tests/possible_types/comment.graphql:5:8 object_extension Comment implements Node\n\
+                 tests/possible_types/search.graphql:15:1 object Post implements Node\n\
+                 tests/possible_types/search.graphql:11:1 object User implements Node\n"
+            ),
+            Some(0)
+        )
+    );
+    assert_eq!(
+        craftql(&["--possible-types", "User"]),
+        (String::new(), Some(0))
+    );
+    assert_eq!(craftql(&["--member-of", "Unknown"]).1, Some(4));
+
+    let (stdout, _) = craftql(&["--member-of", "Comment", "--format", "json"]);
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&stdout).unwrap(),
+        serde_json::json!([
+            {
+                "abstract_type": "Node",
+                "object": "Comment",
+                "path": "tests/possible_types/comment.graphql",
+                "position": [5, 8],
+                "graphql": "object_extension"
+            },
+            {
+                "abstract_type": "SearchResult",
+                "object": "Comment",
+                "path": "tests/possible_types/comment.graphql",
+                "position": [7, 8],
+                "graphql": "union_extension"
+            }
+        ])
+    );
+}
+
+#[test]
+fn check_multiple_schema_definitions() {
+    let craftql = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .arg("tests/multiple_schemas")
+            .arg("--count-only")
+            .args(args)
+            .output()
+            .unwrap();
+
+        (
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+            output.status.code(),
+        )
+    };
+
+    // Both definitions are reported with --strict only.
+    let (stderr, code) = craftql(&["--strict"]);
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains(
+        "Only one schema definition is allowed, found 2: \
+         tests/multiple_schemas/query.graphql:1:1, tests/multiple_schemas/user.graphql:5:1"
+    ));
+    assert_eq!(craftql(&[]), (String::new(), Some(0)));
+}
+
+#[test]
+fn check_strict_missing_definitions() {
+    let craftql = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args([
+                "--schema",
+                "type Query { user: User team: Team @auth }",
+                "--strict",
+                "--count-only",
+            ])
+            .args(args)
+            .output()
+            .unwrap();
+
+        (
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+            output.status.code(),
+        )
+    };
+
+    let (stderr, code) = craftql(&[]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("<inline>:1:1 Query references undefined @auth, Team, User\n"));
+    assert!(stderr.contains("3 missing definitions found"));
+
+    // The ignored ones don't fail the run.
+    let (stderr, code) = craftql(&["--ignore-missing", "Team,User"]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("1 missing definition found"));
+    assert_eq!(
+        craftql(&[
+            "--ignore-missing",
+            "Team,User",
+            "--known-directives",
+            "auth"
+        ]),
+        (String::new(), Some(0))
+    );
+}
+
+#[test]
+fn check_implementors() {
+    let craftql = |args: &[&str]| run_craftql(&[&["tests/implementors"][..], args].concat());
+
+    // User implements Node through an extension, Product only references it.
+    assert_eq!(
+        craftql(&["--implementors", "Node"]),
+        (
+            String::from(
+                "tests/implementors/node.graphql:5:1 interface Entity implements Node\n\
+                 tests/implementors/order.graphql:1:1 object Order implements Node\n\
+                 tests/implementors/extensions.graphql:1:8 object_extension User implements Node\n"
+            ),
+            Some(0)
+        )
+    );
+    assert_eq!(
+        craftql(&["--interfaces-of", "Order"]),
+        (
+            String::from(
+                "tests/implementors/order.graphql:6:8 object_extension Order implements Entity\n\
+                 tests/implementors/order.graphql:1:1 object Order implements Node\n"
+            ),
+            Some(0)
+        )
+    );
+    assert_eq!(
+        craftql(&["--interfaces-of", "Product"]),
+        (String::new(), Some(0))
+    );
+    assert_eq!(craftql(&["--implementors", "Unknown"]).1, Some(4));
+
+    let (stdout, _) = craftql(&["--implementors", "Entity", "--format", "json"]);
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&stdout).unwrap(),
+        serde_json::json!([{
+            "implementor": "Order",
+            "interface": "Entity",
+            "path": "tests/implementors/order.graphql",
+            "position": [6, 8],
+            "graphql": "object_extension"
+        }])
+    );
+}
+
+#[test]
+fn check_ignore_missing() {
+    let craftql = |schema: &str, args: &[&str]| {
+        run_craftql(
+            &[
+                &[
+                    "--schema",
+                    schema,
+                    "--missing-definitions",
+                    "--group-by",
+                    "missing",
+                ][..],
+                args,
+            ]
+            .concat(),
+        )
+    };
+
+    // Foo is defined elsewhere, nothing is missing anymore.
+    assert_eq!(
+        craftql("type Query { foo: Foo }", &["--ignore-missing", "Foo"]),
+        (String::new(), Some(0))
+    );
+
+    let schema = "type Query { foo: Foo bar: [Bar!] }";
+
+    assert_eq!(
+        craftql(schema, &["--count-only"]),
+        (String::from("2\n"), Some(2))
+    );
+    assert_eq!(
+        craftql(schema, &["--ignore-missing", "Foo", "--count-only"]),
+        (String::from("1\n"), Some(2))
+    );
+    assert_eq!(
+        craftql(schema, &["--ignore-missing", "Foo,Bar"]),
+        (String::new(), Some(0))
+    );
+}
+
+#[test]
+fn check_budget() {
+    let craftql = |args: &[&str]| run_craftql(&[&["tests/budget", "--budget"][..], args].concat());
+    let budgets = |stdout: &str| {
+        serde_json::from_str::<serde_json::Value>(stdout)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|violation| {
+                (
+                    violation["budget"].as_str().unwrap().to_owned(),
+                    violation["actual"].as_u64().unwrap(),
+                    violation["limit"].as_u64().unwrap(),
+                )
+            })
+            .collect::<Vec<(String, u64, u64)>>()
+    };
+
+    // Every budget of the configuration is exceeded.
+    let (stdout, code) = craftql(&["--format", "json"]);
+    assert_eq!(code, Some(2));
+    assert_eq!(
+        budgets(&stdout),
+        vec![
+            (String::from("max_types"), 5, 4),
+            (String::from("max_fields_per_type"), 4, 3),
+            (String::from("max_arguments_per_field"), 3, 2),
+            (String::from("max_enum_values"), 3, 2),
+            (String::from("max_union_members"), 2, 1),
+        ]
+    );
+
+    let (stdout, code) = craftql(&[]);
+    assert_eq!(code, Some(2));
+    assert!(stdout.contains(
+        "tests/budget/schema.graphql:6:1 [max_fields_per_type] User has 4 fields, over the budget of 3"
+    ));
+    assert!(stdout.contains("[max_types] The schema has 5 types, over the budget of 4"));
+
+    // The flags override the configuration.
+    assert_eq!(
+        craftql(&[
+            "--max-types",
+            "5",
+            "--max-fields-per-type",
+            "4",
+            "--max-arguments-per-field",
+            "3",
+            "--max-enum-values",
+            "3",
+            "--max-union-members",
+            "2",
+        ]),
+        (String::new(), Some(0))
+    );
+    assert_eq!(
+        craftql(&["--max-types", "5", "--count-only"]),
+        (String::from("4\n"), Some(2))
+    );
+    assert_eq!(
+        craftql(&["--no-config", "--max-enum-values", "2", "--count-only"]),
+        (String::from("1\n"), Some(2))
+    );
+
+    // A budget must be set.
+    assert_eq!(craftql(&["--no-config"]).1, Some(1));
+}
+
+#[test]
+fn check_layered() {
+    let craftql = |path: &str| {
+        run_craftql(&[path, "--layered"])
+            .0
+            .lines()
+            .filter(|line| line.contains("rank"))
+            .map(|line| line.trim().to_owned())
+            .collect::<Vec<String>>()
+    };
+
+    // Image and Role have no dependency, Query is the deepest and is laid
+    // out at the top.
+    assert_eq!(
+        craftql("tests/layered"),
+        vec![
+            "rankdir=BT",
+            "{ rank=same; 3; 4; }",
+            "{ rank=same; 2; }",
+            "{ rank=same; 1; }",
+            "{ rank=same; 0; }"
+        ]
+    );
+    // Post and User depend on each other.
+    assert!(craftql("tests/tree").is_empty());
+    assert_eq!(
+        run_craftql(&["tests/layered", "--layered", "--format", "json"]).1,
+        Some(1)
+    );
+}
+
+#[test]
+fn check_scalar_usage() {
+    let craftql = |args: &[&str]| {
+        run_craftql(&[&["tests/scalar_usage", "--scalar-usage"][..], args].concat())
+    };
+
+    // Used through an argument, a list type and an input object.
+    assert_eq!(
+        craftql(&["DateTime"]),
+        (
+            String::from(
+                "tests/scalar_usage/schema.graphql:1:1 DateTime: 4 reference(s) in 3 type(s)\n  \
+                 tests/scalar_usage/schema.graphql:11:1 Post.editedAt: [DateTime!]\n  \
+                 tests/scalar_usage/schema.graphql:11:1 Post.publishedAt: DateTime!\n  \
+                 tests/scalar_usage/schema.graphql:18:1 PostFilter.before: DateTime\n  \
+                 tests/scalar_usage/schema.graphql:7:1 Query.posts(since:): DateTime\n"
+            ),
+            Some(0)
+        )
+    );
+
+    let (stdout, code) = craftql(&["--format", "json"]);
+    let usages: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        usages
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|usage| (
+                usage["scalar"].as_str().unwrap(),
+                usage["count"].as_u64().unwrap()
+            ))
+            .collect::<Vec<(&str, u64)>>(),
+        [("DateTime", 4), ("JSON", 1), ("Upload", 0)]
+    );
+    assert_eq!(craftql(&["Unknown"]).1, Some(4));
+}
+
+#[test]
+fn check_input_usage() {
+    let craftql =
+        |args: &[&str]| run_craftql(&[&["tests/input_usage", "--input-usage"][..], args].concat());
+
+    // SearchInput nests OrderFilterInput.
+    assert_eq!(
+        craftql(&["OrderFilterInput"]),
+        (
+            String::from(
+                "tests/input_usage/schema.graphql:1:1 Query.orders(filter:): OrderFilterInput\n\
+                 tests/input_usage/schema.graphql:1:1 Query.search(input:): SearchInput! \
+                 (indirect via SearchInput.order)\n"
+            ),
+            Some(0)
+        )
+    );
+
+    let (stdout, code) = craftql(&["OrderFilterInput", "--format", "json"]);
+    let usages: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    assert_eq!(code, Some(0));
+    assert_eq!(usages[0]["indirect"], false);
+    assert_eq!(usages[1]["indirect"], true);
+    assert_eq!(usages[1]["via"], serde_json::json!(["SearchInput.order"]));
+    assert_eq!(craftql(&["Unknown"]).1, Some(4));
+}
+
+#[test]
+fn check_between() {
+    let craftql = |args: &[&str]| run_craftql(&[&["tests/layered"][..], args].concat());
+    let (stdout, code) = craftql(&["--between", "Query,Image", "--format", "json"]);
+    let graph: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    // Role is a dependency of User but not on the path to Image.
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        graph["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| node["id"].as_str().unwrap())
+            .collect::<Vec<&str>>(),
+        ["Query", "User", "Profile", "Image"]
+    );
+    assert_eq!(craftql(&["--between", "Query"]).1, Some(1));
+    assert_eq!(craftql(&["--between", "Query,Unknown"]).1, Some(4));
+}
+
+#[test]
+fn check_sort_by() {
+    let craftql = |args: &[&str]| {
+        run_craftql(&[&["tests/sort_by", "--orphans"][..], args].concat())
+            .0
+            .lines()
+            .filter(|line| line.starts_with("# "))
+            .map(|line| line.trim_start_matches("# ").to_owned())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(
+        craftql(&[]),
+        vec![
+            "tests/sort_by/b.graphql:1:1",
+            "tests/sort_by/b.graphql:5:1",
+            "tests/sort_by/a.graphql:1:1"
+        ]
+    );
+    assert_eq!(
+        craftql(&["--sort-by", "path"]),
+        vec![
+            "tests/sort_by/a.graphql:1:1",
+            "tests/sort_by/b.graphql:1:1",
+            "tests/sort_by/b.graphql:5:1"
+        ]
+    );
+}
+
+#[test]
+fn check_sort_by_lists() {
+    let craftql = |args: &[&str]| run_craftql(args).0;
+
+    // All the lists follow --sort-by, not only the orphans.
+    assert_eq!(
+        craftql(&[
+            "tests/sort_by",
+            "--nodes-matching",
+            ".",
+            "--sort-by",
+            "path"
+        ])
+        .lines()
+        .filter(|line| line.starts_with("# "))
+        .collect::<Vec<&str>>(),
+        [
+            "# tests/sort_by/a.graphql:1:1",
+            "# tests/sort_by/b.graphql:1:1",
+            "# tests/sort_by/b.graphql:5:1"
+        ]
+    );
+    assert_eq!(
+        craftql(&[
+            "tests/implementors",
+            "--implementors",
+            "Node",
+            "--sort-by",
+            "path"
+        ]),
+        "tests/implementors/extensions.graphql:1:8 object_extension User implements Node\n\
+         tests/implementors/node.graphql:5:1 interface Entity implements Node\n\
+         tests/implementors/order.graphql:1:1 object Order implements Node\n"
+    );
+    assert_eq!(
+        craftql(&[
+            "--schema",
+            "type B { a: X } enum A { V } input C { c: Y } extend enum A @d",
+            "--missing-definitions",
+            "--verbose",
+            "--sort-by",
+            "type"
+        ])
+        .lines()
+        .filter(|line| line.ends_with("not defined in:"))
+        .collect::<Vec<&str>>(),
+        [
+            "# @d is not defined in:",
+            "# Y is not defined in:",
+            "# X is not defined in:"
+        ]
+    );
+}
+
+#[test]
+fn check_render() {
+    let dir = tempfile::tempdir().unwrap();
+    let image = dir.path().join("graph.svg");
+    let craftql = |path_env: Option<&std::ffi::OsStr>| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"));
+
+        command
+            .arg("tests/fixtures")
+            .args(["--render", "svg", "--layout", "neato", "--output"])
+            .arg(&image);
+
+        if let Some(path_env) = path_env {
+            command.env("PATH", path_env);
+        }
+
+        let output = command.output().unwrap();
+
+        (
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+            output.status.code(),
+        )
+    };
+
+    // A missing dot binary comes with install hints.
+    let (stderr, code) = craftql(Some(std::ffi::OsStr::new("")));
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("apt install graphviz"));
+
+    // The stderr of a failing dot binary is surfaced.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = tempfile::tempdir().unwrap();
+        let fake_dot = bin.path().join("dot");
+        std::fs::write(&fake_dot, "#!/bin/sh\necho \"$1 $2\" >&2\nexit 3\n").unwrap();
+        std::fs::set_permissions(&fake_dot, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (stderr, code) = craftql(Some(bin.path().as_os_str()));
+        assert_eq!(code, Some(1));
+        assert!(stderr.contains("-Tsvg -Kneato"));
+    }
+
+    // Skipped when Graphviz isn't installed.
+    if std::process::Command::new("dot")
+        .arg("-V")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+
+    assert_eq!(craftql(None).1, Some(0));
+    assert!(std::fs::read_to_string(&image).unwrap().contains("<svg"));
+}
+
+#[test]
+fn check_no_default_render() {
+    let craftql = |path: &str, args: &[&str]| {
+        run_craftql(&[&[path, "--no-default-render"][..], args].concat())
+    };
+
+    assert_eq!(craftql("tests/fixtures", &[]), (String::new(), Some(0)));
+    assert_eq!(craftql("tests/format", &[]), (String::new(), Some(0)));
+    // The requested views are still printed.
+    assert_eq!(
+        craftql("tests/fixtures", &["--orphans", "--count-only"]),
+        (String::from("1\n"), Some(2))
+    );
+    assert_eq!(
+        craftql("tests/fixtures", &["--count-only"]),
+        (String::from("28\n"), Some(0))
+    );
+    // The errors still exit with their code.
+    assert_eq!(craftql("tests/unknown", &[]).1, Some(1));
+    // The strict checks still run, only the rendering is skipped.
+    assert_eq!(
+        craftql("tests/files", &["--strict"]),
+        (String::new(), Some(0))
+    );
+    assert_eq!(
+        craftql("tests/fixtures", &["--strict"]),
+        (String::new(), Some(1))
+    );
+    assert_eq!(
+        craftql("tests/multiple_schemas", &["--strict"]),
+        (String::new(), Some(1))
+    );
+}
+
+#[test]
+fn check_include_builtins() {
+    let render = |args: &[&str]| {
+        run_craftql(
+            &[
+                &[
+                    "--schema",
+                    "type Foo { id: ID! bar: Bar } type Bar { name: String }",
+                ][..],
+                args,
+            ]
+            .concat(),
+        )
+        .0
+    };
+    let dot = render(&["--include-builtins"]);
+
+    assert!(!render(&[]).contains("(Scalar)"));
+    assert!(dot.contains(r#"2 [ label = "ID (Scalar)" style = dashed ]"#));
+    assert!(dot.contains(r#"3 [ label = "String (Scalar)" style = dashed ]"#));
+    assert!(dot.contains("2 -> 0 [ ]"));
+    assert!(dot.contains("3 -> 1 [ ]"));
+}
+
+#[test]
+fn check_imports() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/imports", "--check-imports"])
+        .output()
+        .unwrap();
+
+    // The wildcard import of ../schema.graphql is valid.
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tests/imports/schema.graphql:2 ./types/posts.graphql is not an analyzed file\n\
+         tests/imports/schema.graphql:3 Account is not defined in types/user.graphql\n"
+    );
+}
+
+#[test]
+fn check_empty_types() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/empty_types", "--empty-types"])
+        .output()
+        .unwrap();
+
+    // Bar gets its fields from an extension.
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tests/empty_types/schema.graphql:7:1 object Foo has no fields\n\
+         tests/empty_types/schema.graphql:15:1 interface Node has no fields\n\
+         tests/empty_types/schema.graphql:17:1 input_object Filter has no fields\n"
+    );
+}
+
+#[test]
+fn check_dead_references() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/dead_references", "--dead-references"])
+        .output()
+        .unwrap();
+
+    // Post is reachable as an implementation of Node, Archive is an island.
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tests/dead_references/schema.graphql:24:1 Archive.entries references unreachable ArchiveEntry\n\
+         tests/dead_references/schema.graphql:29:1 ArchiveEntry.archive references unreachable Archive\n\
+         tests/dead_references/schema.graphql:34:8 Archive.previous references unreachable Archive\n"
+    );
+    assert_eq!(
+        run_craftql(&["./tests/fixtures", "--dead-references"]).1,
+        Some(0)
+    );
+}
+
+#[async_std::test]
+async fn check_files_summary() -> Result<()> {
+    let state = State::default();
+    let shared_data = state.shared;
+
+    collect_files(PathBuf::from("tests/files"), &shared_data).await?;
+    build_graph(&shared_data).await?;
+
+    let summaries = summarize_by_file(&*shared_data.graph.lock().await);
+
+    assert_eq!(
+        summaries,
+        vec![
+            FileSummary {
+                path: PathBuf::from("tests/files/post.graphql"),
+                definitions: vec![String::from("Post"), String::from("PostStatus")],
+                cross_file_dependencies: 1,
+            },
+            FileSummary {
+                path: PathBuf::from("tests/files/user.graphql"),
+                definitions: vec![String::from("Query"), String::from("User")],
+                cross_file_dependencies: 1,
+            },
+        ]
+    );
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/files", "--files"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tests/files/post.graphql (1 cross-file dependency): Post, PostStatus\n\
+         tests/files/user.graphql (1 cross-file dependency): Query, User\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn check_tree() {
+    let tree = |args: &[&str]| {
+        let (stdout, code) = run_craftql(&[&["tests/tree", "--tree"][..], args].concat());
+
+        assert_eq!(code, Some(0));
+
+        stdout
+    };
+
+    // Image is reached twice (diamond), Post and User depend on each other.
+    assert_eq!(
+        tree(&["Query"]),
+        "Query (Object)\n\
+         ├── Post (Object)\n\
+         │   ├── Image (Object)\n\
+         │   └── User (Object)\n\
+         │       ├── Post (Object) (cycle)\n\
+         │       └── Profile (Object)\n\
+         │           └── Image (Object) (*)\n\
+         └── User (Object) (*)\n"
+    );
+    assert_eq!(
+        tree(&["Image", "--invert"]),
+        "Image (Object)\n\
+         ├── Post (Object)\n\
+         │   ├── Query (Object)\n\
+         │   └── User (Object)\n\
+         │       ├── Post (Object) (cycle)\n\
+         │       └── Query (Object) (*)\n\
+         └── Profile (Object)\n    \
+         └── User (Object) (*)\n"
+    );
+    // The nodes cut by the depth are expanded elsewhere.
+    assert_eq!(
+        tree(&["Query", "--depth", "2"]),
+        "Query (Object)\n\
+         ├── Post (Object)\n\
+         │   ├── Image (Object)\n\
+         │   └── User (Object)\n\
+         └── User (Object)\n    \
+         ├── Post (Object) (*)\n    \
+         └── Profile (Object)\n"
+    );
+    // The built-in scalars are hidden unless included.
+    assert!(!tree(&["Image"]).contains("String"));
+    assert!(tree(&["Image", "--include-builtins"]).contains("└── String (Scalar)"));
+}
+
+#[test]
+fn check_unknown_directives() {
+    let craftql = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args(["tests/unknown_directives", "--missing-definitions"])
+            .args(args)
+            .output()
+            .unwrap();
+
+        (
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+            output.status.code(),
+        )
+    };
+    let unknown = "tests/unknown_directives/schema.graphql:3:1 Query applies unknown @cached\n\
+                   tests/unknown_directives/schema.graphql:8:1 User applies unknown @entity\n";
+    let role_only = "\n# tests/unknown_directives/schema.graphql (1 missing definition)\n\
+                     User: missing Role\n";
+
+    // Reported as missing definitions by default.
+    let (stdout, stderr, code) = craftql(&[]);
+
+    assert!(stdout.contains("Query: missing @cached\nUser: missing @entity, Role\n"));
+    assert!(stderr.is_empty());
+    assert_eq!(code, Some(2));
+
+    let (stdout, stderr, code) = craftql(&["--unknown-directives", "error"]);
+
+    assert!(stdout.is_empty());
+    assert_eq!(
+        stderr,
+        format!("{}Error: 2 unknown directives found\n", unknown)
+    );
+    assert_eq!(code, Some(1));
+
+    assert_eq!(
+        craftql(&["--unknown-directives", "warn"]),
+        (String::from(role_only), String::from(unknown), Some(2))
+    );
+    assert_eq!(
+        craftql(&["--unknown-directives", "ignore"]),
+        (String::from(role_only), String::new(), Some(2))
+    );
+
+    // The known directives aren't unknown.
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/unknown_directives", "--no-default-render"])
+        .args([
+            "--unknown-directives",
+            "error",
+            "--known-directives",
+            "cached,entity",
+        ])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[async_std::test]
+async fn check_dot_edges() -> Result<()> {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .arg("tests/files")
+        .output()
+        .unwrap();
+
+    // The edges are rendered without label, whatever their weight.
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\ndigraph {\n    \
+         0 [ label = \"Post (Object)\\l\\l[PostStatus, User]\" ]\n    \
+         1 [ label = \"PostStatus (Enum)\" ]\n    \
+         2 [ label = \"Query (Object)\\l\\l[ID, User]\" ]\n    \
+         3 [ label = \"User (Object)\\l\\l[ID, Post]\" ]\n    \
+         1 -> 0 [ ]\n    \
+         3 -> 0 [ ]\n    \
+         3 -> 2 [ ]\n    \
+         0 -> 3 [ ]\n\
+         }\n\n"
+    );
+
+    let schema = Schema::from_path("tests/files", &SchemaOptions::new()).await?;
+    let dot = render_dot(schema.graph(), &[]);
+
+    // The weights label the edges with their dependency otherwise.
+    assert!(dot.contains("    1 -> 0 [ label = \"PostStatus\" ]\n"));
+    assert!(dot.contains("    0 -> 3 [ label = \"Post\" ]\n"));
+
+    Ok(())
+}
+
+#[test]
+fn check_validate_sdl() {
+    let craftql =
+        |path: &str, args: &[&str]| run_craftql(&[&[path, "--validate-sdl"][..], args].concat());
+
+    let (stdout, code) = craftql("tests/validate", &[]);
+
+    assert_eq!(code, Some(2));
+    assert!(stdout
+        .starts_with("tests/validate/comment.graphql: schema parse error: Parse error at 3:1\n"));
+    assert!(
+        stdout.contains("\ntests/validate/post.graphql: schema parse error: Parse error at 3:10\n")
+    );
+    assert!(!stdout.contains("user.graphql"));
+    assert_eq!(
+        craftql("tests/validate", &["--count-only"]),
+        (String::from("2\n"), Some(2))
+    );
+    assert_eq!(craftql("tests/fixtures", &[]), (String::new(), Some(0)));
+}
+
+#[test]
+fn check_external_deps() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/files", "--external-deps", "Query"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(0));
+    // User is defined next to Query, Post and PostStatus in post.graphql.
+    assert!(stdout.starts_with("\n# tests/files/user.graphql:1:1\ntype Query {"));
+    assert!(!stdout.contains("type User"));
+    assert!(stdout.contains("\n# tests/files/post.graphql:1:1\ntype Post {"));
+    assert!(stdout.contains("\n# tests/files/post.graphql:6:1\nenum PostStatus {"));
+}
+
+#[test]
+fn check_ownership() {
+    let craftql = |args: &[&str]| {
+        let (stdout, code) = run_craftql(&[&["tests/ownership", "--ownership"][..], args].concat());
+
+        assert_eq!(code, Some(0));
+
+        stdout
+    };
+
+    assert_eq!(
+        craftql(&[]),
+        "accounts (2 types): Query, User\n  \
+         depends on content: Post\n  \
+         depended on by content: User\n\n\
+         content (2 types): Comment, Post\n  \
+         depends on accounts: User\n  \
+         depended on by accounts: Post\n"
+    );
+
+    // The nested directories are owners of their own with a greater depth.
+    let ownership =
+        serde_json::from_str::<serde_json::Value>(&craftql(&["--depth", "2", "--format", "json"]))
+            .unwrap();
+
+    assert_eq!(
+        ownership[2],
+        serde_json::json!({
+            "owner": "content/comments",
+            "types": ["Comment"],
+            "dependencies": {"accounts": ["User"], "content": ["Post"]},
+            "dependents": {"content": ["Comment"]}
+        })
+    );
+}
+
+#[test]
+fn check_scattered() {
+    let craftql =
+        |args: &[&str]| run_craftql(&[&["tests/extensions", "--scattered"][..], args].concat());
+
+    // User is defined in a.graphql and extended in b.graphql and c.graphql.
+    assert_eq!(
+        craftql(&[]),
+        (
+            String::from(
+                "User (3 files): tests/extensions/a.graphql, tests/extensions/b.graphql, tests/extensions/c.graphql\n"
+            ),
+            Some(2)
+        )
+    );
+    assert_eq!(
+        craftql(&["--scattered-threshold", "3"]),
+        (String::new(), Some(0))
+    );
+    // The path is no longer taken for the threshold.
+    assert_eq!(run_craftql(&["--scattered", "tests/extensions"]).1, Some(2));
+    // The threshold needs --scattered.
+    assert_eq!(
+        run_craftql(&["tests/extensions", "--scattered-threshold", "3"]).1,
+        Some(1)
+    );
+
+    let (stdout, code) = craftql(&["--format", "json"]);
+    let scattered = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+
+    assert_eq!(code, Some(2));
+    assert_eq!(
+        scattered,
+        serde_json::json!([{
+            "name": "User",
+            "paths": [
+                "tests/extensions/a.graphql",
+                "tests/extensions/b.graphql",
+                "tests/extensions/c.graphql"
+            ]
+        }])
+    );
+}
+
+#[async_std::test]
+async fn check_file_graph() -> Result<()> {
+    let schema = Schema::from_path("tests/fixtures", &SchemaOptions::new()).await?;
+    let snapshot = file_graph_snapshot(&file_graph(schema.graph()));
+    let edge = |source: &str, target: &str| {
+        snapshot
+            .edges
+            .iter()
+            .find(|edge| {
+                edge.source == format!("tests/fixtures/{}", source)
+                    && edge.target == format!("tests/fixtures/{}", target)
+            })
+            .map(|edge| edge.weight)
+    };
+
+    assert_eq!(snapshot.nodes.len(), 20);
+    // The edges between the entities of two files are aggregated.
+    assert_eq!(
+        edge(
+            "Types/Interfaces/Character.graphql",
+            "Types/Types/b.graphql"
+        ),
+        Some(4)
+    );
+    assert_eq!(edge("Types/Types/b.graphql", "Types/Types/a.gql"), Some(5));
+    assert_eq!(edge("Types/Types/a.gql", "schema.graphql"), Some(3));
+    assert_eq!(edge("Types/Types/a.gql", "Types/Types/b.graphql"), None);
+    // The edges within a file are dropped.
+    assert!(snapshot.edges.iter().all(|edge| edge.source != edge.target));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/files", "--file-graph"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\ndigraph {\n    \
+         0 [ label = \"tests/files/post.graphql\" ]\n    \
+         1 [ label = \"tests/files/user.graphql\" ]\n    \
+         0 -> 1 [ label = \"1\" ]\n    \
+         1 -> 0 [ label = \"1\" ]\n\
+         }\n\n"
+    );
+
+    let snapshot = file_graph_snapshot(&file_graph(
+        Schema::from_path("tests/files", &SchemaOptions::new())
+            .await?
+            .graph(),
+    ));
+
+    assert_eq!(
+        snapshot.edges,
+        [
+            FileEdge {
+                source: String::from("tests/files/post.graphql"),
+                target: String::from("tests/files/user.graphql"),
+                weight: 1,
+            },
+            FileEdge {
+                source: String::from("tests/files/user.graphql"),
+                target: String::from("tests/files/post.graphql"),
+                weight: 1,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn check_docs_coverage() {
+    let docs_coverage = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args(["tests/docs", "--docs-coverage"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output = docs_coverage(&[]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tests/docs/schema.graphql:16:1 Genre lacks a description\n\
+         Docs coverage: 2/3 (66.67%)\n"
+    );
+
+    // The fields are counted as nodes, the extension's one included.
+    let output = docs_coverage(&["--granularity", "field"]);
+
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("Docs coverage: 4/8 (50.00%)\n"));
+    assert_eq!(
+        docs_coverage(&["--fail-under", "66"]).status.code(),
+        Some(0)
+    );
+    assert_eq!(
+        docs_coverage(&["--fail-under", "70"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        run_craftql(&["tests/docs", "--fail-under", "70"]).1,
+        Some(1),
+        "--fail-under requires --docs-coverage"
+    );
+
+    // The descriptions are exported, the undescribed nodes omitting them.
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/docs", "--format", "json"])
+        .output()
+        .unwrap();
+    let json = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    let node = |id: &str| {
+        json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["id"] == id)
+            .unwrap()
+            .clone()
+    };
+
+    assert_eq!(node("Book")["description"], "A book of the library.");
+    assert_eq!(node("Query")["description"], "Root query.");
+    assert!(node("Genre").get("description").is_none());
+}
+
+#[test]
+fn check_federation() {
+    // Only Rating is genuinely missing from the reviews subgraph.
+    let (stdout, code) = run_craftql(&[
+        "tests/subgraphs/reviews",
+        "--missing-definitions",
+        "--federation",
+    ]);
+
+    assert_eq!(code, Some(2));
+    assert_eq!(
+        stdout,
+        "\n# tests/subgraphs/reviews/schema.graphql (1 missing definition)\n\
+         Review: missing Rating\n\
+         \n# tests/subgraphs/reviews/schema.graphql (5 definitions owned by another subgraph)\n\
+         Product: external Manufacturer, Product\n\
+         Review: external Product, User\n\
+         User: external User\n"
+    );
+
+    // The full definitions are displayed with --verbose.
+    let (stdout, code) = run_craftql(&[
+        "tests/subgraphs/reviews",
+        "--missing-definitions",
+        "--federation",
+        "--verbose",
+    ]);
+
+    assert_eq!(code, Some(2));
+    assert!(stdout.contains("# Rating is not defined in:"));
+    assert!(stdout.contains("# User is owned by another subgraph, referenced in:"));
+    assert!(
+        stdout.contains("# Manufacturer, Product are owned by another subgraph, referenced in:")
+    );
+    assert!(stdout.contains("# Product, User are owned by another subgraph, referenced in:"));
+    assert!(!stdout.contains("@key,"));
+
+    let count = |args: &[&str]| run_craftql(args).0.trim().to_owned();
+
+    assert_eq!(
+        count(&[
+            "tests/subgraphs/reviews",
+            "--missing-definitions",
+            "--count-only"
+        ]),
+        "3"
+    );
+    assert_eq!(
+        count(&[
+            "tests/subgraphs/reviews",
+            "--missing-definitions",
+            "--count-only",
+            "--federation"
+        ]),
+        "1"
+    );
+
+    // The Warehouse entity is resolved through the router.
+    assert_eq!(
+        run_craftql(&["tests/subgraphs/products", "--orphans"]).1,
+        Some(2)
+    );
+    assert_eq!(
+        run_craftql(&["tests/subgraphs/products", "--orphans", "--federation"]).1,
+        Some(0)
+    );
+
+    // The @requires field set is a dependency of the field.
+    let (stdout, _) = run_craftql(&[
+        "tests/subgraphs/products",
+        "--granularity",
+        "field",
+        "--federation",
+        "--incoming-dependencies",
+        "Product.shippingEstimate",
+    ]);
+
+    assert!(stdout.contains("type ProductDimension {"));
+}
+
+#[test]
+fn check_subgraphs() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "--subgraphs",
+            "accounts=tests/composition/accounts,billing=tests/composition/billing",
+        ])
+        .output()
+        .unwrap();
+
+    // Account is defined by another subgraph, Money by none.
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Address is defined without @key nor @shareable in \
+         [accounts] tests/composition/accounts/schema.graphql:16:1, \
+         [billing] tests/composition/billing/schema.graphql:17:1\n\
+         [billing] tests/composition/billing/schema.graphql:10:1 Invoice references Money \
+         which no subgraph defines\n"
+    );
+    assert_eq!(
+        run_craftql(&["--subgraphs", "accounts=tests/composition/accounts"]).1,
+        Some(0)
+    );
+    assert_eq!(
+        run_craftql(&["--subgraphs", "accounts=tests/woot"]).1,
+        Some(1)
+    );
+    assert_eq!(
+        run_craftql(&[
+            "tests/fixtures",
+            "--subgraphs",
+            "accounts=tests/composition/accounts"
+        ])
+        .1,
+        Some(1),
+        "--subgraphs conflicts with the path"
+    );
+}
+
+#[test]
+fn check_entity_json() {
+    let entity_json = |node: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args(["--schema", "type Foo { bar: Bar } type Bar { id: ID! }"])
+            .args(["--entity-json", node])
+            .output()
+            .unwrap()
+    };
+    let output = entity_json("Foo");
+    let entity = serde_json::from_slice::<Entity>(&output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(entity.id, "Foo");
+    assert_eq!(entity.raw, "type Foo {\n  bar: Bar\n}\n");
+    assert_eq!(entity.description, None);
+    assert_eq!(entity_json("Baz").status.code(), Some(4));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/docs", "--entity-json", "Book"])
+        .output()
+        .unwrap();
+    let entity = serde_json::from_slice::<Entity>(&output.stdout).unwrap();
+
+    assert_eq!(
+        entity.description.as_deref(),
+        Some("A book of the library.")
+    );
+}
+
+#[test]
+fn check_unused_fields() {
+    let craftql = |args: &[&str]| {
+        run_craftql(
+            &[
+                &[
+                    "tests/operations",
+                    "--operations",
+                    "tests/operations",
+                    "--unused-fields",
+                ][..],
+                args,
+            ]
+            .concat(),
+        )
+    };
+
+    // Post.id is only selected through the Node interface, Query._service is
+    // a root boilerplate field.
+    assert_eq!(
+        craftql(&[]),
+        (
+            String::from("\n# User\ntests/operations/schema.graphql:16:3 User.nickname\n"),
+            Some(0)
+        )
+    );
+    assert_eq!(craftql(&["--fail-if-any"]).1, Some(2));
+
+    let (stdout, _) = craftql(&["--format", "json"]);
+    let json = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+
+    assert_eq!(json[0]["type"], "User");
+    assert_eq!(json[0]["fields"][0]["name"], "User.nickname");
+}
+
+#[test]
+fn check_select() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "--schema",
+            "type User { profile: UserProfile } type UserProfile { id: ID } type Post { id: ID }",
+            "--select",
+            "User*",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout.contains("\"User (Object)"));
+    assert!(stdout.contains("\"UserProfile (Object)"));
+    assert!(!stdout.contains("Post"));
+}
+
+#[test]
+fn check_bridges() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/bridges", "--bridges"])
+        .output()
+        .unwrap();
+
+    // Only the edge between the two cycles of the barbell is a bridge.
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Warehouse -- Publisher\n"
+    );
+}
+
+#[test]
+fn check_operations() {
+    let craftql = |args: &[&str]| {
+        run_craftql(
+            &[
+                &["tests/operations", "--operations", "tests/operations"][..],
+                args,
+            ]
+            .concat(),
+        )
+    };
+    let entity_json =
+        |node: &str| serde_json::from_str::<Entity>(&craftql(&["--entity-json", node]).0).unwrap();
+
+    // The selections resolve to the types, the fragment spreads to the
+    // fragments.
+    let operation = entity_json("operation:GetUser");
+
+    assert_eq!(operation.graphql, GraphQL::Operation);
+    assert_eq!(
+        operation.dependencies,
+        [
+            "fragment:UserFields",
+            "ID",
+            "Image",
+            "Query",
+            "String",
+            "User"
+        ]
+        .map(Name::from)
+        .to_vec()
+    );
+
+    let fragment = entity_json("fragment:UserFields");
+
+    assert_eq!(fragment.graphql, GraphQL::Fragment);
+    assert_eq!(
+        fragment.dependencies,
+        ["ID", "String", "User"].map(Name::from).to_vec()
+    );
+
+    // The unresolvable selections are reported as missing definitions.
+    let (stdout, code) = craftql(&["--missing-definitions", "--verbose"]);
+
+    assert_eq!(code, Some(2));
+    assert!(stdout.contains("# Query.viewer is not defined in:"));
+    assert!(stdout.contains("query Unresolvable"));
+
+    // The operations using a field are its dependents with the field
+    // granularity.
+    let (stdout, _) = craftql(&[
+        "--granularity",
+        "field",
+        "--outgoing-dependencies",
+        "User.legacyId",
+    ]);
+
+    assert!(stdout.contains("query GetLegacyUser"));
+    assert!(!stdout.contains("query GetUser"));
+
+    // The executable documents fail to parse as a schema without
+    // --operations.
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .arg("tests/operations")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Parse error"));
+}
+
+#[test]
+fn check_dependencies() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "--schema",
+            "type Query { bar: Bar } type Bar { foo: Foo baz: Baz } type Foo { bar: Bar } type Baz { id: ID }",
+            "--dependencies",
+            "Bar",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (incoming, outgoing) = stdout.split_once("## Outgoing dependencies").unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(incoming.contains("## Incoming dependencies"));
+    assert!(incoming.contains("type Foo {"));
+    assert!(incoming.contains("type Baz {"));
+    assert!(outgoing.contains("type Query {"));
+    // Foo is reached through a cycle but only listed once.
+    assert_eq!(stdout.matches("type Foo {").count(), 1);
+}
+
+#[test]
+fn check_directive_arguments() {
+    let entity_json = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args(["tests/directive_arguments", "--entity-json", "Account"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output = entity_json(&[]);
+    let entity = serde_json::from_slice::<Entity>(&output.stdout).unwrap();
+    let via = |entity: &Entity| {
+        entity
+            .directive_dependencies
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        via(&entity),
+        [
+            "Role via @auth(requires:)",
+            "CachePolicy via @cache(policy:)",
+            "Pattern via @constraint(pattern:)"
+        ]
+    );
+    // The string given to @label(text:) resolves to String, also the type of
+    // the name field.
+    assert!(entity.dependencies.contains(&Name::from("String")));
+    // The arguments resolve before the directive definitions are filtered out.
+    assert_eq!(
+        via(&serde_json::from_slice::<Entity>(
+            &entity_json(&["--filter", "object", "--filter", "enum"]).stdout
+        )
+        .unwrap()),
+        via(&entity)
+    );
+    // String-valued arguments don't reference a type.
+    assert!(!entity
+        .dependencies
+        .iter()
+        .any(|dependency| dependency.contains('(')));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "tests/directive_arguments",
+            "--outgoing-dependencies",
+            "Role",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(String::from_utf8_lossy(&output.stdout).contains("type Account"));
+}
+
+#[test]
+fn check_field_granularity() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "tests/granularity",
+            "--granularity",
+            "field",
+            "--outgoing-dependencies",
+            "Address",
+        ])
+        .output()
+        .unwrap();
+
+    // Order.shippingAddress is the only field keeping Address alive.
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .ends_with("\n# tests/granularity/schema.graphql:9:3\nshippingAddress: Address\n\n"));
+    assert_eq!(
+        run_craftql(&["tests/granularity", "--granularity", "node"]).1,
+        Some(1)
+    );
+}
+
+#[test]
+fn check_field_orphans() {
+    let craftql = |args: &[&str]| {
+        run_craftql(
+            &[
+                &[
+                    "tests/granularity",
+                    "--granularity",
+                    "field",
+                    "--operations",
+                    "tests/granularity_operations",
+                    "--orphans",
+                ][..],
+                args,
+            ]
+            .concat(),
+        )
+    };
+
+    // The edge to their type doesn't keep the fields alive, the fields
+    // selected by no operation are orphans.
+    let (stdout, code) = craftql(&[]);
+
+    assert_eq!(code, Some(2));
+    assert_eq!(
+        stdout,
+        "\n# tests/granularity/schema.graphql:18:3\nstreet: String\n\n\
+         \n# tests/granularity/schema.graphql:13:3\nid: ID!\n\n\
+         \n# tests/granularity/schema.graphql:23:3\norders: [Order!]!\n\n\
+         \n# tests/granularity/schema.graphql:3:3\ncustomer(id: ID!): Customer\n\n"
+    );
+    assert_eq!(craftql(&["--count-only"]), (String::from("4\n"), Some(2)));
+
+    // Without the operations, nothing but their type can depend on the
+    // fields, which are never orphans.
+    assert_eq!(
+        run_craftql(&["tests/granularity", "--granularity", "field", "--orphans"]).1,
+        Some(0)
+    );
+}
+
+#[test]
+fn check_known_directives() {
+    assert_eq!(
+        run_craftql(&["./tests/federation", "--missing-definitions"]).1,
+        Some(2)
+    );
+    assert_eq!(
+        run_craftql(&[
+            "./tests/federation",
+            "--missing-definitions",
+            "--known-directives",
+            "link,key"
+        ])
+        .1,
+        Some(0)
+    );
+}
+
+#[test]
+fn check_empty_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let craftql = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .arg(dir.path())
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output = craftql(&["--orphans"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("No GraphQL file found in"));
+
+    // Files without any definition make an empty schema, not a parse error.
+    std::fs::write(dir.path().join("empty.graphql"), "").unwrap();
+    std::fs::write(dir.path().join("comments.gql"), "# TODO\n\n  # Later.\n").unwrap();
+
+    for args in [&[][..], &["--orphans"]] {
+        let output = craftql(args);
+
+        assert_eq!(output.status.code(), Some(3));
+        assert!(output.stdout.is_empty());
+        assert!(
+            String::from_utf8_lossy(&output.stderr).starts_with("No GraphQL definition found in")
+        );
+    }
+
+    assert_eq!(run_craftql(&["--schema", " \n"]).1, Some(3));
+
+    // The blank files are skipped along the other ones.
+    std::fs::write(dir.path().join("schema.graphql"), "type Foo { id: ID! }").unwrap();
+
+    assert_eq!(craftql(&["--orphans"]).status.code(), Some(2));
+}
+
+#[test]
+fn check_exit_codes() {
+    // Success.
+    assert_eq!(
+        run_craftql(&["./tests/fixtures", "--node", "Character"]).1,
+        Some(0)
+    );
+    assert_eq!(run_craftql(&["--help"]).1, Some(0));
+    assert_eq!(run_craftql(&["--version"]).1, Some(0));
+    // Generic error.
+    assert_eq!(run_craftql(&["./tests/woot"]).1, Some(1));
+    // The usage errors don't collide with the validation findings.
+    assert_eq!(run_craftql(&["./tests/fixtures", "--woot"]).1, Some(1));
+    assert_eq!(run_craftql(&["./tests/fixtures", "--wrap"]).1, Some(1));
+    // Validation findings.
+    assert_eq!(
+        run_craftql(&["./tests/fixtures", "--missing-definitions"]).1,
+        Some(2)
+    );
+    assert_eq!(run_craftql(&["./tests/fixtures", "--orphans"]).1, Some(2));
+    // Empty input.
+    assert_eq!(run_craftql(&["./tests/fixtures/c.txt"]).1, Some(3));
+    // Lookup not found.
+    assert_eq!(
+        run_craftql(&["./tests/fixtures", "--node", "Woot"]).1,
+        Some(4)
+    );
+    assert_eq!(
+        run_craftql(&["./tests/fixtures", "--tree", "Woot"]).1,
+        Some(4)
+    );
+}
+
+#[test]
+fn check_inline_schema() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["--schema", "type Foo { id: ID! }", "--node", "Foo"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\n# <inline>:1:1\ntype Foo {\n  id: ID!\n}\n\n"
+    );
+}
+
+#[test]
+fn check_paths_from() -> Result<()> {
+    let count = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args(args)
+            .arg("--count-only")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+
+    // Four enums and two interfaces, relative to the manifest.
+    assert_eq!(count(&["--paths-from", "tests/manifest.txt"])?, "6");
+    // Combined with the path, the files are read once.
+    assert_eq!(
+        count(&[
+            "tests/fixtures/Types/Enums",
+            "--paths-from",
+            "tests/manifest.txt"
+        ])?,
+        "6"
+    );
+
+    // The excluded paths of the configuration found from the manifest apply.
+    let dir = tempfile::tempdir()?;
+
+    std::fs::write(
+        dir.path().join(".craftql.toml"),
+        r#"exclude_paths = ["b.gql"]"#,
+    )?;
+    std::fs::create_dir(dir.path().join("schemas"))?;
+    std::fs::write(dir.path().join("schemas/a.gql"), "enum Foo { A }")?;
+    std::fs::write(dir.path().join("schemas/b.gql"), "type Bar { id: ID! }")?;
+    std::fs::write(dir.path().join("c.gql"), "type Baz { id: ID! }")?;
+    std::fs::write(
+        dir.path().join("manifest.txt"),
+        "# Schemas.\n  schemas  \n\n# c.gql\n",
+    )?;
+
+    let manifest = dir.path().join("manifest.txt");
+
+    assert_eq!(count(&["--paths-from", manifest.to_str().unwrap()])?, "1");
+
+    // An empty manifest is an empty input, a missing one an error.
+    std::fs::write(&manifest, "# Nothing yet.\n")?;
+
+    assert_eq!(
+        run_craftql(&["--paths-from", manifest.to_str().unwrap()]).1,
+        Some(3)
+    );
+    assert_eq!(run_craftql(&["--paths-from", "tests/woot.txt"]).1, Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn check_config_file() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    std::fs::write(
+        dir.path().join(".craftql.toml"),
+        r#"
+extensions = ["gql", "sdl"]
+exclude_paths = ["skipped"]
+filter = ["object"]
+format = "json"
+"#,
+    )?;
+    std::fs::create_dir(dir.path().join("skipped"))?;
+    std::fs::write(dir.path().join("a.gql"), "enum Foo { A }")?;
+    std::fs::write(dir.path().join("b.gql"), "type Bar { id: ID! }")?;
+    std::fs::write(dir.path().join("c.sdl"), "type Baz { id: ID! }")?;
+    std::fs::write(dir.path().join("skipped/d.gql"), "type Qux { id: ID! }")?;
+
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .arg(dir.path())
+            .args(args)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+
+    // The configuration is applied.
+    let stdout = run(&[])?;
+    let json = serde_json::from_str::<serde_json::Value>(&stdout)?;
+    let mut names = json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["name"].as_str().unwrap())
+        .collect::<Vec<&str>>();
+    names.sort_unstable();
+    assert_eq!(names, vec!["Bar", "Baz"]);
+
+    // CLI flags take precedence.
+    let stdout = run(&["--filter", "enum", "--format", "dot"])?;
+    assert!(stdout.contains("digraph"));
+    assert!(stdout.contains("Foo (Enum)"));
+    assert!(!stdout.contains("Bar (Object)"));
+
+    // The configuration is ignored.
+    let stdout = run(&["--no-config"])?;
+    assert!(stdout.contains("Foo (Enum)"));
+    assert!(stdout.contains("Bar (Object)"));
+    assert!(!stdout.contains("Baz (Object)"));
+    assert!(stdout.contains("Qux (Object)"));
+
+    Ok(())
+}
+
+#[test]
+fn check_config_default_path() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    std::fs::write(
+        dir.path().join("craftql.toml"),
+        r#"
+path = "schemas"
+filter = ["enum"]
+"#,
+    )?;
+    std::fs::create_dir(dir.path().join("schemas"))?;
+    std::fs::write(dir.path().join("schemas/a.gql"), "enum Foo { A }")?;
+    std::fs::write(dir.path().join("schemas/b.gql"), "type Bar { id: ID! }")?;
+
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .current_dir(dir.path())
+            .args(args)
+            .output()
+    };
+
+    // The path and the filter come from the configuration of the working
+    // directory.
+    let output = run(&[])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout.contains("Foo (Enum)"));
+    assert!(!stdout.contains("Bar (Object)"));
+
+    // A configuration can be provided explicitly.
+    std::fs::write(dir.path().join("objects.toml"), r#"filter = ["object"]"#)?;
+    let output = run(&["schemas", "--config", "objects.toml"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Foo (Enum)"));
+    assert!(stdout.contains("Bar (Object)"));
+
+    // Without any configuration, a path is required.
+    assert_eq!(run(&["--no-config"])?.status.code(), Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn check_ignore_case() {
+    let run = |node: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args([
+                "--schema",
+                "type User { id: ID! } enum Role { A } enum ROLE { B }",
+                "--ignore-case",
+                "--node",
+                node,
+            ])
+            .output()
+            .unwrap()
+    };
+
+    let output = run("user");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("type User {"));
+
+    let output = run("role");
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Node role is ambiguous, candidates are: ROLE, Role\n"
+    );
+}
+
+#[test]
+fn check_schema_from_sources() -> Result<()> {
+    let schema = Schema::from_sources(vec![
+        (
+            PathBuf::from("some_path/House.gql"),
+            String::from("type House { owner: Owner! color: Color }"),
+        ),
+        (
+            PathBuf::from("some_path/Owner.gql"),
+            String::from("type Owner { name: String! }"),
+        ),
+        (
+            PathBuf::from("some_path/Peer.gql"),
+            String::from("type Peer { id: ID! }"),
+        ),
+    ])?;
+
+    assert_eq!(schema.graph().node_count(), 3);
+    assert_eq!(schema.graph().edge_count(), 1);
+    assert_eq!(schema.dependencies().len(), 3);
+    assert_eq!(
+        schema.missing_definitions().values().collect::<Vec<_>>(),
+        vec![&vec![Name::from("Color")]]
+    );
+
+    let mut orphans = schema
+        .orphans()
+        .into_iter()
+        .map(|entity| entity.name)
+        .collect::<Vec<String>>();
+    orphans.sort();
+    assert_eq!(orphans, vec!["House", "Peer"]);
+
+    let incoming = schema.neighbors("House", Direction::Incoming);
+    assert_eq!(incoming.len(), 1);
+    assert_eq!(incoming.first().unwrap().name, "Owner");
+
+    assert_eq!(
+        schema.node("Owner").unwrap().path,
+        PathBuf::from("some_path/Owner.gql")
+    );
+    assert!(schema.node("Woot").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn check_unknown_enum_values() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/enum_values", "--lint"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines().collect::<Vec<&str>>();
+    lines.sort_unstable();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        lines,
+        [
+            "tests/enum_values/schema.graphql:17:1 [unknown_enum_value] PostFilter.roles defaults to OWNER which is not a value of Role",
+            "tests/enum_values/schema.graphql:17:1 [unknown_enum_value] PostFilter.sort defaults to NEWEST which is not a value of Sort",
+            "tests/enum_values/schema.graphql:22:1 [unknown_enum_value] Query.posts(filter:) defaults to NEWEST which is not a value of Sort",
+            "tests/enum_values/schema.graphql:27:1 [unknown_enum_value] @auth(requires:) on Post references SUPERUSER which is not a value of Role",
+        ]
+    );
+}
+
+#[test]
+fn check_incompatible_interface_fields() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/interfaces", "--lint"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines().collect::<Vec<&str>>();
+    lines.sort_unstable();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        lines,
+        [
+            "tests/interfaces/schema.graphql:21:1 [incompatible_interface_field] Post.url(width:) is a required argument not defined by Resource",
+            "tests/interfaces/schema.graphql:27:1 [incompatible_interface_field] Image.id is of type ID which is not compatible with ID! of Node",
+            "tests/interfaces/schema.graphql:27:1 [incompatible_interface_field] Image.id is of type ID which is not compatible with ID! of Resource",
+            "tests/interfaces/schema.graphql:27:1 [incompatible_interface_field] Image.owner is of type [Team] which is not compatible with Owner of Resource",
+            "tests/interfaces/schema.graphql:27:1 [incompatible_interface_field] Image.url is of type String which is not compatible with String! of Resource",
+            "tests/interfaces/schema.graphql:27:1 [incompatible_interface_field] Image.url(absolute:) is of type String instead of Boolean as in Resource",
+            "tests/interfaces/schema.graphql:36:1 [incompatible_interface_field] Video.owner is missing, required by Resource",
+        ]
+    );
+}
+
+#[test]
+fn check_introspection() {
+    let nodes = |path: &str| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .args([path, "--format", "json"])
+            .output()
+            .unwrap();
+        let mut graph = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+
+        // Only the locations differ.
+        for node in graph["nodes"].as_array_mut().unwrap() {
+            let node = node.as_object_mut().unwrap();
+
+            node.remove("path");
+            node.remove("position");
+        }
+
+        graph
+    };
+
+    assert_eq!(
+        nodes("tests/introspection/schema.json"),
+        nodes("tests/introspection/schema.graphql")
+    );
+
+    // The .json files are only walked with --introspection.
+    let count = |args: &[&str]| {
+        run_craftql(&[&["tests/introspection", "--count-only"][..], args].concat()).0
+    };
+
+    assert_eq!(count(&[]), "9\n");
+    assert_eq!(count(&["--introspection"]), "18\n");
+}
+
+/// Serve a single HTTP response on a local port, returning the URL and the
+/// received request.
+#[cfg(feature = "remote")]
+fn serve_once(status: &str, body: String) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let status = status.to_owned();
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = vec![];
+        let mut buffer = [0; 4096];
+
+        // Read the headers, then the body up to its announced length.
+        loop {
+            let read = stream.read(&mut buffer).unwrap();
+
+            request.extend_from_slice(&buffer[..read]);
+
+            let text = String::from_utf8_lossy(&request).to_lowercase();
+
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map_or(0, |length| length.trim().parse::<usize>().unwrap());
+
+                if request.len() >= end + 4 + length || read == 0 {
+                    break;
+                }
+            }
+        }
+
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        String::from_utf8_lossy(&request).into_owned()
+    });
+
+    (url, handle)
+}
+
+#[cfg(feature = "remote")]
+#[test]
+fn check_remote_schema() {
+    let (url, handle) = serve_once(
+        "200 OK",
+        std::fs::read_to_string("tests/introspection/schema.json").unwrap(),
+    );
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["--url", &url, "--header", "Authorization: Bearer token"])
+        .args(["--count-only"])
+        .output()
+        .unwrap();
+    let request = handle.join().unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "9\n");
+    assert!(request.starts_with("POST /graphql"));
+    assert!(request
+        .to_lowercase()
+        .contains("authorization: bearer token"));
+    assert!(request.contains("IntrospectionQuery"));
+
+    // The GraphQL errors are reported.
+    let (url, handle) = serve_once(
+        "401 Unauthorized",
+        String::from(r#"{"errors":[{"message":"Missing token"}]}"#),
+    );
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["--url", &url, "--orphans"])
+        .output()
+        .unwrap();
+
+    handle.join().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Missing token"));
+}
+
+#[test]
+fn check_gvjson() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/fixtures", "--format", "gvjson"])
+        .output()
+        .unwrap();
+    let gvjson = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    let objects = gvjson["objects"].as_array().unwrap();
+    let edges = gvjson["edges"].as_array().unwrap();
+
+    assert_eq!(objects.len(), 28);
+    assert!(!edges.is_empty());
+    // The edges reference the nodes by their index.
+    assert!(edges.iter().all(|edge| {
+        objects[edge["tail"].as_u64().unwrap() as usize]["_gvid"] == edge["tail"]
+            && objects[edge["head"].as_u64().unwrap() as usize]["_gvid"] == edge["head"]
+    }));
+}
+
+#[test]
+fn check_unused_directives() {
+    let craftql = |args: &[&str]| run_craftql(&[&["tests/unused_directives"][..], args].concat());
+
+    let (stdout, code) = craftql(&["--unused-directives"]);
+
+    assert_eq!(code, Some(2));
+    assert!(stdout.contains("directive @unused on OBJECT"));
+    assert!(!stdout.contains("@auth"));
+    assert!(!stdout.contains("Orphan"));
+
+    // The orphans leave the unused directives out, unless asked for.
+    let (stdout, _) = craftql(&["--orphans"]);
+
+    assert!(stdout.contains("type Orphan"));
+    assert!(!stdout.contains("@unused"));
+
+    let (stdout, _) = craftql(&["--orphans", "--only", "directive"]);
+
+    assert!(stdout.contains("directive @unused on OBJECT"));
+    assert!(!stdout.contains("Orphan"));
+
+    assert_eq!(
+        craftql(&["--unused-directives", "--count-only"]),
+        (String::from("1\n"), Some(2))
+    );
+}
+
+#[test]
+fn check_depth_metrics() {
+    let craftql =
+        |args: &[&str]| run_craftql(&[&["tests/depth", "--depth-metrics"][..], args].concat());
+
+    assert_eq!(
+        craftql(&[]),
+        (String::from("Query: 3\nMutation: 1\n"), Some(0))
+    );
+    assert_eq!(
+        craftql(&["--root", "Post", "--root", "Reaction"]),
+        (String::from("Post: 2\nReaction: 1\n"), Some(0))
+    );
+    assert_eq!(craftql(&["--root", "Unknown"]).1, Some(4));
+}
+
+#[test]
+fn check_missing_definitions_by_file() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/fixtures", "--missing-definitions"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\n# tests/fixtures/Types/Interfaces/Character.graphql (1 missing definition)\n\
+         Character: missing Color\n\
+         \n# tests/fixtures/Types/Unions/SearchResultExtension.graphql (2 missing definitions)\n\
+         SearchResult: missing Ewok, Gungan\n"
+    );
+}
+
+#[test]
+fn check_html() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args(["tests/fixtures", "--format", "html"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("<html"));
+    assert!(stdout.contains("28 nodes"));
+}
+
+#[test]
+fn check_diff() {
+    let craftql = |args: &[&str]| {
+        run_craftql(&[&["diff", "tests/diff/old", "tests/diff/new"][..], args].concat())
+    };
+    let (stdout, code) = craftql(&[]);
+
+    assert_eq!(code, Some(2));
+    assert_eq!(
+        stdout,
+        "[safe] Type Post was added\n\
+         [breaking] Field Query.legacy was removed\n\
+         [safe] Field Query.post was added\n\
+         [dangerous] Argument Query.users(after:) was added\n\
+         [dangerous] Enum value Role.EDITOR was added\n\
+         [safe] Field User.name changed type from String to String!\n"
+    );
+    assert_eq!(craftql(&["--allow-breaking"]).1, Some(0));
+}
+
+#[test]
+fn check_snapshot() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let schema = dir.path().join("schema");
+
+    std::fs::create_dir(&schema)?;
+    std::fs::copy(
+        "tests/diff/old/schema.graphql",
+        schema.join("schema.graphql"),
+    )?;
+
+    let craftql = |action: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .current_dir(dir.path())
+            .args(["snapshot", action, "schema.lock", "schema"])
+            .output()
+    };
+
+    assert_eq!(craftql("write")?.status.code(), Some(0));
+
+    // The snapshot is deterministic.
+    let lock = std::fs::read_to_string(dir.path().join("schema.lock"))?;
+    assert_eq!(craftql("write")?.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("schema.lock"))?,
+        lock
+    );
+    assert_eq!(craftql("check")?.status.code(), Some(0));
+
+    // Mutate the copy of the fixture.
+    std::fs::write(
+        schema.join("schema.graphql"),
+        "type Query { users: [User] } type User { id: ID! } type Post { id: ID! }",
+    )?;
+
+    let output = craftql("check")?;
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Added object Post\n\
+         Changed object Query\n\
+         Dependencies of Query changed: -Int, -String\n\
+         Removed enum Role\n\
+         Changed object User\n\
+         Dependencies of User changed: -Role, -String\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("drifted from schema.lock"));
+
+    Ok(())
+}
+
+#[test]
+fn check_fmt() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let schema = dir.path().join("schema.graphql");
+
+    std::fs::copy("tests/format/messy.graphql", &schema)?;
+
+    let craftql = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+            .current_dir(dir.path())
+            .arg("fmt")
+            .args(args)
+            .arg("schema.graphql")
+            .output()
+    };
+
+    // The comment within the query type would be dropped.
+    let output = craftql(&[])?;
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("The comment on line 4 would be dropped, pass --drop-comments to drop it"));
+    assert_eq!(
+        std::fs::read_to_string(&schema)?,
+        std::fs::read_to_string("tests/format/messy.graphql")?
+    );
+
+    let output = craftql(&["--check", "--drop-comments"])?;
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "schema.graphql\n");
+    // The check doesn't write the files.
+    assert_eq!(
+        std::fs::read_to_string(&schema)?,
+        std::fs::read_to_string("tests/format/messy.graphql")?
+    );
+
+    assert_eq!(craftql(&["--drop-comments"])?.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read_to_string(&schema)?,
+        std::fs::read_to_string("tests/format/formatted.graphql")?
+    );
+
+    // Formatting twice is a no-op.
+    let output = craftql(&["--check"])?;
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn check_missing_definitions_json() -> Result<()> {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "--schema",
+            "type User { team: Team role: Role id: ID! } type Query { user: User post: Post } enum Role { A }",
+            "--missing-definitions",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    let json = serde_json::from_slice::<serde_json::Value>(&output.stdout)?;
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        json,
+        serde_json::json!([
+            { "node": "Query", "path": "<inline>", "missing": ["Post"] },
+            { "node": "User", "path": "<inline>", "missing": ["Team"] },
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn check_merge_extensions() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "--schema",
+            "type User { id: ID! } extend type User { name: String } extend type User { name: String age: Int } enum Role { A } extend enum Role { B }",
+            "--merge-extensions",
+            "--nodes",
+            "User",
+            "--nodes",
+            "Role",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout.contains("type User {\n  id: ID!\n  name: String\n  age: Int\n}"));
+    assert!(stdout.contains("enum Role {\n  A\n  B\n}"));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "<inline>:1:64 User.name is already defined, skipped\n"
+    );
+
+    // The extension nodes are gone from the rendered graph.
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_craftql"))
+        .args([
+            "--schema",
+            "enum Role { A } extend enum Role { B }",
+            "--merge-extensions",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Enum extension"));
+}
+
+#[async_std::test]
+async fn check_extension_ids() -> Result<()> {
+    let schema = Schema::from_path("tests/extensions", &SchemaOptions::new()).await?;
+    let extensions = schema
+        .entities()
+        .filter(|entity| entity.extends.as_deref() == Some("User"))
+        .map(|entity| {
+            (
+                entity.id.as_str(),
+                entity.path.to_string_lossy().into_owned(),
+            )
+        })
+        .collect::<Vec<(&str, String)>>();
+
+    // Both extensions are nodes with their own id, numbered in the paths order.
+    assert_eq!(
+        extensions,
+        [
+            ("User__1", String::from("tests/extensions/b.graphql")),
+            ("User__2", String::from("tests/extensions/c.graphql")),
+        ]
+    );
+    assert_eq!(
+        schema.node("User__1").unwrap().raw,
+        "extend type User {\n  name: String\n}\n"
+    );
+    assert_eq!(
+        schema.node("User__2").unwrap().raw,
+        "extend type User {\n  email: String\n}\n"
+    );
+    assert_eq!(schema.node("User").unwrap().extends, None);
+
+    Ok(())
+}
+
+#[test]
+fn check_with_extensions() {
+    let craftql =
+        |args: &[&str]| run_craftql(&[&["tests/extensions", "--node"][..], args].concat()).0;
+
+    assert_eq!(
+        craftql(&["User", "--with-extensions"]),
+        "\n# tests/extensions/a.graphql:1:1\ntype User {\n  id: ID!\n}\n\n\
+         \n# tests/extensions/b.graphql:1:8\nextend type User {\n  name: String\n}\n\n\
+         \n# tests/extensions/c.graphql:1:8\nextend type User {\n  email: String\n}\n\n"
+    );
+    // Only the definition by default.
+    assert_eq!(
+        craftql(&["User"]),
+        "\n# tests/extensions/a.graphql:1:1\ntype User {\n  id: ID!\n}\n\n"
+    );
+    // Only the SDL, without the path header.
+    assert_eq!(craftql(&["User", "--raw"]), "type User {\n  id: ID!\n}\n");
+    assert_eq!(
+        craftql(&["User", "--raw", "--with-extensions"]),
+        "type User {\n  id: ID!\n}\n\
+         extend type User {\n  name: String\n}\n\
+         extend type User {\n  email: String\n}\n"
+    );
+    // --raw only applies to --node and --nodes.
+    assert_eq!(run_craftql(&["tests/extensions", "--raw"]).1, Some(1));
+}
+
+#[test]
+fn check_path_styles() -> Result<()> {
+    let craftql = |args: &[&str]| {
+        run_craftql(&[&["tests/extensions", "--node", "User"][..], args].concat()).0
+    };
+    let absolute = std::fs::canonicalize("tests/extensions/a.graphql")?;
+
+    assert!(craftql(&[]).starts_with("\n# tests/extensions/a.graphql:1:1\n"));
+    assert!(craftql(&["--absolute-paths"])
+        .starts_with(&format!("\n# {}:1:1\n", absolute.to_string_lossy())));
+    assert!(craftql(&["--relative-to", "tests/extensions"]).starts_with("\n# a.graphql:1:1\n"));
+    assert!(
+        craftql(&["--relative-to", "src"]).starts_with("\n# ../tests/extensions/a.graphql:1:1\n")
+    );
+
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_gzip() -> Result<()> {
+    let schema = Schema::from_path("tests/gzip", &SchemaOptions::new()).await?;
+    let entities = schema
+        .entities()
+        .map(|entity| {
+            (
+                entity.name.as_str(),
+                entity.path.to_string_lossy().into_owned(),
+            )
+        })
+        .collect::<Vec<(&str, String)>>();
+
+    // The compressed file is decompressed, its path is kept as is.
+    assert_eq!(
+        entities,
+        [
+            ("Post", String::from("tests/gzip/post.graphql")),
+            ("User", String::from("tests/gzip/user.gql.gz")),
+        ]
+    );
+    assert!(schema.missing_definitions().is_empty());
+
+    Ok(())
+}